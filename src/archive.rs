@@ -0,0 +1,105 @@
+use anyhow::{Context, Result};
+use base64::Engine;
+use chrono::NaiveDate;
+use fantoccini::Client;
+use serde_json::{json, Value};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use zip::write::FileOptions;
+
+use crate::verify::Verification;
+use crate::PpData;
+
+// --month 2024-07 -> 2024-07-01
+pub fn parse_month(s: &str) -> Result<NaiveDate, String> {
+    NaiveDate::parse_from_str(&format!("{}-01", s.trim()), "%Y-%m-%d")
+        .map_err(|_| format!("Invalid month, expected YYYY-MM: {}", s))
+}
+
+// 현재 페이지를 PDF 로 (WebDriver Print Page)
+pub async fn print_pdf(client: &Client, driver_url: &str) -> Result<Vec<u8>> {
+    let session_id = client
+        .session_id()
+        .await
+        .context("Failed to get WebDriver session id")?
+        .context("WebDriver session id is missing")?;
+
+    let response: Value = reqwest::Client::builder()
+        .timeout(Duration::from_secs(60))
+        .build()?
+        .post(format!("{}/session/{}/print", driver_url, session_id))
+        .json(&json!({ "background": true, "orientation": "portrait" }))
+        .send()
+        .await
+        .context("Failed to print the page")?
+        .error_for_status()?
+        .json()
+        .await
+        .context("Failed to read the printed page")?;
+
+    let encoded = response["value"]
+        .as_str()
+        .context("Print response has no PDF data")?;
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .context("Failed to decode the printed PDF")
+}
+
+// <root>/<고객 번호>/<YYYY-MM>/ 에 PDF, JSON, CSV, 검증 결과 저장
+pub fn write_bundle(
+    root: &Path,
+    record: &PpData,
+    pdf: &[u8],
+    verification: &Verification,
+) -> Result<PathBuf> {
+    let dir = root
+        .join(&verification.customer_number)
+        .join(&verification.month);
+    fs::create_dir_all(&dir).context(format!("Failed to create directory: {}", dir.display()))?;
+
+    fs::write(dir.join("bill.pdf"), pdf)?;
+    fs::write(
+        dir.join("record.json"),
+        serde_json::to_vec_pretty(record).context("Failed to serialize record")?,
+    )?;
+    fs::write(
+        dir.join("record.csv"),
+        format!(
+            "customer_number,claim_date,usage,paid\n{},{},{},{}\n",
+            verification.customer_number, record.claim_date, record.usage, record.paid
+        ),
+    )?;
+    fs::write(
+        dir.join("verification.json"),
+        serde_json::to_vec_pretty(verification).context("Failed to serialize verification")?,
+    )?;
+    Ok(dir)
+}
+
+// 폴더를 같은 이름의 zip 으로 묶고 폴더는 삭제
+pub fn zip_dir(dir: &Path) -> Result<PathBuf> {
+    let path = dir.with_extension("zip");
+    let mut zip = zip::ZipWriter::new(
+        File::create(&path).context(format!("Failed to create {}", path.display()))?,
+    );
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
+
+    let mut entries: Vec<PathBuf> = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<std::io::Result<_>>()?;
+    entries.sort();
+    for entry in entries {
+        let name = entry
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        zip.start_file(name, options)?;
+        zip.write_all(&fs::read(&entry)?)?;
+    }
+    zip.finish().context("Failed to write zip")?;
+
+    fs::remove_dir_all(dir).context(format!("Failed to remove {}", dir.display()))?;
+    Ok(path)
+}
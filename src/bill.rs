@@ -0,0 +1,161 @@
+// 청구 월 교차 검증 (목록 값과 청구서 상세, 저장된 값 비교), 청구서 상세의 요금 항목
+// browser 없이 동작 (--no-default-features 로 wasm 에서도 사용)
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+use crate::{parse_paid, parse_use_kwh, PpData};
+
+// 사용량 비교 허용 오차 (kWh 소수점 표시 차이)
+const USAGE_TOLERANCE: f64 = 0.001;
+
+// 청구서 상세의 사용량, 청구 금액
+#[derive(Debug, Clone, Serialize)]
+pub struct BillDetail {
+    pub usage: f64,
+    pub paid: Decimal,
+    // 요금 항목
+    #[serde(skip_serializing_if = "Breakdown::is_empty")]
+    pub breakdown: Breakdown,
+}
+
+impl BillDetail {
+    // 청구서 상세 화면의 사용량, 청구 금액 문자열
+    pub fn parse(usage: &str, amount: &str) -> Result<Self> {
+        Ok(Self {
+            usage: parse_use_kwh(usage.trim())?,
+            paid: parse_paid(amount.trim())?,
+            breakdown: Breakdown::default(),
+        })
+    }
+}
+
+// 청구서 상세의 요금 항목 (--detailed, 화면에 없는 항목은 None)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Breakdown {
+    // 기본요금
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_charge: Option<Decimal>,
+    // 전력량요금
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub energy_charge: Option<Decimal>,
+    // 기후환경요금
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub climate_charge: Option<Decimal>,
+    // 연료비조정액 (인하되면 음수)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fuel_adjustment: Option<Decimal>,
+    // 부가가치세
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vat: Option<Decimal>,
+    // 전력산업기반기금
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub power_fund: Option<Decimal>,
+    // TV수신료
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tv_fee: Option<Decimal>,
+    // 그 외 금액 항목 (화면의 항목 이름 -> 금액)
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub other: BTreeMap<String, Decimal>,
+}
+
+type BreakdownField = fn(&mut Breakdown) -> &mut Option<Decimal>;
+
+// 항목 이름에 포함된 문자열 (공백 제거 후 비교)
+const BREAKDOWN_LABELS: &[(&str, BreakdownField)] = &[
+    ("기본요금", |items| &mut items.base_charge),
+    ("전력량요금", |items| &mut items.energy_charge),
+    ("기후환경요금", |items| &mut items.climate_charge),
+    ("연료비조정", |items| &mut items.fuel_adjustment),
+    ("부가가치세", |items| &mut items.vat),
+    ("부가세", |items| &mut items.vat),
+    ("전력산업기반기금", |items| &mut items.power_fund),
+    ("TV수신료", |items| &mut items.tv_fee),
+];
+
+impl Breakdown {
+    // 청구서 상세 화면의 (항목 이름, 값) 목록, '원' 금액이 아닌 항목 (사용량, 날짜 등) 은 무시
+    pub fn from_fields(fields: &[(String, String)]) -> Self {
+        let mut items = Self::default();
+        for (label, value) in fields {
+            let label: String = label.split_whitespace().collect();
+            if label.is_empty() || !value.contains('원') {
+                continue;
+            }
+            let Ok(amount) = parse_paid(value.trim()) else {
+                continue;
+            };
+            match BREAKDOWN_LABELS
+                .iter()
+                .find(|(name, _)| label.contains(name))
+            {
+                Some((_, field)) => {
+                    field(&mut items).get_or_insert(amount);
+                }
+                None => {
+                    items.other.entry(label).or_insert(amount);
+                }
+            }
+        }
+        items
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+// 청구 월 1건의 교차 검증 결과 (목록 값 기준으로 청구서 상세, 저장된 값과 비교)
+#[derive(Debug, Serialize)]
+pub struct Verification {
+    pub customer_number: String,
+    pub month: String,
+    // match, mismatch, unverified (비교할 값 없음)
+    pub status: &'static str,
+    pub differences: Vec<Value>,
+}
+
+impl Verification {
+    pub fn new(
+        customer_number: &str,
+        record: &PpData,
+        detail: Option<&BillDetail>,
+        stored: Option<&PpData>,
+    ) -> Self {
+        let mut differences = Vec::new();
+        let mut compare = |source: &str, usage: f64, paid: Decimal| {
+            if (usage - record.usage).abs() > USAGE_TOLERANCE {
+                differences.push(json!({
+                    "field": "usage", "source": source, "expected": usage, "scraped": record.usage,
+                }));
+            }
+            if paid != record.paid {
+                differences.push(json!({
+                    "field": "paid", "source": source, "expected": paid, "scraped": record.paid,
+                }));
+            }
+        };
+        if let Some(detail) = detail {
+            compare("bill_detail", detail.usage, detail.paid);
+        }
+        if let Some(stored) = stored {
+            compare("store", stored.usage, stored.paid);
+        }
+
+        let status = if !differences.is_empty() {
+            "mismatch"
+        } else if detail.is_none() && stored.is_none() {
+            "unverified"
+        } else {
+            "match"
+        };
+        Self {
+            customer_number: customer_number.to_string(),
+            month: record.claim_date.format("%Y-%m").to_string(),
+            status,
+            differences,
+        }
+    }
+}
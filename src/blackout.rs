@@ -0,0 +1,141 @@
+use chrono::{DateTime, FixedOffset, NaiveTime, TimeDelta, Utc};
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+// portal 점검 시간 등 조회를 시작하지 않는 KST 시간대 (23:30-01:00 처럼 자정을 넘을 수 있음)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Window {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl Window {
+    // time 이 시간대 안이면 끝날 때까지 남은 시간
+    fn remaining(&self, time: NaiveTime) -> Option<Duration> {
+        let inside = if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        };
+        if !inside {
+            return None;
+        }
+        // 자정을 넘으면 음수가 되므로 하루를 더함
+        let seconds = (self.end - time).num_seconds().rem_euclid(86_400);
+        Some(Duration::from_secs(seconds as u64))
+    }
+}
+
+impl FromStr for Window {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Invalid blackout window, expected HH:MM-HH:MM (KST): {}", s);
+        let (start, end) = s.trim().split_once('-').ok_or_else(invalid)?;
+        let time =
+            |value: &str| NaiveTime::parse_from_str(value.trim(), "%H:%M").map_err(|_| invalid());
+        let window = Window {
+            start: time(start)?,
+            end: time(end)?,
+        };
+        if window.start == window.end {
+            return Err(format!("Blackout window {} is empty", s));
+        }
+        Ok(window)
+    }
+}
+
+impl fmt::Display for Window {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}-{}",
+            self.start.format("%H:%M"),
+            self.end.format("%H:%M")
+        )
+    }
+}
+
+// pipeline 설정 파일의 blackout 문자열
+impl<'de> Deserialize<'de> for Window {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+// at 이 시간대 안이면 모든 시간대를 벗어날 때까지 대기 시간 (이어지거나 겹치는 시간대는 합쳐서)
+pub fn remaining(windows: &[Window], at: DateTime<Utc>) -> Option<Duration> {
+    let mut waited = Duration::ZERO;
+    // 시간대마다 한 번씩만 연장되므로 windows 수만큼 반복하면 충분
+    for _ in 0..=windows.len() {
+        let time = (at + TimeDelta::seconds(waited.as_secs() as i64))
+            .with_timezone(&kst())
+            .time();
+        match windows.iter().find_map(|window| window.remaining(time)) {
+            Some(remaining) if !remaining.is_zero() => waited += remaining,
+            _ => break,
+        }
+    }
+    (!waited.is_zero()).then_some(waited)
+}
+
+fn kst() -> FixedOffset {
+    FixedOffset::east_opt(9 * 3600).expect("valid KST offset")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn window(s: &str) -> Window {
+        s.parse().unwrap()
+    }
+
+    fn time(hour: u32, minute: u32) -> NaiveTime {
+        NaiveTime::from_hms_opt(hour, minute, 0).unwrap()
+    }
+
+    // KST 시각의 UTC
+    fn kst_at(hour: u32, minute: u32) -> DateTime<Utc> {
+        NaiveDate::from_ymd_opt(2024, 3, 15)
+            .unwrap()
+            .and_time(time(hour, minute))
+            .and_local_timezone(kst())
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn minutes(minutes: u64) -> Duration {
+        Duration::from_secs(minutes * 60)
+    }
+
+    #[test]
+    fn window_across_midnight() {
+        let window = window("23:30-01:00");
+        assert_eq!(window.remaining(time(23, 45)), Some(minutes(75)));
+        assert_eq!(window.remaining(time(0, 30)), Some(minutes(30)));
+        assert_eq!(window.remaining(time(1, 0)), None);
+        assert_eq!(window.remaining(time(23, 29)), None);
+    }
+
+    #[test]
+    fn joins_back_to_back_windows() {
+        let windows = [window("02:00-03:00"), window("03:00-03:30")];
+        assert_eq!(remaining(&windows, kst_at(2, 30)), Some(minutes(60)));
+        assert_eq!(remaining(&windows, kst_at(3, 10)), Some(minutes(20)));
+    }
+
+    #[test]
+    fn outside_windows() {
+        let windows = [window("23:30-01:00"), window("02:00-03:00")];
+        assert_eq!(remaining(&windows, kst_at(1, 0)), None);
+        assert_eq!(remaining(&windows, kst_at(1, 59)), None);
+        assert_eq!(remaining(&windows, kst_at(23, 29)), None);
+        assert_eq!(remaining(&windows, kst_at(23, 45)), Some(minutes(75)));
+    }
+}
@@ -0,0 +1,95 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use tokio::runtime::Runtime;
+
+use crate::client::LoginFlow;
+use crate::config::Credentials;
+use crate::customer;
+use crate::latency::PageLatency;
+use crate::selectors::{SelectorOverride, WindowSize};
+use crate::{DailyUsage, HourlyUsage, PpData};
+
+// async 가 아닌 program 에서 쓰는 KepcoClient (내부 current-thread runtime 에서 실행)
+// 호출 순서는 KepcoClient 와 같음: login -> select_customer -> fetch_*
+pub struct KepcoClient {
+    inner: crate::KepcoClient,
+    // WebDriver session 의 background task 도 이 runtime 에서 실행 (호출 중에만 진행)
+    runtime: Runtime,
+}
+
+impl KepcoClient {
+    // 실행 중인 WebDriver server (ChromeDriver 등) 에 headless session 연결
+    pub fn connect(webdriver_url: &str) -> Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("Failed to start the tokio runtime")?;
+        let inner = runtime.block_on(crate::KepcoClient::connect(webdriver_url))?;
+        Ok(Self { inner, runtime })
+    }
+
+    // 로그인 전후 단계 (pre-login script, 계정 확인, CAPTCHA 저장 위치)
+    pub fn with_login_flow(mut self, flow: LoginFlow) -> Self {
+        self.inner = self.inner.with_login_flow(flow);
+        self
+    }
+
+    // session 의 window 크기, layout 별 selector 대신 쓸 selector
+    pub fn with_selectors(mut self, window: WindowSize, overrides: Vec<SelectorOverride>) -> Self {
+        self.inner = self.inner.with_selectors(window, overrides);
+        self
+    }
+
+    // 페이지별 timeout, 로딩 완료 조건
+    pub fn with_latency(mut self, latency: PageLatency) -> Self {
+        self.inner = self.inner.with_latency(latency);
+        self
+    }
+
+    // portal 주소 (기본 TARGET_URL)
+    pub fn with_target_url(mut self, target_url: impl Into<String>) -> Self {
+        self.inner = self.inner.with_target_url(target_url);
+        self
+    }
+
+    // 로그인 후 판별한 layout 이름
+    pub fn layout(&self) -> Option<&'static str> {
+        self.inner.layout()
+    }
+
+    pub fn login(&mut self, credentials: &Credentials) -> Result<()> {
+        self.runtime.block_on(self.inner.login(credentials))
+    }
+
+    pub fn select_customer(&mut self, customer_number: &str) -> Result<()> {
+        self.runtime
+            .block_on(self.inner.select_customer(customer_number))
+    }
+
+    pub fn customer_info(&mut self, customer_number: &str) -> Result<customer::CustomerInfo> {
+        self.runtime
+            .block_on(self.inner.customer_info(customer_number))
+    }
+
+    pub fn fetch_monthly_claims(&mut self) -> Result<Vec<PpData>> {
+        self.runtime.block_on(self.inner.fetch_monthly_claims())
+    }
+
+    pub fn fetch_year_claims(&mut self, year: i32) -> Result<Vec<PpData>> {
+        self.runtime.block_on(self.inner.fetch_year_claims(year))
+    }
+
+    pub fn fetch_daily_usage(&mut self, year: i32, month: u32) -> Result<Vec<DailyUsage>> {
+        self.runtime
+            .block_on(self.inner.fetch_daily_usage(year, month))
+    }
+
+    pub fn fetch_hourly_usage(&mut self, date: NaiveDate) -> Result<Vec<HourlyUsage>> {
+        self.runtime.block_on(self.inner.fetch_hourly_usage(date))
+    }
+
+    // WebDriver session 종료 (driver process 는 호출하는 쪽에서 종료)
+    pub fn close(self) -> Result<()> {
+        self.runtime.block_on(self.inner.close())
+    }
+}
@@ -0,0 +1,124 @@
+use chrono::{Datelike, Days, Months, NaiveDate, Utc};
+use std::fmt::Write as _;
+
+use crate::watch;
+
+// calendar 일정 설정
+#[derive(Debug, Clone)]
+pub struct CalendarOptions {
+    // 검침일 (1~31, 짧은 달은 말일)
+    pub reading_day: u32,
+    // 검침일 이후 청구 요금이 조회되기까지 걸리는 일수
+    pub arrival_lag_days: u32,
+    // 납기일 (예상 청구일 이후 첫 해당 일)
+    pub due_day: Option<u32>,
+    // 포함할 월 수
+    pub months: u32,
+}
+
+// 하루 종일 일정 1개
+struct Event {
+    uid: String,
+    date: NaiveDate,
+    summary: String,
+    // 며칠 전 알림
+    alarm_days: Option<u32>,
+}
+
+// 고객 번호별 (첫 월) 검침일, 예상 청구일, 납기일 일정 -> iCalendar 문자열
+pub fn render(customers: &[(String, NaiveDate)], options: &CalendarOptions) -> String {
+    let mut events = Vec::new();
+    for (customer_number, first) in customers {
+        for i in 0..options.months {
+            let Some(month) = first.checked_add_months(Months::new(i)) else {
+                continue;
+            };
+            let Some(reading) = watch::day_of_month(month, options.reading_day) else {
+                continue;
+            };
+            let key = format!("{}-{}", customer_number, month.format("%Y%m"));
+            events.push(Event {
+                uid: format!("reading-{}", key),
+                date: reading,
+                summary: format!("KEPCO meter reading ({})", customer_number),
+                alarm_days: None,
+            });
+
+            let Some(arrival) =
+                reading.checked_add_days(Days::new(options.arrival_lag_days.into()))
+            else {
+                continue;
+            };
+            events.push(Event {
+                uid: format!("bill-{}", key),
+                date: arrival,
+                summary: format!("KEPCO bill expected ({})", customer_number),
+                alarm_days: None,
+            });
+
+            if let Some(due) = options.due_day.and_then(|day| due_date(arrival, day)) {
+                events.push(Event {
+                    uid: format!("due-{}", key),
+                    date: due,
+                    summary: format!("KEPCO payment due ({})", customer_number),
+                    alarm_days: Some(1),
+                });
+            }
+        }
+    }
+
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ");
+    let mut ics = String::new();
+    for line in [
+        "BEGIN:VCALENDAR",
+        "VERSION:2.0",
+        "PRODID:-//rip_hyphen//KEPCO billing//EN",
+        "CALSCALE:GREGORIAN",
+        "X-WR-CALNAME:KEPCO billing",
+    ] {
+        let _ = write!(ics, "{}\r\n", line);
+    }
+    for event in events {
+        let _ = write!(ics, "BEGIN:VEVENT\r\n");
+        let _ = write!(ics, "UID:{}@rip-hyphen\r\n", event.uid);
+        let _ = write!(ics, "DTSTAMP:{}\r\n", stamp);
+        let _ = write!(
+            ics,
+            "DTSTART;VALUE=DATE:{}\r\n",
+            event.date.format("%Y%m%d")
+        );
+        if let Some(end) = event.date.succ_opt() {
+            let _ = write!(ics, "DTEND;VALUE=DATE:{}\r\n", end.format("%Y%m%d"));
+        }
+        let _ = write!(ics, "SUMMARY:{}\r\n", escape(&event.summary));
+        let _ = write!(ics, "TRANSP:TRANSPARENT\r\n");
+        if let Some(days) = event.alarm_days {
+            let _ = write!(ics, "BEGIN:VALARM\r\n");
+            let _ = write!(ics, "ACTION:DISPLAY\r\n");
+            let _ = write!(ics, "DESCRIPTION:{}\r\n", escape(&event.summary));
+            let _ = write!(ics, "TRIGGER:-P{}D\r\n", days);
+            let _ = write!(ics, "END:VALARM\r\n");
+        }
+        let _ = write!(ics, "END:VEVENT\r\n");
+    }
+    let _ = write!(ics, "END:VCALENDAR\r\n");
+    ics
+}
+
+// 예상 청구일 이후 첫 납기일 (짧은 달은 말일)
+fn due_date(arrival: NaiveDate, due_day: u32) -> Option<NaiveDate> {
+    let this_month = watch::day_of_month(arrival, due_day)?;
+    if this_month >= arrival {
+        return Some(this_month);
+    }
+    let next = arrival.with_day(1)?.checked_add_months(Months::new(1))?;
+    watch::day_of_month(next, due_day)
+}
+
+// TEXT 값 escape (RFC 5545)
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,236 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use crate::error::{ErrorCode, Failure};
+use crate::output::{self, OutputFormat};
+
+// 확인 항목 결과
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Pass,
+    // 통과했지만 fallback selector, 건너뛴 row 등 확인 필요
+    Warn,
+    Fail,
+    // 비교할 값이 없어 확인하지 않음
+    Skip,
+}
+
+impl CheckStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CheckStatus::Pass => "pass",
+            CheckStatus::Warn => "warn",
+            CheckStatus::Fail => "fail",
+            CheckStatus::Skip => "skip",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Check {
+    pub name: String,
+    // 실패하면 confidence 와 무관하게 hold
+    pub critical: bool,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+// 전체 실행 허용 여부
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Go,
+    Hold,
+}
+
+// canary 실행 결과 (data dir 의 canary.json, --require-canary 가 확인)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Report {
+    pub run_id: String,
+    pub finished_at: DateTime<Utc>,
+    pub customer_number: String,
+    pub layout: Option<String>,
+    // 확인한 항목 중 통과 비율 (warn 은 절반)
+    pub confidence: f64,
+    pub verdict: Verdict,
+    pub checks: Vec<Check>,
+}
+
+impl Report {
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .context(format!("Failed to create directory: {}", dir.display()))?;
+        }
+        fs::write(path, serde_json::to_vec_pretty(self)?)
+            .context(format!("Failed to write {}", path.display()))
+    }
+}
+
+// 순서대로 쌓는 확인 항목
+#[derive(Debug, Default)]
+pub struct Checks {
+    checks: Vec<Check>,
+    layout: Option<String>,
+}
+
+impl Checks {
+    pub fn push(
+        &mut self,
+        name: impl Into<String>,
+        critical: bool,
+        status: CheckStatus,
+        detail: impl Into<String>,
+    ) {
+        self.checks.push(Check {
+            name: name.into(),
+            critical,
+            status,
+            detail: detail.into(),
+        });
+    }
+
+    // 단계 결과를 critical 항목으로 기록, 성공하면 값 반환 (실패하면 다음 단계를 진행하지 않음)
+    pub fn step<T>(&mut self, name: &str, result: Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => {
+                self.push(name, true, CheckStatus::Pass, "");
+                Some(value)
+            }
+            Err(e) => {
+                self.push(name, true, CheckStatus::Fail, format!("{:#}", e));
+                None
+            }
+        }
+    }
+
+    pub fn layout(&mut self, name: &str, fallback: bool) {
+        self.layout = Some(name.to_string());
+        if fallback {
+            self.push(
+                "layout",
+                false,
+                CheckStatus::Warn,
+                format!("Unknown layout, fell back to '{}'", name),
+            );
+        } else {
+            self.push("layout", false, CheckStatus::Pass, name);
+        }
+    }
+
+    // chain 의 첫 selector 가 맞으면 pass, 뒤의 fallback 이 맞으면 warn
+    pub fn selector(&mut self, key: &str, matched: Option<usize>, chain: usize, critical: bool) {
+        let name = format!("selector:{}", key);
+        match matched {
+            Some(0) => self.push(name, critical, CheckStatus::Pass, ""),
+            Some(index) => self.push(
+                name,
+                critical,
+                CheckStatus::Warn,
+                format!("Matched fallback {} of {}", index + 1, chain),
+            ),
+            None => self.push(name, critical, CheckStatus::Fail, "No selector matched"),
+        }
+    }
+
+    pub fn finish(self, run_id: &str, customer_number: &str, min_confidence: f64) -> Report {
+        let counted: Vec<&Check> = self
+            .checks
+            .iter()
+            .filter(|check| check.status != CheckStatus::Skip)
+            .collect();
+        let score: f64 = counted
+            .iter()
+            .map(|check| match check.status {
+                CheckStatus::Pass => 1.0,
+                CheckStatus::Warn => 0.5,
+                _ => 0.0,
+            })
+            .sum();
+        let confidence = if counted.is_empty() {
+            0.0
+        } else {
+            score / counted.len() as f64
+        };
+        let critical_failed = self
+            .checks
+            .iter()
+            .any(|check| check.critical && check.status == CheckStatus::Fail);
+
+        Report {
+            run_id: run_id.to_string(),
+            finished_at: Utc::now(),
+            customer_number: customer_number.to_string(),
+            layout: self.layout,
+            confidence,
+            verdict: if critical_failed || confidence < min_confidence {
+                Verdict::Hold
+            } else {
+                Verdict::Go
+            },
+            checks: self.checks,
+        }
+    }
+}
+
+// 마지막 canary 가 max_age_hours 안에 go 로 끝났는지 (--require-canary)
+pub fn require(path: &Path, max_age_hours: u64) -> Result<()> {
+    let blocked = |message: String| Err(Failure::new(ErrorCode::Canary, message).into());
+    let report: Report = match fs::read(path) {
+        Ok(bytes) => serde_json::from_slice(&bytes)
+            .context(format!("Invalid canary report {}", path.display()))?,
+        Err(_) => return blocked("No canary report found, run the canary subcommand first".into()),
+    };
+    if report.verdict != Verdict::Go {
+        return blocked(format!(
+            "The last canary ({}) held the run with confidence {:.2}",
+            report.run_id, report.confidence
+        ));
+    }
+    let age = (Utc::now() - report.finished_at).num_hours();
+    if age > max_age_hours as i64 {
+        return blocked(format!(
+            "The last canary ({}) is {}h old, older than {}h",
+            report.run_id, age, max_age_hours
+        ));
+    }
+    Ok(())
+}
+
+pub fn render(report: &Report, format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(report).context("Failed to serialize canary to JSON")
+        }
+        OutputFormat::Csv => Err(output::csv_unsupported("canary")),
+        OutputFormat::Table => {
+            let mut table = String::new();
+            for check in &report.checks {
+                let _ = writeln!(
+                    table,
+                    "{:<24}  {:<4}  {}{}",
+                    check.name,
+                    check.status.as_str(),
+                    if check.critical { "" } else { "(optional) " },
+                    check.detail
+                );
+            }
+            let _ = writeln!(
+                table,
+                "{}: confidence {:.2}, {}",
+                report.customer_number,
+                report.confidence,
+                match report.verdict {
+                    Verdict::Go => "go",
+                    Verdict::Hold => "hold",
+                }
+            );
+            Ok(table)
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use anyhow::Result;
+use fantoccini::{elements::Element, Client, Locator};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tokio::time::{timeout, Duration};
+
+use crate::error::{ErrorCode, Failure};
+use crate::warnings::{self, WarningKind};
+
+// 로그인 화면의 CAPTCHA (이미지 문자, reCAPTCHA, hCaptcha)
+const SELECTORS: &[&str] = &[
+    "img[src*='captcha' i]",
+    "img[id*='captcha' i]",
+    "input[name*='captcha' i]",
+    "iframe[src*='recaptcha']",
+    "iframe[src*='hcaptcha']",
+    ".g-recaptcha",
+];
+// 화면 캡처 대기 시간
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(10);
+
+// 보이는 CAPTCHA 요소가 있으면 challenge 를 dir 에 저장하고 CaptchaRequired
+pub async fn check(client: &Client, dir: &Path) -> Result<()> {
+    let Some((selector, element)) = detect(client).await else {
+        return Ok(());
+    };
+    let artifacts = capture(client, &element, dir).await;
+    Err(Failure::new(
+        ErrorCode::CaptchaRequired,
+        "The portal asked for a CAPTCHA at login, rerun with --interactive to solve it once \
+         (or session export --headed, then session import)",
+    )
+    .with_selector(selector)
+    .with_artifacts(artifacts)
+    .into())
+}
+
+async fn detect(client: &Client) -> Option<(&'static str, Element)> {
+    for selector in SELECTORS {
+        let Ok(elements) = client.find_all(Locator::Css(selector)).await else {
+            continue;
+        };
+        for element in elements {
+            if element.is_displayed().await.unwrap_or(false) {
+                return Some((selector, element));
+            }
+        }
+    }
+    None
+}
+
+// challenge 요소와 페이지 전체 캡처
+async fn capture(client: &Client, element: &Element, dir: &Path) -> Vec<PathBuf> {
+    if let Err(e) = fs::create_dir_all(dir) {
+        warnings::emit(
+            WarningKind::Artifact,
+            format!("Failed to create directory {}: {}", dir.display(), e),
+        );
+        return Vec::new();
+    }
+    let name = chrono::Local::now().format("%Y%m%d-%H%M%S-captcha");
+    let mut artifacts = Vec::new();
+
+    let shots = [
+        (
+            dir.join(format!("{}.png", name)),
+            timeout(CAPTURE_TIMEOUT, element.screenshot()).await,
+        ),
+        (
+            dir.join(format!("{}-page.png", name)),
+            timeout(CAPTURE_TIMEOUT, client.screenshot()).await,
+        ),
+    ];
+    for (path, shot) in shots {
+        let Ok(Ok(png)) = shot else {
+            continue;
+        };
+        match fs::write(&path, png) {
+            Ok(()) => artifacts.push(path),
+            Err(e) => warnings::emit(
+                WarningKind::Artifact,
+                format!("Failed to write {}: {}", path.display(), e),
+            ),
+        }
+    }
+    artifacts
+}
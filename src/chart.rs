@@ -0,0 +1,179 @@
+use anyhow::{Context, Result};
+use chrono::Datelike;
+use std::fmt::Write as _;
+
+use crate::PpData;
+
+// SVG chart 크기 (px)
+const CHART_WIDTH: f64 = 720.0;
+const CHART_HEIGHT: f64 = 200.0;
+const CHART_PADDING: f64 = 28.0;
+
+// 알림용 PNG 크기 (px), 색 (RGB)
+const PNG_WIDTH: u32 = 520;
+const PNG_HEIGHT: u32 = 160;
+const PNG_PADDING: u32 = 12;
+const BACKGROUND: [u8; 3] = [255, 255, 255];
+const BAR: [u8; 3] = [0x4e, 0x79, 0xa7];
+const LATEST_BAR: [u8; 3] = [0xe1, 0x57, 0x59];
+const BASELINE: [u8; 3] = [0xcc, 0xcc, 0xcc];
+
+// sparkline 단계
+const SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+// 최근 months 개월 (오래된 달부터)
+pub fn recent(rows: &[PpData], months: usize) -> Vec<&PpData> {
+    let mut rows: Vec<&PpData> = rows.iter().collect();
+    rows.sort_by_key(|entry| entry.claim_date);
+    rows.split_off(rows.len().saturating_sub(months))
+}
+
+// 막대 chart (사용량)
+pub fn bar_svg(rows: &[&PpData], value: impl Fn(&PpData) -> f64) -> String {
+    let values: Vec<f64> = rows.iter().map(|entry| value(entry)).collect();
+    let (scale, baseline) = scale(&values);
+    let slot = (CHART_WIDTH - CHART_PADDING * 2.0) / values.len() as f64;
+
+    let mut svg = svg_open();
+    for (index, (entry, value)) in rows.iter().zip(&values).enumerate() {
+        let x = CHART_PADDING + slot * index as f64;
+        let y = baseline - value.max(0.0) * scale;
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" class=\"bar\"><title>{} {}</title></rect>",
+            x + slot * 0.15,
+            y,
+            slot * 0.7,
+            baseline - y,
+            entry.claim_date.format("%Y-%m"),
+            value
+        );
+    }
+    svg.push_str(&month_axis(rows, slot));
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// 꺾은선 chart (청구금액)
+pub fn line_svg(rows: &[&PpData], value: impl Fn(&PpData) -> f64) -> String {
+    let values: Vec<f64> = rows.iter().map(|entry| value(entry)).collect();
+    let (scale, baseline) = scale(&values);
+    let slot = (CHART_WIDTH - CHART_PADDING * 2.0) / values.len() as f64;
+
+    let points: Vec<String> = values
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            format!(
+                "{:.1},{:.1}",
+                CHART_PADDING + slot * (index as f64 + 0.5),
+                baseline - value * scale
+            )
+        })
+        .collect();
+    let mut svg = svg_open();
+    let _ = writeln!(
+        svg,
+        "<polyline points=\"{}\" class=\"line\"/>",
+        points.join(" ")
+    );
+    svg.push_str(&month_axis(rows, slot));
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// 최대값 기준 px 배율, 0 의 y 좌표 (환급 등 음수 포함)
+fn scale(values: &[f64]) -> (f64, f64) {
+    let max = values.iter().copied().fold(0.0, f64::max);
+    let min = values.iter().copied().fold(0.0, f64::min);
+    let range = (max - min).max(1.0);
+    let scale = (CHART_HEIGHT - CHART_PADDING * 2.0) / range;
+    (scale, CHART_PADDING + max * scale)
+}
+
+fn svg_open() -> String {
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">\n",
+        CHART_WIDTH, CHART_HEIGHT
+    )
+}
+
+// x 축 월 표시 (1월과 첫 달은 연도 포함)
+fn month_axis(rows: &[&PpData], slot: f64) -> String {
+    let mut axis = String::new();
+    for (index, entry) in rows.iter().enumerate() {
+        let label = if index == 0 || entry.claim_date.month() == 1 {
+            entry.claim_date.format("%y.%m").to_string()
+        } else {
+            entry.claim_date.format("%m").to_string()
+        };
+        let _ = writeln!(
+            axis,
+            "<text x=\"{:.1}\" y=\"{:.1}\" class=\"axis\">{}</text>",
+            CHART_PADDING + slot * (index as f64 + 0.5),
+            CHART_HEIGHT - 8.0,
+            label
+        );
+    }
+    axis
+}
+
+// 사용량 막대 PNG (알림 첨부용, 글자 없이 추세만, 최근 달 강조)
+pub fn usage_png(rows: &[&PpData]) -> Result<Vec<u8>> {
+    let (width, height) = (PNG_WIDTH as usize, PNG_HEIGHT as usize);
+    let mut pixels: Vec<[u8; 3]> = vec![BACKGROUND; width * height];
+    let padding = PNG_PADDING as usize;
+    let baseline = height - padding;
+    let max = rows
+        .iter()
+        .map(|entry| entry.usage)
+        .fold(0.0, f64::max)
+        .max(1.0);
+    let slot = (width - padding * 2) / rows.len().max(1);
+
+    for (index, entry) in rows.iter().enumerate() {
+        let bar_height = (entry.usage.max(0.0) / max * (baseline - padding) as f64) as usize;
+        let color = if index + 1 == rows.len() {
+            LATEST_BAR
+        } else {
+            BAR
+        };
+        let left = padding + slot * index + slot / 6;
+        let right = padding + slot * (index + 1) - slot / 6;
+        for y in baseline - bar_height..baseline {
+            for x in left..right {
+                pixels[y * width + x] = color;
+            }
+        }
+    }
+    for x in padding..width - padding {
+        pixels[baseline * width + x] = BASELINE;
+    }
+
+    let mut png = Vec::new();
+    let mut encoder = png::Encoder::new(&mut png, PNG_WIDTH, PNG_HEIGHT);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().context("Failed to encode chart")?;
+    writer
+        .write_image_data(&pixels.concat())
+        .context("Failed to encode chart")?;
+    writer.finish().context("Failed to encode chart")?;
+    Ok(png)
+}
+
+// 한 줄 추세 (Slack 등 이미지를 올릴 수 없는 채널)
+pub fn sparkline(rows: &[&PpData]) -> String {
+    let max = rows.iter().map(|entry| entry.usage).fold(0.0, f64::max);
+    let min = rows
+        .iter()
+        .map(|entry| entry.usage)
+        .fold(f64::MAX, f64::min);
+    let range = (max - min).max(f64::EPSILON);
+    rows.iter()
+        .map(|entry| {
+            let level = ((entry.usage - min) / range * (SPARKS.len() - 1) as f64).round();
+            SPARKS[(level as usize).min(SPARKS.len() - 1)]
+        })
+        .collect()
+}
@@ -0,0 +1,73 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::PpData;
+
+// 연도 page 마다 누적 결과를 partial 파일로 기록, 완료 시 최종 경로로 rename
+// (중간에 실패해도 이미 조회한 연도는 복구 가능)
+pub struct Checkpoint {
+    path: PathBuf,
+    partial: PathBuf,
+    rows: BTreeMap<String, Vec<PpData>>,
+}
+
+impl Checkpoint {
+    pub fn new(path: PathBuf) -> Self {
+        let partial = path.with_extension("json.partial");
+        Self {
+            path,
+            partial,
+            rows: BTreeMap::new(),
+        }
+    }
+
+    pub fn partial_path(&self) -> &Path {
+        &self.partial
+    }
+
+    pub fn customers(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows.values().map(Vec::len).sum()
+    }
+
+    // 고객 번호별 지금까지 기록된 row
+    pub fn customer_rows(&self, customer: &str) -> &[PpData] {
+        self.rows.get(customer).map_or(&[], Vec::as_slice)
+    }
+
+    // page 누적 후 partial 파일 갱신 (tmp 에 쓴 뒤 rename 하여 항상 완전한 JSON 유지)
+    pub fn record(&mut self, customer: &str, page: &[PpData]) -> Result<()> {
+        self.rows
+            .entry(customer.to_string())
+            .or_default()
+            .extend_from_slice(page);
+
+        let tmp = self.partial.with_extension("partial.tmp");
+        fs::write(&tmp, serde_json::to_vec(&self.rows)?)
+            .context(format!("Failed to write checkpoint: {}", tmp.display()))?;
+        fs::rename(&tmp, &self.partial).context(format!(
+            "Failed to update checkpoint: {}",
+            self.partial.display()
+        ))
+    }
+
+    // 조회 완료, partial -> 최종 파일
+    pub fn finish(self) -> Result<PathBuf> {
+        if !self.partial.exists() {
+            fs::write(&self.partial, serde_json::to_vec(&self.rows)?).context(format!(
+                "Failed to write checkpoint: {}",
+                self.partial.display()
+            ))?;
+        }
+        fs::rename(&self.partial, &self.path).context(format!(
+            "Failed to finalize checkpoint: {}",
+            self.path.display()
+        ))?;
+        Ok(self.path)
+    }
+}
@@ -0,0 +1,1118 @@
+use anyhow::{Context, Result};
+use chrono::{Datelike, NaiveDate};
+use fantoccini::{elements::Element, Client, ClientBuilder, Locator};
+use serde_json::{json, Map, Value};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio::time::{timeout, Duration, Instant};
+
+use crate::config::Credentials;
+use crate::error::{ErrorCode, Failure, Step};
+use crate::latency::PageLatency;
+use crate::ready::ReadyCondition;
+use crate::selectors::{self, SelectorOverride, SelectorProfile, WindowSize};
+use crate::warnings::{self, WarningKind};
+use crate::{
+    captcha, customer, extract, landing, pacing, prelogin, query, DailyUsage, HourlyUsage, PpData,
+};
+
+pub const TARGET_URL: &str = "https://pp.kepco.co.kr";
+
+// 월별 청구 요금 표, 연도 선택
+const CLAIMS_TABLE: &str = "//*[@id='grid']/tbody";
+pub const YEAR_SELECT: Locator<'static> = Locator::Id("year");
+
+// 일별 사용량 표, 월 선택 (연도는 YEAR_SELECT)
+const DAILY_TABLE: &str = "//*[@id='grid']/tbody";
+const MONTH_SELECT: Locator<'static> = Locator::Id("month");
+
+// 시간대별 사용량 표, 일자 입력 형식
+const HOURLY_TABLE: &str = "//*[@id='grid']/tbody";
+const HOURLY_DATE_FORMAT: &str = "%Y-%m-%d";
+
+// 연도 page 단위 parsing 결과 처리
+pub type PageSink<'a> = dyn FnMut(Vec<PpData>) -> Result<()> + 'a;
+
+// 월별 청구 요금 조회 범위
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ClaimScope<'a> {
+    // 저장된 data (도달하면 이전 연도 조회 중단)
+    pub known: Option<&'a HashMap<NaiveDate, PpData>>,
+    // 조회할 청구 월 범위 (--from, --to), 범위 밖 연도 option 은 조회하지 않음
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    // 지난 조회의 최근 청구 월 (--since-last), 이 월 이후 row 만 전달하고 도달하면 이전 연도 조회 중단
+    pub since: Option<NaiveDate>,
+}
+
+impl ClaimScope<'_> {
+    // 연도를 알 수 없는 option 은 조회
+    fn includes_year(&self, year: Option<i32>) -> bool {
+        let Some(year) = year else {
+            return true;
+        };
+        self.from.is_none_or(|from| year >= from.year())
+            && self.to.is_none_or(|to| year <= to.year())
+    }
+}
+
+// 공지 팝업 닫고 로그인
+pub async fn login(
+    client: &Client,
+    target_url: &str,
+    user_id: &str,
+    user_pw: &str,
+    flow: &LoginFlow,
+    latency: &mut PageLatency,
+) -> Result<()> {
+    // 사내 SSO 경유 등 로그인 전 이동
+    flow.pre_login.run(client).await?;
+
+    // 페이지 이동
+    client
+        .goto(&format!("{}/intro.do", target_url))
+        .await
+        .context(Failure::new(ErrorCode::Navigation, "Failed to navigate"))?;
+
+    // 공지 팝업 로드 대기
+    wait_for_element(client, Locator::Id("notice_auto_popup")).await?;
+    //공지 팝업 비활성화
+    click_element(client, Locator::XPath("/html/body/div[2]/div[3]/label")).await?;
+
+    // id 입력 로드 대기
+    wait_for_element(client, Locator::Id("RSA_USER_ID")).await?;
+    // id 입력
+    enter_value_in_element(client, Locator::Id("RSA_USER_ID"), user_id).await?;
+    // pw 입력
+    enter_value_in_element(client, Locator::Id("RSA_USER_PWD"), user_pw).await?;
+    // 로그인 form 의 CAPTCHA
+    captcha::check(client, &flow.captcha_dir).await?;
+    // 로그인 버튼 클릭
+    click_element(
+        client,
+        Locator::XPath("/html/body/div[1]/div[2]/div[1]/form/fieldset/input[1]"),
+    )
+    .await?;
+
+    // 로딩 대기
+    wait_for_loading(client, latency, "login").await?;
+    // 로그인 실패가 반복되면 나타나는 CAPTCHA
+    captcha::check(client, &flow.captcha_dir).await?;
+
+    // 의도한 계정으로 로그인됐는지 확인
+    match &flow.landing {
+        Some(landing) => landing.verify(client).await,
+        None => Ok(()),
+    }
+}
+
+// 표준 로그인 전후 설정 단계
+#[derive(Debug, Clone, Default)]
+pub struct LoginFlow {
+    // --pre-login script
+    pub pre_login: prelogin::Script,
+    // --expect-account
+    pub landing: Option<landing::LandingCheck>,
+    // CAPTCHA challenge 저장 위치
+    pub captcha_dir: PathBuf,
+}
+
+// 현재 화면의 항목 이름, 값 (th/td, dt/dd 쌍)
+const LABELED_FIELDS_SCRIPT: &str = r#"
+const pairs = [];
+for (const [name, value] of [["th", "td"], ["dt", "dd"]]) {
+    for (const label of document.querySelectorAll(name)) {
+        const next = label.nextElementSibling;
+        if (next && next.tagName.toLowerCase() === value) {
+            pairs.push([label.innerText, next.innerText]);
+        }
+    }
+}
+return pairs;
+"#;
+
+// 고객 정보 조회 화면 이동 후 계약 정보 읽기
+pub async fn read_customer_info(
+    client: &Client,
+    user_num: &str,
+    profile: &selectors::SelectorProfile,
+    latency: &mut PageLatency,
+) -> Result<customer::CustomerInfo> {
+    let menu = profile.customer_info.resolve(client).await?;
+    click_element(client, menu.locator()).await?;
+    wait_for_loading(client, latency, "customer_info").await?;
+
+    let fields = read_labeled_fields(client, "customer info")
+        .await
+        .context("Failed to read the customer info page")?;
+    Ok(customer::CustomerInfo::from_fields(user_num, &fields))
+}
+
+// 고객 정보, 청구서 상세 화면처럼 항목 이름과 값이 나란히 있는 화면 읽기
+pub async fn read_labeled_fields(client: &Client, page: &str) -> Result<Vec<(String, String)>> {
+    serde_json::from_value(client.execute(LABELED_FIELDS_SCRIPT, vec![]).await?)
+        .context(format!("Unexpected {} page", page))
+}
+
+// 월별 청구 요금 페이지 이동 (url 은 최초 1회 조회 후 재사용)
+pub async fn open_monthly_claims(
+    client: &Client,
+    target_url: &str,
+    claim_url: &mut Option<String>,
+    profile: &selectors::SelectorProfile,
+    latency: &mut PageLatency,
+) -> Result<()> {
+    open_menu(
+        client,
+        target_url,
+        claim_url,
+        &profile.monthly_claims,
+        "monthly_claim_href",
+        "claims",
+        latency,
+    )
+    .await
+}
+
+// 고객 번호 선택
+pub async fn select_customer(
+    client: &Client,
+    user_num: &str,
+    profile: &selectors::SelectorProfile,
+    latency: &mut PageLatency,
+) -> Result<()> {
+    // user_num 클릭 (하이픈, 별칭 등 표시 형식과 무관하게 번호로 비교)
+    let options = customer_options(client, profile).await?;
+    let Some((option, _)) = options
+        .iter()
+        .find(|(_, text)| customer::matches(text, user_num))
+    else {
+        let available: Vec<&str> = options.iter().map(|(_, text)| text.as_str()).collect();
+        return Err(Failure::new(
+            ErrorCode::ElementNotFound,
+            format!(
+                "Customer number {} not found, available: {}",
+                user_num,
+                available.join(", ")
+            ),
+        )
+        .with_selector(&profile.customer_options)
+        .into());
+    };
+    pacing::click(option)
+        .await
+        .context(format!("Failed to click the customer number: {}", user_num))?;
+
+    // 로딩 대기
+    wait_for_loading(client, latency, "customer").await
+}
+
+// 고객 번호 목록 열고 항목, 표시 문자열 반환
+pub async fn customer_options(
+    client: &Client,
+    profile: &selectors::SelectorProfile,
+) -> Result<Vec<(Element, String)>> {
+    // user_num selector 클릭
+    let toggle = profile.customer_toggle.resolve(client).await?;
+    click_element(client, toggle.locator()).await?;
+
+    let options_selector = profile.customer_options.resolve(client).await?;
+    let mut options = Vec::new();
+    for option in client.find_all(options_selector.locator()).await? {
+        let text = option.text().await?.trim().to_string();
+        options.push((option, text));
+    }
+    Ok(options)
+}
+
+// 월별 청구 요금 페이지에서 전체 기간 (scope 의 연도 범위) 조회
+// 연도 page 단위로 on_page 에 전달 (최신 연도부터, 중복 제거, 정렬)
+// scope.known 이 있으면 이미 저장된 월에 도달한 시점에서 조회 중단
+pub async fn fetch_monthly_claims(
+    client: &Arc<Client>,
+    scope: ClaimScope<'_>,
+    on_page: &mut PageSink<'_>,
+    profile: &selectors::SelectorProfile,
+    latency: &mut PageLatency,
+    archive: Option<&extract::RawArchive>,
+) -> Result<()> {
+    // 중복 제거, 정렬 후 전달
+    let mut unique_dates = HashSet::new();
+    let mut emit = |mut page: Vec<PpData>| -> Result<()> {
+        page.retain(|entry| unique_dates.insert(entry.claim_date));
+        page.sort_by_key(|entry| Reverse(entry.claim_date));
+        on_page(page)
+    };
+
+    // data from table -> vec
+    let mut page = extract::grab(client, CLAIMS_TABLE, None).await?;
+    let mut data_vec = extract::parse(page.clone()).await?.into_rows();
+    // 최초 로드 시 표시 중인 연도
+    let displayed_year = data_vec.iter().map(|entry| entry.claim_date.year()).max();
+    if let Some(archive) = archive {
+        page.year_view = displayed_year;
+        archive.keep(&page);
+    }
+    for entry in data_vec.iter_mut() {
+        if let Some(provenance) = entry.provenance.as_mut() {
+            provenance.year_view = displayed_year;
+        }
+    }
+    let reached_known = skip_known_rows(&mut data_vec, scope);
+    emit(data_vec)?;
+
+    if reached_known {
+        return Ok(());
+    }
+
+    // 표시 중인 연도 외 전체 연도 data parsing
+    parsing_options_data(
+        client,
+        displayed_year,
+        scope,
+        &mut emit,
+        profile,
+        latency,
+        archive,
+    )
+    .await
+}
+
+// 연도 option 하나만 선택해 조회
+pub async fn fetch_year_claims(
+    client: &Arc<Client>,
+    select_locator: Locator<'_>,
+    year: i32,
+    profile: &selectors::SelectorProfile,
+    latency: &mut PageLatency,
+    archive: Option<&extract::RawArchive>,
+) -> Result<Vec<PpData>> {
+    // 연도 option 찾기
+    let option = find_option(client, select_locator, year, "Year").await?;
+
+    // 옵션 선택 후 조회
+    profile
+        .year_query(client)
+        .await?
+        .query(client, &[query::Input::Option(&option)], latency)
+        .await?;
+
+    // data parsing (해당 연도 row 만)
+    let page = extract::grab(client, CLAIMS_TABLE, Some(year)).await?;
+    if let Some(archive) = archive {
+        archive.keep(&page);
+    }
+    let mut data_vec = extract::parse(page).await?.into_rows();
+    data_vec.retain(|entry| entry.claim_date.year() == year);
+    data_vec.sort_by_key(|entry| Reverse(entry.claim_date));
+
+    Ok(data_vec)
+}
+
+// 일별 사용량 페이지 이동 후 연도, 월 선택해 한 달 조회 (url 은 최초 1회 조회 후 재사용)
+pub async fn fetch_daily_usage(
+    client: &Client,
+    target_url: &str,
+    daily_url: &mut Option<String>,
+    year: i32,
+    month: u32,
+    profile: &selectors::SelectorProfile,
+    latency: &mut PageLatency,
+) -> Result<Vec<DailyUsage>> {
+    let view = NaiveDate::from_ymd_opt(year, month, 1).context(Failure::new(
+        ErrorCode::Config,
+        format!("Invalid month: {}-{}", year, month),
+    ))?;
+
+    open_menu(
+        client,
+        target_url,
+        daily_url,
+        &profile.daily_usage,
+        "daily_usage_href",
+        "daily",
+        latency,
+    )
+    .await?;
+
+    // 연도, 월 option 선택 후 조회
+    let year_option = find_option(client, YEAR_SELECT, year, "Year").await?;
+    let month_option = find_option(client, MONTH_SELECT, month as i32, "Month").await?;
+    profile
+        .daily_query(client)
+        .await?
+        .query(
+            client,
+            &[
+                query::Input::Option(&year_option),
+                query::Input::Option(&month_option),
+            ],
+            latency,
+        )
+        .await?;
+
+    // data parsing (해당 월 row 만)
+    let page = extract::grab(client, DAILY_TABLE, Some(year)).await?;
+    let mut data = extract::parse_daily_page(&page.html, view).into_rows();
+    data.retain(|entry| entry.date.year() == year && entry.date.month() == month);
+    data.sort_by_key(|entry| entry.date);
+
+    Ok(data)
+}
+
+// 시간대별 사용량 페이지 이동 후 일자 입력해 하루 조회 (url 은 최초 1회 조회 후 재사용)
+pub async fn fetch_hourly_usage(
+    client: &Client,
+    target_url: &str,
+    hourly_url: &mut Option<String>,
+    date: NaiveDate,
+    profile: &selectors::SelectorProfile,
+    latency: &mut PageLatency,
+) -> Result<Vec<HourlyUsage>> {
+    open_menu(
+        client,
+        target_url,
+        hourly_url,
+        &profile.hourly_usage,
+        "hourly_usage_href",
+        "hourly",
+        latency,
+    )
+    .await?;
+
+    // 일자 입력 후 조회
+    let input = profile.hourly_date.resolve(client).await?;
+    let value = date.format(HOURLY_DATE_FORMAT).to_string();
+    profile
+        .hourly_query(client)
+        .await?
+        .query(
+            client,
+            &[query::Input::Text {
+                locator: input.locator(),
+                value: &value,
+            }],
+            latency,
+        )
+        .await?;
+
+    // data parsing (1시간 또는 15분 구간)
+    let page = extract::grab(client, HOURLY_TABLE, Some(date.year())).await?;
+    Ok(extract::parse_hourly_page(&page.html, date).into_rows())
+}
+
+// 메뉴 링크의 페이지 이동 후 로딩 대기 (url 은 최초 1회 조회 후 재사용)
+async fn open_menu(
+    client: &Client,
+    target_url: &str,
+    cached_url: &mut Option<String>,
+    menu: &selectors::Selector,
+    name: &str,
+    page: &'static str,
+    latency: &mut PageLatency,
+) -> Result<()> {
+    let url = match cached_url {
+        Some(url) => url.clone(),
+        None => {
+            let url = menu_url(client, target_url, menu, name).await?;
+            *cached_url = Some(url.clone());
+            url
+        }
+    };
+    client.goto(&url).await.context(Failure::new(
+        ErrorCode::Navigation,
+        format!("Failed go to {}", name),
+    ))?;
+
+    // 로딩 대기
+    wait_for_loading(client, latency, page).await
+}
+
+// 메뉴 링크의 이동 url
+async fn menu_url(
+    client: &Client,
+    target_url: &str,
+    menu: &selectors::Selector,
+    name: &str,
+) -> Result<String> {
+    let resolved = menu.resolve(client).await?;
+    let locator = resolved.locator();
+    let href = get_href_by_locator(client, locator).await.context(
+        Failure::new(
+            ErrorCode::ElementNotFound,
+            format!("Failed to find {}", name),
+        )
+        .with_selector(locator),
+    )?;
+    Ok(format!("{}{}", target_url, href))
+}
+
+// select box 에서 표시 문자열의 숫자가 value 인 option (e.g. "2024년", "05월")
+async fn find_option(
+    client: &Client,
+    select_locator: Locator<'_>,
+    value: i32,
+    what: &str,
+) -> Result<Element> {
+    let options = query::select_options(client, select_locator).await?;
+    let mut available = Vec::with_capacity(options.len());
+    for option in options {
+        let number = get_option_number(&option).await;
+        if number == Some(value) {
+            return Ok(option);
+        }
+        available.extend(number);
+    }
+    Err(Failure::new(
+        ErrorCode::ElementNotFound,
+        format!("{} {} not found in options {:?}", what, value, available),
+    )
+    .with_selector(select_locator)
+    .into())
+}
+
+// 저장된 data 와 동일한 row, --since-last 월 이전 row 제거, 이미 조회한 월에 도달했는지 반환
+fn skip_known_rows(data: &mut Vec<PpData>, scope: ClaimScope<'_>) -> bool {
+    // 지난 조회의 최근 청구 월은 값이 바뀌었을 수 있어 다시 전달
+    let reached_since = scope.since.is_some_and(|since| {
+        let before = data.len();
+        data.retain(|entry| entry.claim_date >= since);
+        data.len() != before || data.iter().any(|entry| entry.claim_date == since)
+    });
+    let Some(known) = scope.known else {
+        return reached_since;
+    };
+
+    let before = data.len();
+    data.retain(|entry| {
+        !known
+            .get(&entry.claim_date)
+            .is_some_and(|stored| stored.same_values(entry))
+    });
+    data.len() != before || reached_since
+}
+
+// 요소 대기
+async fn wait_for_element(client: &Client, locator: Locator<'_>) -> Result<Option<Element>> {
+    wait_for_element_within(
+        client,
+        locator,
+        Duration::from_secs(30),
+        Duration::from_millis(250),
+    )
+    .await
+}
+
+// 요소 대기 (timeout, poll 간격 지정)
+async fn wait_for_element_within(
+    client: &Client,
+    locator: Locator<'_>,
+    duration: Duration,
+    poll: Duration,
+) -> Result<Option<Element>> {
+    match client
+        .wait()
+        .at_most(duration)
+        .every(poll)
+        .for_element(locator)
+        .await
+    {
+        Ok(element) => Ok(Some(element)),
+        Err(e) => {
+            warnings::emit(
+                WarningKind::MissingElement,
+                format!("Failed to find the element: {:?}: {}", locator, e),
+            );
+            Err(Failure::new(
+                ErrorCode::ElementNotFound,
+                format!("Failed to find the element: {:?}", e),
+            )
+            .with_selector(locator)
+            .into())
+        }
+    }
+}
+
+// 요소 클릭
+pub async fn click_element(client: &Client, locator: Locator<'_>) -> Result<()> {
+    if let Ok(element) = client.find(locator).await {
+        pacing::click(&element)
+            .await
+            .context(format!("Failed to click the element: {:?}", locator))?;
+    } else {
+        warnings::emit(
+            WarningKind::MissingElement,
+            format!("Failed to find the element: {:?}", locator),
+        );
+        return Err(Failure::new(
+            ErrorCode::ElementNotFound,
+            format!("Failed to find the element: {:?}", locator),
+        )
+        .with_selector(locator)
+        .into());
+    }
+    Ok(())
+}
+
+// 요소에 값 입력
+async fn enter_value_in_element(client: &Client, locator: Locator<'_>, text: &str) -> Result<()> {
+    if let Ok(element) = client.find(locator).await {
+        if let Err(e) = pacing::type_text(&element, text).await {
+            warnings::emit(
+                WarningKind::MissingElement,
+                format!("Failed to enter text into {:?}: {}", locator, e),
+            );
+        }
+    } else {
+        warnings::emit(
+            WarningKind::MissingElement,
+            format!("Failed to find the input element: {:?}", locator),
+        );
+    }
+    Ok(())
+}
+
+// loading overlay 대기 (페이지별 관측 소요 시간으로 timeout, poll 간격 조정)
+pub async fn wait_for_loading(
+    client: &Client,
+    latency: &mut PageLatency,
+    page: &'static str,
+) -> Result<()> {
+    let started = Instant::now();
+    let (duration, poll) = (latency.timeout(page), latency.poll_interval(page));
+    match latency.ready(page) {
+        ReadyCondition::Hidden(css) => {
+            wait_for_element_display_none(client, Locator::Css(css), duration, poll).await?
+        }
+        ReadyCondition::Present(css) => {
+            wait_for_element_within(client, Locator::Css(css), duration, poll).await?;
+        }
+        ReadyCondition::Script(predicate) => {
+            wait_for_script(client, predicate, duration, poll).await?
+        }
+    }
+    latency.record(page, started.elapsed());
+    Ok(())
+}
+
+// JS 식이 true 가 될 때까지 대기
+async fn wait_for_script(
+    client: &Client,
+    predicate: &str,
+    duration: Duration,
+    poll: Duration,
+) -> Result<()> {
+    let script = format!("return !!({});", predicate);
+    let deadline = Instant::now() + duration;
+    loop {
+        if let Ok(Value::Bool(true)) = client.execute(&script, vec![]).await {
+            return Ok(());
+        }
+        if Instant::now() >= deadline {
+            return Err(Failure::new(
+                ErrorCode::Timeout,
+                format!(
+                    "Page ready condition not met within the given duration: {}",
+                    predicate
+                ),
+            )
+            .into());
+        }
+        tokio::time::sleep(poll).await;
+    }
+}
+
+// 요소 비활성화 대기
+async fn wait_for_element_display_none(
+    client: &Client,
+    locator: Locator<'_>,
+    duration: Duration,
+    poll: Duration,
+) -> Result<()> {
+    let element = match wait_for_element_within(client, locator, duration, poll).await? {
+        Some(element) => element,
+        None => return Err(anyhow::anyhow!("Failed to find the element: {:?}", locator)),
+    };
+
+    // MutationObserver 로 style/class 변경 시점에 바로 확인 (polling 없음)
+    let script = r#"
+        const [element, timeoutMs, done] = arguments;
+        const isHidden = () => window.getComputedStyle(element).display === 'none';
+        if (isHidden()) {
+            done(true);
+            return;
+        }
+        const observer = new MutationObserver(() => {
+            if (isHidden()) {
+                observer.disconnect();
+                clearTimeout(timer);
+                done(true);
+            }
+        });
+        const timer = setTimeout(() => {
+            observer.disconnect();
+            done(isHidden());
+        }, timeoutMs);
+        observer.observe(element, { attributes: true, attributeFilter: ['style', 'class'] });
+        "#;
+    let args = vec![
+        serde_json::to_value(&element).context("Failed to serialize element")?,
+        json!(duration.as_millis() as u64),
+    ];
+
+    // script 자체가 응답하지 않는 경우 대비
+    let element_hidden = timeout(
+        duration + Duration::from_secs(1),
+        client.execute_async(script, args),
+    )
+    .await;
+
+    match element_hidden {
+        Ok(Ok(Value::Bool(true))) => Ok(()),
+        Ok(Err(e)) => Err(anyhow::anyhow!(
+            "Failed to observe the element: {:?}\n {}",
+            locator,
+            e
+        )),
+        _ => Err(Failure::new(
+            ErrorCode::Timeout,
+            "Failed to find the element within the given duration",
+        )
+        .with_selector(locator)
+        .into()),
+    }
+}
+
+// option 텍스트의 숫자 (e.g. "2024년" -> 2024, "05월" -> 5)
+async fn get_option_number(option: &Element) -> Option<i32> {
+    let text = option.text().await.ok()?;
+    text.chars()
+        .filter(|c| c.is_ascii_digit())
+        .collect::<String>()
+        .parse()
+        .ok()
+}
+
+// get text from locator
+pub async fn get_text_by_locator(client: &Client, locator: Locator<'_>) -> Option<String> {
+    match client.find(locator).await.ok() {
+        Some(element) => element.text().await.ok(),
+        None => None,
+    }
+}
+
+// get href from locator
+async fn get_href_by_locator(client: &Client, locator: Locator<'_>) -> Option<String> {
+    match client.find(locator).await.ok() {
+        Some(element) => element.attr("href").await.ok().flatten(),
+        None => None,
+    }
+}
+
+// options 들의 결과값 parsing
+async fn parsing_options_data(
+    client: &Arc<Client>,
+    displayed_year: Option<i32>,
+    scope: ClaimScope<'_>,
+    on_page: &mut PageSink<'_>,
+    profile: &selectors::SelectorProfile,
+    latency: &mut PageLatency,
+    archive: Option<&extract::RawArchive>,
+) -> Result<()> {
+    // option 요소
+    let options = query::select_options(client, YEAR_SELECT).await?;
+
+    // 이전 연도 page 를 parsing 하는 동안 다음 연도 조회 (browser 단계와 parse 단계가 서로 기다리지 않음)
+    // 저장된 월에서 멈출 수 있으면 parsing 결과를 먼저 확인 (버릴 연도를 조회하지 않음)
+    let can_stop = scope.known.is_some() || scope.since.is_some();
+    let mut parsing: Option<JoinHandle<Result<extract::Parsed>>> = None;
+
+    // 전체 연도 data parsing
+    for option in options.iter() {
+        // 이미 표시 중인 연도면 재조회 생략
+        let option_year = get_option_number(option).await;
+        if displayed_year.is_some() && option_year == displayed_year {
+            continue;
+        }
+        // --from, --to 범위 밖 연도
+        if !scope.includes_year(option_year) {
+            continue;
+        }
+
+        // 저장된 월 도달 시 이전 연도 조회 중단
+        if can_stop {
+            if let Some(task) = parsing.take() {
+                if finish_page(task, scope, on_page).await? {
+                    return Ok(());
+                }
+            }
+        }
+
+        // 옵션 선택 후 조회
+        profile
+            .year_query(client)
+            .await?
+            .query(client, &[query::Input::Option(option)], latency)
+            .await?;
+
+        // 멈출 일이 없으면 조회하는 동안 parsing 한 이전 연도 전달
+        if let Some(task) = parsing.take() {
+            finish_page(task, scope, on_page).await?;
+        }
+
+        // 표 HTML 만 가져오고 parsing 은 blocking thread 에서
+        let page = extract::grab(client, CLAIMS_TABLE, option_year).await?;
+        if let Some(archive) = archive {
+            archive.keep(&page);
+        }
+        parsing = Some(tokio::spawn(extract::parse(page)));
+    }
+
+    if let Some(task) = parsing {
+        finish_page(task, scope, on_page).await?;
+    }
+    Ok(())
+}
+
+// parsing 결과에서 저장된 row 제거 후 전달, 저장된 월 도달 여부 반환
+async fn finish_page(
+    task: JoinHandle<Result<extract::Parsed>>,
+    scope: ClaimScope<'_>,
+    on_page: &mut PageSink<'_>,
+) -> Result<bool> {
+    let mut data = task.await.context("Parse task failed")??.into_rows();
+    let reached_known = skip_known_rows(&mut data, scope);
+    on_page(data)?;
+    Ok(reached_known)
+}
+
+// 파워플래너 조회 client (binary 를 실행하지 않고 다른 program 에서 직접 조회)
+// login -> select_customer -> fetch_monthly_claims 순서로 호출, 고객 번호마다 select_customer 부터 반복
+pub struct KepcoClient {
+    client: Arc<Client>,
+    target_url: String,
+    flow: LoginFlow,
+    window: WindowSize,
+    overrides: Vec<SelectorOverride>,
+    latency: PageLatency,
+    // 로그인 후 판별한 layout
+    profile: Option<SelectorProfile>,
+    // 월별 청구 요금 url (최초 1회 조회 후 재사용)
+    claim_url: Option<String>,
+    // 일별, 시간대별 사용량 url (최초 1회 조회 후 재사용)
+    daily_url: Option<String>,
+    hourly_url: Option<String>,
+}
+
+impl KepcoClient {
+    // 이미 연결된 WebDriver session 사용 (window 크기는 호출하는 쪽에서 설정)
+    pub fn new(client: Client) -> Self {
+        Self {
+            client: Arc::new(client),
+            target_url: TARGET_URL.to_string(),
+            flow: LoginFlow::default(),
+            window: selectors::DEFAULT_WINDOW,
+            overrides: Vec::new(),
+            latency: PageLatency::new(&[], &[]),
+            profile: None,
+            claim_url: None,
+            daily_url: None,
+            hourly_url: None,
+        }
+    }
+
+    // 실행 중인 WebDriver server (ChromeDriver 등) 에 headless session 연결
+    pub async fn connect(webdriver_url: &str) -> Result<Self> {
+        let capabilities: Map<String, Value> = serde_json::from_value(json!({
+            "goog:chromeOptions": {
+                "args": ["--headless", "--disable-gpu"]
+            }
+        }))?;
+        let client = ClientBuilder::native()
+            .capabilities(capabilities)
+            .connect(webdriver_url)
+            .await
+            .context(Failure::new(
+                ErrorCode::Driver,
+                format!("Failed to connect to WebDriver: {}", webdriver_url),
+            ))?;
+        let window = selectors::DEFAULT_WINDOW;
+        client
+            .set_window_rect(0, 0, window.width, window.height)
+            .await
+            .context(Failure::new(ErrorCode::Driver, "Failed to set window size"))?;
+        Ok(Self::new(client))
+    }
+
+    // 로그인 전후 단계 (pre-login script, 계정 확인, CAPTCHA 저장 위치)
+    pub fn with_login_flow(mut self, flow: LoginFlow) -> Self {
+        self.flow = flow;
+        self
+    }
+
+    // session 의 window 크기, layout 별 selector 대신 쓸 selector
+    pub fn with_selectors(mut self, window: WindowSize, overrides: Vec<SelectorOverride>) -> Self {
+        self.window = window;
+        self.overrides = overrides;
+        self
+    }
+
+    // 페이지별 timeout, 로딩 완료 조건
+    pub fn with_latency(mut self, latency: PageLatency) -> Self {
+        self.latency = latency;
+        self
+    }
+
+    // portal 주소 (기본 TARGET_URL)
+    pub fn with_target_url(mut self, target_url: impl Into<String>) -> Self {
+        self.target_url = target_url.into();
+        self
+    }
+
+    // 직접 조작할 때 쓰는 WebDriver session (로그인 후 화면 조회는 with_browser)
+    pub fn webdriver(&self) -> &Arc<Client> {
+        &self.client
+    }
+
+    // 로그인 후 판별한 layout 이름
+    pub fn layout(&self) -> Option<&'static str> {
+        self.profile.as_ref().map(|profile| profile.name)
+    }
+
+    // 로그인 후 layout 판별
+    pub async fn login(&mut self, credentials: &Credentials) -> Result<()> {
+        login(
+            &self.client,
+            &self.target_url,
+            &credentials.user_id,
+            &credentials.user_pw,
+            &self.flow,
+            &mut self.latency,
+        )
+        .await
+        .context(Step("login"))?;
+        self.profile = Some(selectors::detect(&self.client, self.window, &self.overrides).await);
+        Ok(())
+    }
+
+    // 고객 번호 전환 후 월별 청구 요금 페이지 이동
+    pub async fn select_customer(&mut self, customer_number: &str) -> Result<()> {
+        let profile = logged_in(self.profile.as_ref())?;
+        select_customer(&self.client, customer_number, profile, &mut self.latency)
+            .await
+            .context(Step("select_customer"))?;
+        open_monthly_claims(
+            &self.client,
+            &self.target_url,
+            &mut self.claim_url,
+            profile,
+            &mut self.latency,
+        )
+        .await
+        .context(Step("navigate_claims"))
+    }
+
+    // 선택한 고객 번호의 계약 정보 (고객 정보 조회 화면 이동, 이후 select_customer 다시 호출)
+    pub async fn customer_info(&mut self, customer_number: &str) -> Result<customer::CustomerInfo> {
+        let profile = logged_in(self.profile.as_ref())?;
+        read_customer_info(&self.client, customer_number, profile, &mut self.latency)
+            .await
+            .context(Step("customer_info"))
+    }
+
+    // 선택한 고객 번호의 전체 기간 월별 청구 요금 (최신 월부터)
+    pub async fn fetch_monthly_claims(&mut self) -> Result<Vec<PpData>> {
+        let profile = logged_in(self.profile.as_ref())?;
+        let mut data = Vec::new();
+        fetch_monthly_claims(
+            &self.client,
+            ClaimScope::default(),
+            &mut |page| {
+                data.extend(page);
+                Ok(())
+            },
+            profile,
+            &mut self.latency,
+            None,
+        )
+        .await
+        .context(Step("scrape"))?;
+        Ok(data)
+    }
+
+    // 선택한 고객 번호의 한 해 월별 청구 요금
+    pub async fn fetch_year_claims(&mut self, year: i32) -> Result<Vec<PpData>> {
+        let profile = logged_in(self.profile.as_ref())?;
+        fetch_year_claims(
+            &self.client,
+            YEAR_SELECT,
+            year,
+            profile,
+            &mut self.latency,
+            None,
+        )
+        .await
+        .context(Step("backfill"))
+    }
+
+    // 선택한 고객 번호의 한 달 일별 사용량 (일별 사용량 페이지로 이동, 월별 청구 요금은 select_customer 다시 호출)
+    pub async fn fetch_daily_usage(&mut self, year: i32, month: u32) -> Result<Vec<DailyUsage>> {
+        let profile = logged_in(self.profile.as_ref())?;
+        fetch_daily_usage(
+            &self.client,
+            &self.target_url,
+            &mut self.daily_url,
+            year,
+            month,
+            profile,
+            &mut self.latency,
+        )
+        .await
+        .context(Step("daily_usage"))
+    }
+
+    // 선택한 고객 번호의 하루 시간대별 사용량 (시간대별 사용량 페이지로 이동, 월별 청구 요금은 select_customer 다시 호출)
+    pub async fn fetch_hourly_usage(&mut self, date: NaiveDate) -> Result<Vec<HourlyUsage>> {
+        let profile = logged_in(self.profile.as_ref())?;
+        fetch_hourly_usage(
+            &self.client,
+            &self.target_url,
+            &mut self.hourly_url,
+            date,
+            profile,
+            &mut self.latency,
+        )
+        .await
+        .context(Step("hourly_usage"))
+    }
+
+    // 로그인된 session 으로 crate 가 다루지 않는 화면 직접 조회 (이후 select_customer 다시 호출)
+    // f 가 끝나면 (실패해도) 새로 열린 창을 닫고 원래 창으로 복귀
+    pub async fn with_browser<T>(
+        &mut self,
+        f: impl for<'b> AsyncFnOnce(&mut Browser<'b>) -> Result<T>,
+    ) -> Result<T> {
+        let profile = logged_in(self.profile.as_ref())?;
+        let window = self.client.window().await?;
+        let before = self.client.windows().await?;
+        let mut browser = Browser {
+            client: &self.client,
+            target_url: &self.target_url,
+            profile,
+            latency: &mut self.latency,
+        };
+        let result = f(&mut browser).await;
+
+        for opened in self.client.windows().await? {
+            if !before.contains(&opened) {
+                self.client.switch_to_window(opened).await?;
+                self.client.close_window().await?;
+            }
+        }
+        self.client
+            .switch_to_window(window)
+            .await
+            .context(Failure::new(
+                ErrorCode::Driver,
+                "Failed to return to the original window",
+            ))?;
+        result
+    }
+
+    // WebDriver session 종료 (driver process 는 호출하는 쪽에서 종료)
+    pub async fn close(self) -> Result<()> {
+        Client::clone(&self.client)
+            .close()
+            .await
+            .context(Failure::new(
+                ErrorCode::Driver,
+                "Failed to close the WebDriver session",
+            ))
+    }
+}
+
+// with_browser 안에서 쓰는 로그인된 session 과 대기, 클릭 helper
+pub struct Browser<'a> {
+    client: &'a Arc<Client>,
+    target_url: &'a str,
+    profile: &'a SelectorProfile,
+    latency: &'a mut PageLatency,
+}
+
+impl Browser<'_> {
+    // WebDriver session (fantoccini)
+    pub fn client(&self) -> &Client {
+        self.client
+    }
+
+    // 로그인 후 판별한 layout 의 selector
+    pub fn selectors(&self) -> &SelectorProfile {
+        self.profile
+    }
+
+    // portal 주소 기준 경로 (절대 url 도 가능) 이동 후 loading overlay 대기
+    pub async fn goto(&mut self, path: &str, page: &'static str) -> Result<()> {
+        let url = if path.starts_with("http") {
+            path.to_string()
+        } else {
+            format!(
+                "{}/{}",
+                self.target_url.trim_end_matches('/'),
+                path.trim_start_matches('/')
+            )
+        };
+        self.client
+            .goto(&url)
+            .await
+            .context(format!("Failed to open {}", url))?;
+        self.wait(page).await
+    }
+
+    // loading overlay 대기 (page 이름별로 timeout, 관측 시간 기록, --step-timeout 에 없는 이름은 기본값)
+    pub async fn wait(&mut self, page: &'static str) -> Result<()> {
+        wait_for_loading(self.client, self.latency, page).await
+    }
+
+    // 요소 클릭 (pacing 적용)
+    pub async fn click(&self, locator: Locator<'_>) -> Result<()> {
+        click_element(self.client, locator).await
+    }
+
+    // 요소 text (없으면 None)
+    pub async fn text(&self, locator: Locator<'_>) -> Option<String> {
+        get_text_by_locator(self.client, locator).await
+    }
+
+    // 현재 화면의 항목 이름, 값 (th/td, dt/dd 쌍)
+    pub async fn labeled_fields(&self) -> Result<Vec<(String, String)>> {
+        read_labeled_fields(self.client, "current").await
+    }
+
+    // 현재 화면의 표 (xpath 의 tbody) 를 row 별 cell text 로
+    pub async fn table(&self, tbody: &str) -> Result<Vec<Vec<String>>> {
+        let mut rows = Vec::new();
+        for row in self
+            .client
+            .find_all(Locator::XPath(&format!("{}/tr", tbody)))
+            .await?
+        {
+            let mut cells = Vec::new();
+            for cell in row.find_all(Locator::Css("td")).await? {
+                cells.push(cell.text().await?.trim().to_string());
+            }
+            rows.push(cells);
+        }
+        Ok(rows)
+    }
+}
+
+// 로그인 전이면 설정 오류
+fn logged_in(profile: Option<&SelectorProfile>) -> Result<&SelectorProfile> {
+    profile.context(Failure::new(
+        ErrorCode::Config,
+        "Not logged in, call login() first",
+    ))
+}
@@ -0,0 +1,336 @@
+use anyhow::{bail, ensure, Context, Result};
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer};
+use std::env;
+use std::fmt;
+use std::path::Path;
+use std::process::Stdio;
+use std::str::FromStr;
+use tokio::process::Command;
+use tokio::time::{timeout, Duration};
+
+use crate::customer;
+use crate::error::{ErrorCode, Failure};
+
+// 외부 명령 provider 응답 대기 시간
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+// OS keyring 항목의 service 이름 (account 는 user ID)
+#[cfg(feature = "keyring")]
+const KEYRING_SERVICE: &str = "rip_hyphen";
+
+// 로그인 정보
+#[derive(Deserialize)]
+pub struct Credentials {
+    pub user_id: String,
+    pub user_pw: String,
+}
+
+impl Credentials {
+    pub fn new(user_id: String, user_pw: String) -> Result<Self> {
+        ensure!(!user_id.trim().is_empty(), "User ID is empty");
+        ensure!(!user_pw.is_empty(), "Password is empty");
+        Ok(Self { user_id, user_pw })
+    }
+
+    pub fn from_env() -> Result<Self> {
+        let user_id = env::var("PP_ID").context("PP_ID is not set")?;
+        let user_pw = env::var("PP_PW").context("PP_PW is not set")?;
+
+        ensure!(!user_id.trim().is_empty(), "PP_ID is empty");
+        ensure!(!user_pw.is_empty(), "PP_PW is empty");
+
+        Ok(Self { user_id, user_pw })
+    }
+}
+
+// 로그인 정보 공급자 (조회를 시작할 때마다 호출)
+// 기본 제공 provider 외에 사내 DB, HSM, tenant 별 vault 등 직접 구현한 provider 도 Config::load 에 넘길 수 있음
+#[async_trait]
+pub trait CredentialProvider: Send + Sync {
+    // 로그, 실패 메시지에 표시할 이름
+    fn name(&self) -> String;
+
+    async fn credentials(&self) -> Result<Credentials>;
+}
+
+// 환경 변수 PP_ID, PP_PW
+pub struct EnvProvider;
+
+#[async_trait]
+impl CredentialProvider for EnvProvider {
+    fn name(&self) -> String {
+        "env".to_string()
+    }
+
+    async fn credentials(&self) -> Result<Credentials> {
+        Credentials::from_env()
+    }
+}
+
+// 호출하는 쪽에서 받은 로그인 정보 (명령행 --id, --pw 등)
+pub struct StaticProvider {
+    pub user_id: String,
+    pub user_pw: String,
+}
+
+#[async_trait]
+impl CredentialProvider for StaticProvider {
+    fn name(&self) -> String {
+        "static".to_string()
+    }
+
+    async fn credentials(&self) -> Result<Credentials> {
+        Credentials::new(self.user_id.clone(), self.user_pw.clone())
+    }
+}
+
+// 외부 명령이 stdout 에 출력한 JSON ({"user_id": "...", "user_pw": "..."})
+pub struct CommandProvider {
+    pub command: String,
+}
+
+#[async_trait]
+impl CredentialProvider for CommandProvider {
+    fn name(&self) -> String {
+        format!("command:{}", self.command)
+    }
+
+    async fn credentials(&self) -> Result<Credentials> {
+        let output = timeout(
+            COMMAND_TIMEOUT,
+            Command::new("sh")
+                .arg("-c")
+                .arg(&self.command)
+                .stdin(Stdio::null())
+                .stderr(Stdio::inherit())
+                .kill_on_drop(true)
+                .output(),
+        )
+        .await
+        .context(format!(
+            "Credential command did not finish within {}s",
+            COMMAND_TIMEOUT.as_secs()
+        ))?
+        .context("Failed to run the credential command")?;
+        if !output.status.success() {
+            bail!("Credential command exited with {}", output.status);
+        }
+        let Credentials { user_id, user_pw } = serde_json::from_slice(&output.stdout)
+            .context("Credential command must print {\"user_id\": ..., \"user_pw\": ...}")?;
+        Credentials::new(user_id, user_pw)
+    }
+}
+
+// OS keyring 에 저장한 비밀번호 (macOS Keychain, Windows Credential Manager, Linux Secret Service)
+pub struct KeyringProvider {
+    pub user_id: String,
+}
+
+#[async_trait]
+impl CredentialProvider for KeyringProvider {
+    fn name(&self) -> String {
+        format!("keyring:{}", self.user_id)
+    }
+
+    async fn credentials(&self) -> Result<Credentials> {
+        let user_pw = read_keyring(&self.user_id).await?;
+        Credentials::new(self.user_id.clone(), user_pw)
+    }
+}
+
+// OS keyring 에 비밀번호 저장 (같은 user ID 면 덮어씀)
+#[cfg(feature = "keyring")]
+pub async fn store_keyring(user_id: &str, user_pw: &str) -> Result<()> {
+    let user_pw = user_pw.to_string();
+    keyring_call(user_id, move |entry| entry.set_password(&user_pw)).await
+}
+
+#[cfg(feature = "keyring")]
+async fn read_keyring(user_id: &str) -> Result<String> {
+    keyring_call(user_id, |entry| entry.get_password()).await
+}
+
+// keyring 호출은 blocking (D-Bus 등) 이라 blocking thread 에서 실행
+#[cfg(feature = "keyring")]
+async fn keyring_call<T: Send + 'static>(
+    user_id: &str,
+    call: impl FnOnce(&keyring::Entry) -> keyring::Result<T> + Send + 'static,
+) -> Result<T> {
+    let user_id = user_id.to_string();
+    let account = user_id.clone();
+    let result =
+        tokio::task::spawn_blocking(move || call(&keyring::Entry::new(KEYRING_SERVICE, &account)?))
+            .await
+            .context("Keyring task failed")?;
+    result.map_err(|e| {
+        let message = match e {
+            keyring::Error::NoEntry => format!(
+                "No password for {} in the OS keyring, run init to store it",
+                user_id
+            ),
+            e => format!("OS keyring is not available: {}", e),
+        };
+        Failure::new(ErrorCode::Config, message).into()
+    })
+}
+
+// keyring feature 없이 build 하면 keyring:<user id> 는 사용할 수 없음
+#[cfg(not(feature = "keyring"))]
+pub async fn store_keyring(_user_id: &str, _user_pw: &str) -> Result<()> {
+    Err(no_keyring())
+}
+
+#[cfg(not(feature = "keyring"))]
+async fn read_keyring(_user_id: &str) -> Result<String> {
+    Err(no_keyring())
+}
+
+#[cfg(not(feature = "keyring"))]
+fn no_keyring() -> anyhow::Error {
+    Failure::new(
+        ErrorCode::Config,
+        "Built without the keyring feature, use --credentials env or command:<command>",
+    )
+    .into()
+}
+
+// --credentials 로 지정하는 기본 제공 provider
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum CredentialSource {
+    // PP_ID, PP_PW
+    #[default]
+    Env,
+    // command:<shell command>
+    Command(String),
+    // keyring:<user id>
+    Keyring(String),
+}
+
+impl FromStr for CredentialSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "env" => Ok(CredentialSource::Env),
+            _ => {
+                let value = |prefix: &str| {
+                    s.strip_prefix(prefix)
+                        .map(str::trim)
+                        .filter(|value| !value.is_empty())
+                        .map(str::to_string)
+                };
+                value("command:")
+                    .map(CredentialSource::Command)
+                    .or_else(|| value("keyring:").map(CredentialSource::Keyring))
+                    .ok_or_else(|| {
+                        format!(
+                            "Invalid credential source, expected env, command:<command> or keyring:<user id>: {}",
+                            s
+                        )
+                    })
+            }
+        }
+    }
+}
+
+impl fmt::Display for CredentialSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CredentialSource::Env => write!(f, "env"),
+            CredentialSource::Command(command) => write!(f, "command:{}", command),
+            CredentialSource::Keyring(user_id) => write!(f, "keyring:{}", user_id),
+        }
+    }
+}
+
+// pipeline 파일의 "credentials" (--credentials 와 같은 형식)
+impl<'de> Deserialize<'de> for CredentialSource {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl CredentialSource {
+    pub fn build(&self) -> Box<dyn CredentialProvider> {
+        match self {
+            CredentialSource::Env => Box::new(EnvProvider),
+            CredentialSource::Command(command) => Box::new(CommandProvider {
+                command: command.clone(),
+            }),
+            CredentialSource::Keyring(user_id) => Box::new(KeyringProvider {
+                user_id: user_id.clone(),
+            }),
+        }
+    }
+}
+
+// 실행 설정 (driver 실행 전 검증)
+pub struct Config {
+    pub user_id: String,
+    pub user_pw: String,
+    pub user_nums: Vec<String>,
+}
+
+impl Config {
+    // provider 의 로그인 정보, 환경 변수의 고객 번호 로드, 검증
+    pub async fn load(provider: &dyn CredentialProvider) -> Result<Self> {
+        Self::for_customers(provider, customer_numbers()?).await
+    }
+
+    // PP_NUMBER 대신 지정한 고객 번호 (pipeline 의 customers)
+    pub async fn for_customers(
+        provider: &dyn CredentialProvider,
+        user_nums: Vec<String>,
+    ) -> Result<Self> {
+        let user_nums: Vec<String> = user_nums
+            .into_iter()
+            .filter(|num| !num.trim().is_empty())
+            .collect();
+        ensure!(!user_nums.is_empty(), "No customer number");
+        let user_nums = user_nums
+            .iter()
+            .map(|num| customer::validate(num))
+            .collect::<Result<_>>()?;
+        let Credentials { user_id, user_pw } = provider.credentials().await.context(format!(
+            "Failed to get credentials from {}",
+            provider.name()
+        ))?;
+        Ok(Self {
+            user_id,
+            user_pw,
+            user_nums,
+        })
+    }
+}
+
+// 고객 번호 목록 (PP_NUMBER, 콤마 구분)
+pub fn customer_numbers() -> Result<Vec<String>> {
+    let user_nums: Vec<String> = env::var("PP_NUMBER")
+        .context("PP_NUMBER is not set")?
+        .split(',')
+        .map(|num| num.trim().to_string())
+        .filter(|num| !num.is_empty())
+        .collect();
+
+    ensure!(!user_nums.is_empty(), "PP_NUMBER has no customer number");
+    user_nums
+        .iter()
+        .map(|num| customer::validate(num).context("Invalid PP_NUMBER"))
+        .collect()
+}
+
+// 설정 JSON 파일 (pipeline, pre-login script 등) 읽기
+// 실패하면 잘못된 key 경로와 줄, 열 (예: Invalid pipelines file p.json at [1].sinks[0]: ... at line 9 column 21)
+pub fn from_json<T: DeserializeOwned>(text: &str, path: &Path, what: &str) -> Result<T> {
+    let deserializer = &mut serde_json::Deserializer::from_str(text);
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let location = e.path().to_string();
+        anyhow::Error::new(e.into_inner()).context(Failure::new(
+            ErrorCode::Config,
+            format!("Invalid {} {} at {}", what, path.display(), location),
+        ))
+    })
+}
@@ -0,0 +1,116 @@
+use anyhow::{ensure, Result};
+use serde::Serialize;
+
+// 고객 번호 자리수 (portal 표시 형식: 00-0000-0000)
+const CUSTOMER_NUMBER_LEN: usize = 10;
+
+// 숫자만 남김 (하이픈, 공백 등 표시 형식 제거)
+pub fn normalize(number: &str) -> String {
+    number.chars().filter(char::is_ascii_digit).collect()
+}
+
+// 설정된 고객 번호 검증 후 정규화 값 반환
+// (검증 숫자 규칙은 공개되지 않아 구성 문자, 자리수만 확인)
+pub fn validate(number: &str) -> Result<String> {
+    ensure!(
+        number
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '-' || c.is_whitespace()),
+        "Invalid customer number '{}': only digits and hyphens are allowed",
+        number
+    );
+    let normalized = normalize(number);
+    ensure!(
+        normalized.len() == CUSTOMER_NUMBER_LEN,
+        "Invalid customer number '{}': expected {} digits, got {}",
+        number,
+        CUSTOMER_NUMBER_LEN,
+        normalized.len()
+    );
+    Ok(normalized)
+}
+
+// portal 목록 표시 문자열이 고객 번호를 가리키는지 (별칭이 붙어도 번호 부분으로 비교)
+pub fn matches(display: &str, number: &str) -> bool {
+    normalize(display).contains(&normalize(number))
+}
+
+// portal 고객 번호 목록 항목
+#[derive(Debug, Clone, Serialize)]
+pub struct Meter {
+    pub customer_number: String,
+    // 번호를 제외한 표시 문자열 (별칭, 주소)
+    pub display_name: String,
+}
+
+impl Meter {
+    // 표시 문자열에서 고객 번호 자리수의 숫자열을 찾아 분리
+    pub fn from_display(display: &str) -> Option<Self> {
+        let token = display
+            .split(|c: char| !(c.is_ascii_digit() || c == '-'))
+            .find(|token| normalize(token).len() == CUSTOMER_NUMBER_LEN)?;
+        let display_name = display
+            .replacen(token, "", 1)
+            .trim_matches(|c: char| c.is_whitespace() || "()[]-:/".contains(c))
+            .to_string();
+        Some(Self {
+            customer_number: normalize(token),
+            display_name,
+        })
+    }
+}
+
+// 고객 정보 조회 화면의 계약 정보 (건물 자료와 연결할 때 사용)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CustomerInfo {
+    pub customer_number: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    // 계약종별 (주택용, 일반용, 산업용 등)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contract_type: Option<String>,
+    // 공급방식 (저압, 고압 등)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub supply_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meter_number: Option<String>,
+}
+
+impl CustomerInfo {
+    // 화면의 항목 이름, 값 쌍에서 아는 항목만 채움 (고객 번호는 화면 값이 없으면 조회한 번호)
+    pub fn from_fields(number: &str, fields: &[(String, String)]) -> Self {
+        let mut info = Self {
+            customer_number: normalize(number),
+            ..Self::default()
+        };
+        for (label, value) in fields {
+            let label: String = label.split_whitespace().collect();
+            let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+            if value.is_empty() {
+                continue;
+            }
+            let slot = if label.contains("고객번호") {
+                if normalize(&value).len() == CUSTOMER_NUMBER_LEN {
+                    info.customer_number = normalize(&value);
+                }
+                continue;
+            } else if ["주소", "사용장소", "설치장소"]
+                .iter()
+                .any(|name| label.contains(name))
+            {
+                &mut info.address
+            } else if label.contains("계약종별") {
+                &mut info.contract_type
+            } else if label.contains("공급방식") {
+                &mut info.supply_type
+            } else if label.contains("계기번호") {
+                &mut info.meter_number
+            } else {
+                continue;
+            };
+            // 같은 항목이 여러 번 나오면 처음 값
+            slot.get_or_insert(value);
+        }
+        info
+    }
+}
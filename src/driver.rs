@@ -0,0 +1,118 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::process::{Child, Command};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::error::{ErrorCode, Failure};
+
+pub mod resolver;
+
+// 실행 중인 ChromeDriver (panic, signal 때 남은 driver 를 한꺼번에 종료)
+static RUNNING: Mutex<BTreeMap<u64, Child>> = Mutex::new(BTreeMap::new());
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+// ChromeDriver process, drop 시 driver 와 driver 가 띄운 Chrome 종료
+// (? 로 빠져나가거나 실행이 취소되어도 driver 가 남지 않음)
+pub struct DriverGuard {
+    id: u64,
+}
+
+impl DriverGuard {
+    pub fn spawn(path: &Path, port: u16) -> Result<Self> {
+        let mut command = Command::new(path);
+        command.arg(format!("--port={}", port));
+        // driver 가 띄운 Chrome 까지 같이 종료하도록 별도 process group
+        #[cfg(unix)]
+        std::os::unix::process::CommandExt::process_group(&mut command, 0);
+        let child = command.spawn().context(Failure::new(
+            ErrorCode::Driver,
+            "Failed to start ChromeDriver",
+        ))?;
+        let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+        running().insert(id, child);
+        Ok(Self { id })
+    }
+
+    // driver process 가 이미 종료됐는지 (잘못된 binary, port 충돌 등으로 바로 죽은 경우)
+    pub fn exited(&self) -> bool {
+        running()
+            .get_mut(&self.id)
+            .is_none_or(|child| !matches!(child.try_wait(), Ok(None)))
+    }
+
+    // 조회를 마친 뒤 결과 출력 전에 종료 (drop 과 같음)
+    pub fn kill(self) {
+        drop(self);
+    }
+}
+
+impl Drop for DriverGuard {
+    fn drop(&mut self) {
+        let child = running().remove(&self.id);
+        if let Some(child) = child {
+            terminate(child);
+        }
+    }
+}
+
+// 남은 driver 전부 종료 (panic hook, 실행 종료 시)
+pub fn kill_all() {
+    let drivers = std::mem::take(&mut *running());
+    for child in drivers.into_values() {
+        terminate(child);
+    }
+}
+
+// release build 는 panic = "abort" 라 drop 이 실행되지 않으므로 panic hook 에서 종료
+pub fn install_panic_hook() {
+    let default = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        kill_all();
+        default(info);
+    }));
+}
+
+// Ctrl-C (SIGINT), SIGTERM 을 받을 때까지 대기, 받은 signal 이름 반환
+pub async fn shutdown_signal() -> &'static str {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        if let Ok(mut terminate) = signal(SignalKind::terminate()) {
+            return tokio::select! {
+                _ = ctrl_c() => "SIGINT",
+                _ = terminate.recv() => "SIGTERM",
+            };
+        }
+    }
+    ctrl_c().await;
+    "SIGINT"
+}
+
+// signal handler 를 등록하지 못하면 계속 대기 (실행은 그대로 진행)
+async fn ctrl_c() {
+    if tokio::signal::ctrl_c().await.is_err() {
+        std::future::pending::<()>().await;
+    }
+}
+
+// panic 중에도 종료할 수 있도록 poison 무시
+fn running() -> MutexGuard<'static, BTreeMap<u64, Child>> {
+    RUNNING.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn terminate(mut child: Child) {
+    // process group 전체 (Chrome 포함) 에 SIGTERM 후 driver 는 강제 종료
+    #[cfg(unix)]
+    if let Ok(pid) = i32::try_from(child.id()) {
+        // SAFETY: 직접 띄운 process group 에 signal 만 보냄
+        unsafe {
+            libc::kill(-pid, libc::SIGTERM);
+        }
+    }
+    if let Err(e) = child.kill() {
+        eprintln!("Failed to kill ChromeDriver: {}", e);
+    }
+    let _ = child.wait();
+}
@@ -0,0 +1,285 @@
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::env;
+use std::fs;
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use tokio::process::Command;
+use tokio::sync::OnceCell;
+
+use crate::error::{ErrorCode, Failure};
+
+// Chrome for Testing 의 build (major.minor.build) 별 최신 chromedriver download 목록 (Chrome 115 이후)
+const KNOWN_VERSIONS_URL: &str = "https://googlechromelabs.github.io/chrome-for-testing/latest-patch-versions-per-build-with-downloads.json";
+
+// $PATH 에 없을 때 확인하는 설치 위치
+const FALLBACK_PATHS: &[&str] = &[
+    "/opt/homebrew/bin/chromedriver",
+    "/usr/local/bin/chromedriver",
+    "/usr/lib/chromium/chromedriver",
+    "/usr/lib/chromium-browser/chromedriver",
+];
+
+// 설치된 Chrome 버전을 확인할 실행 파일 (--version)
+#[cfg(target_os = "macos")]
+const CHROME_BINARIES: &[&str] = &[
+    "/Applications/Google Chrome.app/Contents/MacOS/Google Chrome",
+    "/Applications/Chromium.app/Contents/MacOS/Chromium",
+];
+#[cfg(not(target_os = "macos"))]
+const CHROME_BINARIES: &[&str] = &[
+    "google-chrome",
+    "google-chrome-stable",
+    "chromium",
+    "chromium-browser",
+];
+
+#[cfg(windows)]
+const DRIVER_FILE: &str = "chromedriver.exe";
+#[cfg(not(windows))]
+const DRIVER_FILE: &str = "chromedriver";
+
+// chromedriver 찾는 방법 (실행 시작 시 설정)
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    // --chromedriver, $CHROMEDRIVER (지정하면 그대로 사용)
+    pub path: Option<PathBuf>,
+    // 설치된 Chrome 과 맞는 driver 가 없으면 download (--download-driver)
+    pub download: bool,
+    // download 한 driver 위치 (data dir 의 drivers)
+    pub cache_dir: Option<PathBuf>,
+}
+
+static SETTINGS: Mutex<Option<Settings>> = Mutex::new(None);
+// 실행 중 한 번만 찾음
+static RESOLVED: OnceCell<PathBuf> = OnceCell::const_new();
+
+pub fn configure(settings: Settings) {
+    *SETTINGS.lock().unwrap_or_else(PoisonError::into_inner) = Some(settings);
+}
+
+// 사용할 chromedriver 경로
+pub async fn resolve() -> Result<PathBuf> {
+    let settings = SETTINGS
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+        .unwrap_or_default();
+    RESOLVED.get_or_try_init(|| find(&settings)).await.cloned()
+}
+
+// 지정 경로 > $PATH (Chrome 과 major 버전이 맞는지 확인) > download
+async fn find(settings: &Settings) -> Result<PathBuf> {
+    if let Some(path) = &settings.path {
+        if !path.is_file() {
+            return Err(not_found(format!("ChromeDriver not found: {}", path.display())).into());
+        }
+        return Ok(path.clone());
+    }
+
+    let chrome = chrome_version().await;
+    if let Some(installed) = search_path() {
+        let driver = version_of(&installed).await;
+        match (&chrome, &driver) {
+            (Some(chrome), Some(driver)) if major(chrome) != major(driver) => {
+                // 맞지 않는 driver 로는 session 을 만들 수 없으므로 download 하지 않으면 실패
+                if !settings.download {
+                    return Err(Failure::new(
+                        ErrorCode::Driver,
+                        format!(
+                            "ChromeDriver {} at {} does not match Chrome {}, install a matching one or pass --download-driver",
+                            driver,
+                            installed.display(),
+                            chrome
+                        ),
+                    )
+                    .into());
+                }
+                eprintln!(
+                    "ChromeDriver {} at {} does not match Chrome {}",
+                    driver,
+                    installed.display(),
+                    chrome
+                );
+            }
+            _ => return Ok(installed),
+        }
+    }
+
+    if !settings.download {
+        return Err(not_found(
+            "ChromeDriver not found in $CHROMEDRIVER or $PATH, install it or pass --download-driver",
+        )
+        .into());
+    }
+    let chrome = chrome.ok_or_else(|| {
+        not_found("Chrome not found, can not pick a ChromeDriver version to download")
+    })?;
+    let cache_dir = settings
+        .cache_dir
+        .as_deref()
+        .ok_or_else(|| not_found("No data directory to download ChromeDriver into"))?;
+    download(&chrome, cache_dir).await
+}
+
+fn not_found(message: impl Into<String>) -> Failure {
+    Failure::new(ErrorCode::Config, message)
+}
+
+// $PATH 의 chromedriver, 없으면 알려진 설치 위치
+fn search_path() -> Option<PathBuf> {
+    let path = env::var_os("PATH").unwrap_or_default();
+    env::split_paths(&path)
+        .map(|dir| dir.join(DRIVER_FILE))
+        .chain(FALLBACK_PATHS.iter().map(PathBuf::from))
+        .find(|candidate| candidate.is_file())
+}
+
+// 설치된 Chrome 버전 (찾지 못하면 None)
+async fn chrome_version() -> Option<String> {
+    for binary in CHROME_BINARIES {
+        if let Some(version) = version_of(Path::new(binary)).await {
+            return Some(version);
+        }
+    }
+    // Windows 는 실행 파일 대신 registry 의 버전
+    #[cfg(windows)]
+    if let Some(version) = registry_version().await {
+        return Some(version);
+    }
+    None
+}
+
+#[cfg(windows)]
+async fn registry_version() -> Option<String> {
+    let output = Command::new("reg")
+        .args([
+            "query",
+            r"HKCU\Software\Google\Chrome\BLBeacon",
+            "/v",
+            "version",
+        ])
+        .output()
+        .await
+        .ok()?;
+    parse_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+// 실행 파일의 --version 출력 중 버전 (e.g. "Google Chrome 126.0.6478.126", "ChromeDriver 126.0.6478.126 (...)")
+async fn version_of(program: &Path) -> Option<String> {
+    let output = Command::new(program).arg("--version").output().await.ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    parse_version(&String::from_utf8_lossy(&output.stdout))
+}
+
+fn parse_version(text: &str) -> Option<String> {
+    text.split_whitespace()
+        .find(|token| token.contains('.') && token.chars().all(|c| c.is_ascii_digit() || c == '.'))
+        .map(str::to_string)
+}
+
+fn major(version: &str) -> &str {
+    version.split('.').next().unwrap_or(version)
+}
+
+// Chrome for Testing 의 platform 이름
+fn platform() -> Result<&'static str> {
+    match (env::consts::OS, env::consts::ARCH) {
+        ("linux", "x86_64") => Ok("linux64"),
+        ("macos", "aarch64") => Ok("mac-arm64"),
+        ("macos", "x86_64") => Ok("mac-x64"),
+        ("windows", "x86_64") => Ok("win64"),
+        ("windows", "x86") => Ok("win32"),
+        (os, arch) => Err(not_found(format!(
+            "No ChromeDriver download for {}-{}, install it and set $CHROMEDRIVER",
+            os, arch
+        ))
+        .into()),
+    }
+}
+
+// Chrome 의 build 와 맞는 chromedriver 를 cache_dir/<build>/ 에 download (이미 있으면 재사용)
+async fn download(chrome: &str, cache_dir: &Path) -> Result<PathBuf> {
+    let build = chrome.rsplit_once('.').map_or(chrome, |(build, _)| build);
+    let target = cache_dir.join(build).join(DRIVER_FILE);
+    if target.is_file() {
+        return Ok(target);
+    }
+
+    let failed = |message: String| Failure::new(ErrorCode::Driver, message);
+    let platform = platform()?;
+    let http = reqwest::Client::new();
+    let versions: Value = http
+        .get(KNOWN_VERSIONS_URL)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .context(failed("Failed to fetch the ChromeDriver versions".into()))?
+        .json()
+        .await
+        .context(failed("Invalid ChromeDriver versions".into()))?;
+    let url = versions["builds"][build]["downloads"]["chromedriver"]
+        .as_array()
+        .and_then(|downloads| {
+            downloads
+                .iter()
+                .find(|download| download["platform"] == platform)
+        })
+        .and_then(|download| download["url"].as_str())
+        .ok_or_else(|| {
+            not_found(format!(
+                "No ChromeDriver download for Chrome {} on {}",
+                chrome, platform
+            ))
+        })?;
+
+    eprintln!("Downloading ChromeDriver for Chrome {}: {}", chrome, url);
+    let archive = http
+        .get(url)
+        .send()
+        .await
+        .and_then(|response| response.error_for_status())
+        .context(failed(format!("Failed to download {}", url)))?
+        .bytes()
+        .await
+        .context(failed(format!("Failed to download {}", url)))?;
+    let binary = extract_driver(&archive).context(failed(format!("Invalid archive {}", url)))?;
+
+    // process 마다 다른 tmp 에 쓴 뒤 rename (동시에 실행된 다른 process 가 반쯤 쓴 파일을 실행하지 않도록)
+    let dir = target.parent().expect("driver path has a parent");
+    fs::create_dir_all(dir).context(format!("Failed to create directory: {}", dir.display()))?;
+    let tmp = dir.join(format!("{}.{}.tmp", DRIVER_FILE, std::process::id()));
+    fs::write(&tmp, binary).context(format!("Failed to write {}", tmp.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&tmp, fs::Permissions::from_mode(0o755))
+            .context(format!("Failed to make {} executable", tmp.display()))?;
+    }
+    if let Err(e) = fs::rename(&tmp, &target) {
+        let _ = fs::remove_file(&tmp);
+        // 다른 process 가 먼저 rename 했으면 그 driver 사용 (Windows 는 덮어쓰지 못함)
+        if !target.is_file() {
+            return Err(e).context(format!("Failed to write {}", target.display()));
+        }
+    }
+    eprintln!("Saved ChromeDriver to {}", target.display());
+    Ok(target)
+}
+
+// zip 안의 chromedriver-<platform>/chromedriver
+fn extract_driver(archive: &[u8]) -> Result<Vec<u8>> {
+    let mut zip = zip::ZipArchive::new(Cursor::new(archive))?;
+    for index in 0..zip.len() {
+        let mut file = zip.by_index(index)?;
+        if Path::new(file.name()).file_name() == Some(DRIVER_FILE.as_ref()) {
+            let mut binary = Vec::new();
+            file.read_to_end(&mut binary)?;
+            return Ok(binary);
+        }
+    }
+    bail!("{} not found in the archive", DRIVER_FILE)
+}
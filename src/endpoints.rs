@@ -0,0 +1,81 @@
+use anyhow::{anyhow, Context, Result};
+use fantoccini::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+use crate::config;
+
+// 조회 화면 뒤의 XHR/fetch data endpoint (--discover-endpoints 로 수집)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Endpoint {
+    pub method: String,
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post_data: Option<String>,
+    // 응답 MIME type
+    pub mime_type: String,
+}
+
+// 저장된 endpoint 목록 (파일이 없으면 빈 목록)
+pub fn load(path: &Path) -> Result<Vec<Endpoint>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let text = fs::read_to_string(path)
+        .context(format!("Failed to read endpoints: {}", path.display()))?;
+    config::from_json(&text, path, "endpoints file")
+}
+
+// 기존 목록에 병합 후 저장 (method, url, body 기준 중복 제거), 새로 추가된 수 반환
+pub fn save(path: &Path, discovered: Vec<Endpoint>) -> Result<usize> {
+    let mut endpoints = load(path)?;
+    let before = endpoints.len();
+    for endpoint in discovered {
+        let exists = endpoints.iter().any(|stored| {
+            stored.method == endpoint.method
+                && stored.url == endpoint.url
+                && stored.post_data == endpoint.post_data
+        });
+        if !exists {
+            endpoints.push(endpoint);
+        }
+    }
+    fs::write(path, serde_json::to_vec_pretty(&endpoints)?)
+        .context(format!("Failed to write endpoints: {}", path.display()))?;
+    Ok(endpoints.len() - before)
+}
+
+// 로그인된 browser 세션의 cookie 로 endpoint 직접 호출 (JSON 이 아니면 문자열)
+pub async fn fetch(client: &Client, endpoint: &Endpoint) -> Result<Value> {
+    let script = r#"
+        const [url, method, contentType, body, done] = arguments;
+        const headers = contentType ? { 'Content-Type': contentType } : {};
+        fetch(url, { method, headers, body, credentials: 'same-origin' })
+            .then(response => response.text().then(text => done({ status: response.status, text })))
+            .catch(error => done({ error: String(error) }));
+        "#;
+    let args = vec![
+        json!(endpoint.url),
+        json!(endpoint.method),
+        json!(endpoint.content_type),
+        json!(endpoint.post_data),
+    ];
+    let response = client
+        .execute_async(script, args)
+        .await
+        .context(format!("Failed to fetch {}", endpoint.url))?;
+
+    if let Some(error) = response["error"].as_str() {
+        return Err(anyhow!("Failed to fetch {}: {}", endpoint.url, error));
+    }
+    let status = response["status"].as_u64().unwrap_or_default();
+    if !(200..300).contains(&status) {
+        return Err(anyhow!("{} returned status {}", endpoint.url, status));
+    }
+    let text = response["text"].as_str().unwrap_or_default();
+    Ok(serde_json::from_str(text).unwrap_or_else(|_| Value::String(text.to_string())))
+}
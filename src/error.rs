@@ -0,0 +1,129 @@
+use anyhow::Error;
+use serde::Serialize;
+use serde_json::json;
+use std::fmt;
+use std::path::PathBuf;
+
+// 실패 분류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    Config,
+    Driver,
+    Navigation,
+    ElementNotFound,
+    Timeout,
+    Hung,
+    Store,
+    // sink 연결, 권한 확인 실패
+    Sink,
+    // 로그인 후 다른 계정 화면 (cache 된 session 등)
+    WrongAccount,
+    // 로그인 화면의 CAPTCHA (사람이 풀어야 함)
+    CaptchaRequired,
+    // 일부 고객 번호만 실패
+    PartialFailure,
+    // 저장된 data 가 최신 기준을 넘음 (status)
+    Stale,
+    // canary 가 portal 변경을 감지했거나 통과한 canary 가 없음
+    Canary,
+    // SIGINT, SIGTERM 으로 중단 (driver 종료 후)
+    Interrupted,
+    Unknown,
+}
+
+impl ErrorCode {
+    // 재시도로 해결될 수 있는 실패
+    pub fn retryable(self) -> bool {
+        matches!(
+            self,
+            ErrorCode::Driver | ErrorCode::Navigation | ErrorCode::Timeout | ErrorCode::Hung
+        )
+    }
+}
+
+// 재시도로 해결될 수 있는 에러인지
+pub fn is_retryable(error: &Error) -> bool {
+    code(error).retryable()
+}
+
+// 프로세스 종료 코드 (일부 고객 번호만 실패하면 2, 오래된 data 는 3, signal 로 중단되면 130)
+pub fn exit_code(error: &Error) -> i32 {
+    match code(error) {
+        ErrorCode::PartialFailure => 2,
+        ErrorCode::Stale => 3,
+        ErrorCode::Interrupted => 130,
+        _ => 1,
+    }
+}
+
+// 분류, selector 가 포함된 실패
+#[derive(Debug)]
+pub struct Failure {
+    pub code: ErrorCode,
+    pub selector: Option<String>,
+    pub artifacts: Vec<PathBuf>,
+    message: String,
+}
+
+impl Failure {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            selector: None,
+            artifacts: Vec::new(),
+            message: message.into(),
+        }
+    }
+
+    pub fn with_selector(mut self, selector: impl fmt::Debug) -> Self {
+        self.selector = Some(format!("{:?}", selector));
+        self
+    }
+
+    pub fn with_artifacts(mut self, artifacts: Vec<PathBuf>) -> Self {
+        self.artifacts.extend(artifacts);
+        self
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Failure {}
+
+// 실패한 단계 (context 로 부착)
+#[derive(Debug, Clone, Copy)]
+pub struct Step(pub &'static str);
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Failed at step '{}'", self.0)
+    }
+}
+
+// 에러 분류 (Failure 가 없으면 Unknown)
+pub fn code(error: &Error) -> ErrorCode {
+    error
+        .downcast_ref::<Failure>()
+        .map_or(ErrorCode::Unknown, |failure| failure.code)
+}
+
+// 에러 -> JSON object
+pub fn to_json(error: &Error) -> serde_json::Value {
+    let failure = error.downcast_ref::<Failure>();
+    let code = code(error);
+
+    json!({
+        "code": code,
+        "step": error.downcast_ref::<Step>().map(|step| step.0),
+        "selector": failure.and_then(|failure| failure.selector.as_deref()),
+        "retryable": code.retryable(),
+        "message": error.to_string(),
+        "causes": error.chain().skip(1).map(|cause| cause.to_string()).collect::<Vec<_>>(),
+        "artifacts": failure.map_or(&[][..], |failure| &failure.artifacts[..]),
+    })
+}
@@ -0,0 +1,129 @@
+use anyhow::{bail, Context, Result};
+use calamine::{open_workbook_auto, Data, DataType, Reader};
+use chrono::{Datelike, NaiveDate};
+use rust_decimal::Decimal;
+use std::path::Path;
+
+use crate::warnings::{self, WarningKind};
+use crate::{parse_date, parse_paid, parse_use_kwh, PpData};
+
+// header 를 찾을 최대 행 수 (제목, 조회 조건 행 다음)
+const HEADER_SEARCH_ROWS: usize = 20;
+
+// column 위치
+struct Columns {
+    claim_date: usize,
+    usage: usize,
+    paid: usize,
+}
+
+// header 행에서 청구년월, 사용량, 청구금액 column 찾기
+fn find_columns(row: &[Data]) -> Option<Columns> {
+    let position = |labels: &[&str]| {
+        row.iter().position(|cell| {
+            let text: String = cell.to_string().split_whitespace().collect();
+            labels.iter().any(|label| text.contains(label))
+        })
+    };
+    Some(Columns {
+        claim_date: position(&["청구년월", "청구월", "년월"])?,
+        usage: position(&["사용량"])?,
+        paid: position(&["청구금액", "청구요금"])?,
+    })
+}
+
+// KEPCO 에서 내려받은 월별 청구 요금 Excel (xls, xlsx) -> row
+// 합계 행 등 청구년월이 없는 행은 건너뜀
+pub fn read(path: &Path) -> Result<Vec<PpData>> {
+    let mut workbook =
+        open_workbook_auto(path).context(format!("Failed to open {}", path.display()))?;
+
+    for (sheet, range) in workbook.worksheets() {
+        let rows: Vec<&[Data]> = range.rows().collect();
+        let Some((header, columns)) = rows
+            .iter()
+            .take(HEADER_SEARCH_ROWS)
+            .enumerate()
+            .find_map(|(index, row)| find_columns(row).map(|columns| (index, columns)))
+        else {
+            continue;
+        };
+
+        let mut data = Vec::new();
+        for (index, row) in rows.iter().enumerate().skip(header + 1) {
+            let cell = |column: usize| row.get(column).unwrap_or(&Data::Empty);
+            let Some(claim_date) = claim_date(cell(columns.claim_date)) else {
+                if !row.iter().all(DataType::is_empty) {
+                    warnings::emit(
+                        WarningKind::SkippedRow,
+                        format!("Skipping {} row {} without a claim month", sheet, index + 1),
+                    );
+                }
+                continue;
+            };
+            data.push(PpData {
+                claim_date,
+                usage: usage(cell(columns.usage)).context(format!(
+                    "Invalid usage in {} row {}",
+                    sheet,
+                    index + 1
+                ))?,
+                paid: paid(cell(columns.paid)).context(format!(
+                    "Invalid amount in {} row {}",
+                    sheet,
+                    index + 1
+                ))?,
+                payment_status: None,
+                provisional: false,
+                breakdown: None,
+                provenance: None,
+            });
+        }
+        return Ok(data);
+    }
+    bail!(
+        "No sheet with 청구년월, 사용량 and 청구금액 columns in {}",
+        path.display()
+    )
+}
+
+// "2024년 07월", "2024-07", "2024.07", 날짜 서식 cell
+fn claim_date(cell: &Data) -> Option<NaiveDate> {
+    if let Some(date) = cell.as_date() {
+        return date.with_day(1);
+    }
+    let text = cell.as_string()?;
+    let text = text.trim();
+    if text.contains('년') {
+        return parse_date(text).ok();
+    }
+    let digits: String = text.chars().filter(char::is_ascii_digit).collect();
+    match digits.len() {
+        6 | 8 => NaiveDate::parse_from_str(&format!("{}01", &digits[..6]), "%Y%m%d").ok(),
+        _ => None,
+    }
+}
+
+fn usage(cell: &Data) -> Result<f64> {
+    match cell {
+        Data::Empty => Ok(0.0),
+        cell => match cell
+            .get_float()
+            .or(cell.get_int().map(|value| value as f64))
+        {
+            Some(value) => Ok(value),
+            None => parse_use_kwh(&cell.to_string()),
+        },
+    }
+}
+
+fn paid(cell: &Data) -> Result<Decimal> {
+    match cell {
+        Data::Empty => Ok(Decimal::ZERO),
+        Data::Int(value) => Ok(Decimal::from(*value)),
+        Data::Float(value) => {
+            Decimal::try_from(*value).context(format!("Invalid amount: {}", value))
+        }
+        cell => parse_paid(&cell.to_string()),
+    }
+}
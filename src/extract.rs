@@ -0,0 +1,492 @@
+use anyhow::{Context, Result};
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta};
+#[cfg(feature = "browser")]
+use fantoccini::Client;
+use rust_decimal::Decimal;
+use scraper::{ElementRef, Html, Selector};
+#[cfg(feature = "browser")]
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "browser")]
+use crate::warnings::{self, WarningKind};
+use crate::{
+    parse_date, parse_paid, parse_payment_status, parse_use_kwh, split_paid, DailyUsage,
+    HourlyUsage, PpData, Provenance,
+};
+
+// 월별 청구 요금 표의 row
+const ROW_SELECTOR: &str = "tbody > tr[id]";
+// 청구 월, 사용량, 청구 요금 column (1부터)
+const CLAIM_DATE_COLUMN: usize = 1;
+const USAGE_COLUMN: usize = 4;
+const PAID_COLUMN: usize = 8;
+// 일별 사용량 표의 일자, 사용량 column (1부터)
+const DAILY_DATE_COLUMN: usize = 1;
+const DAILY_USAGE_COLUMN: usize = 2;
+// 시간대별 사용량 하루 구간 수 (1시간, 15분)
+const HOURLY_INTERVALS: [usize; 2] = [24, 96];
+
+// 연도 page 에서 가져온 표 HTML (browser 단계 결과, parse 단계 입력)
+#[derive(Debug, Clone)]
+pub struct RawPage {
+    pub html: String,
+    // 조회한 연도 view
+    pub year_view: Option<i32>,
+}
+
+// 연도 page 표 HTML 보관 (--archive-raw), <root>/<고객 번호>/<연도>/<run id>.html
+#[cfg(feature = "browser")]
+#[derive(Debug, Clone)]
+pub struct RawArchive {
+    dir: PathBuf,
+    run_id: String,
+}
+
+#[cfg(feature = "browser")]
+impl RawArchive {
+    pub fn new(root: &Path, customer_number: &str, run_id: &str) -> Self {
+        Self {
+            dir: root.join(customer_number),
+            run_id: run_id.to_string(),
+        }
+    }
+
+    // 보관 실패는 조회를 멈추지 않음
+    pub fn keep(&self, page: &RawPage) {
+        if let Err(e) = self.save(page) {
+            warnings::emit(
+                WarningKind::Artifact,
+                format!("Failed to archive the raw page: {:#}", e),
+            );
+        }
+    }
+
+    fn save(&self, page: &RawPage) -> Result<PathBuf> {
+        let year = page
+            .year_view
+            .map_or_else(|| "unknown".to_string(), |year| year.to_string());
+        let dir = self.dir.join(year);
+        fs::create_dir_all(&dir)
+            .context(format!("Failed to create directory: {}", dir.display()))?;
+        let path = dir.join(format!("{}.html", self.run_id));
+        fs::write(&path, &page.html).context(format!("Failed to write {}", path.display()))?;
+        Ok(path)
+    }
+}
+
+// 보관된 page (고객 번호 -> 오래된 실행부터 run id, page)
+pub fn archived(root: &Path) -> Result<BTreeMap<String, Vec<(String, RawPage)>>> {
+    let mut customers: BTreeMap<String, Vec<(String, RawPage)>> = BTreeMap::new();
+    if !root.exists() {
+        return Ok(customers);
+    }
+    for customer in subdirs(root)? {
+        let customer_number = file_name(&customer);
+        let pages = customers.entry(customer_number).or_default();
+        for year in subdirs(&customer)? {
+            let year_view = file_name(&year).parse().ok();
+            for entry in
+                fs::read_dir(&year).context(format!("Failed to read {}", year.display()))?
+            {
+                let path = entry?.path();
+                if path.extension().is_some_and(|ext| ext == "html") {
+                    let html = fs::read_to_string(&path)
+                        .context(format!("Failed to read {}", path.display()))?;
+                    let run_id = path
+                        .file_stem()
+                        .map(|stem| stem.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    pages.push((run_id, RawPage { html, year_view }));
+                }
+            }
+        }
+        // run id 는 ULID 라 문자열 순서가 실행 순서
+        pages.sort_by(|a, b| a.0.cmp(&b.0));
+    }
+    Ok(customers)
+}
+
+fn subdirs(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut dirs = Vec::new();
+    for entry in fs::read_dir(dir).context(format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            dirs.push(path);
+        }
+    }
+    dirs.sort();
+    Ok(dirs)
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+// browser 단계: 표 HTML 을 script 한 번으로 가져옴 (row, cell 마다 WebDriver 왕복하지 않음)
+#[cfg(feature = "browser")]
+pub async fn grab(client: &Client, table_xpath: &str, year_view: Option<i32>) -> Result<RawPage> {
+    let script = r#"
+        const table = document.evaluate(arguments[0], document, null, XPathResult.FIRST_ORDERED_NODE_TYPE, null).singleNodeValue;
+        if (table === null) {
+            throw new Error('Table element not found');
+        }
+        return table.outerHTML;
+    "#;
+    let html = client
+        .execute(script, vec![Value::String(table_xpath.to_string())])
+        .await
+        .context("Failed to read the claims table")?;
+    Ok(RawPage {
+        html: html
+            .as_str()
+            .context("Expected HTML from the claims table script")?
+            .to_string(),
+        year_view,
+    })
+}
+
+// parse 단계 결과 (경고는 실행 scope 가 없는 thread 에서 만들어져 호출한 task 에서 기록)
+#[derive(Debug)]
+pub struct Parsed<T = PpData> {
+    rows: Vec<T>,
+    skipped: Vec<String>,
+}
+
+impl<T> Parsed<T> {
+    pub fn rows(&self) -> &[T] {
+        &self.rows
+    }
+
+    // 읽지 못해 건너뛴 row 의 이유
+    pub fn skipped(&self) -> &[String] {
+        &self.skipped
+    }
+
+    // 건너뛴 row 경고 기록 후 row 반환
+    #[cfg(feature = "browser")]
+    pub fn into_rows(self) -> Vec<T> {
+        for message in self.skipped {
+            warnings::emit(WarningKind::SkippedRow, message);
+        }
+        self.rows
+    }
+}
+
+// parse 단계: blocking thread 에서 parsing (browser 단계는 다음 연도 조회를 계속 진행)
+#[cfg(feature = "browser")]
+pub async fn parse(page: RawPage) -> Result<Parsed> {
+    tokio::task::spawn_blocking(move || parse_page(&page))
+        .await
+        .context("Parse task failed")
+}
+
+// 표 HTML -> row (읽지 못한 row 는 건너뜀)
+pub fn parse_page(page: &RawPage) -> Parsed {
+    // tbody 만 가져와도 parsing 되도록 table 로 감쌈
+    let document = Html::parse_fragment(&format!("<table>{}</table>", page.html));
+    let rows = Selector::parse(ROW_SELECTOR).expect("valid row selector");
+    let cells = Selector::parse("td").expect("valid cell selector");
+    let span = Selector::parse("a span").expect("valid span selector");
+
+    let mut parsed = Parsed {
+        rows: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for row in document.select(&rows) {
+        let row_cells: Vec<ElementRef> = row
+            .children()
+            .filter_map(ElementRef::wrap)
+            .filter(|cell| cells.matches(cell))
+            .collect();
+        let cell = |column: usize| row_cells.get(column - 1).copied();
+
+        let claim_date = cell(CLAIM_DATE_COLUMN)
+            .and_then(|cell| cell.select(&span).next())
+            .map(text);
+        let usage = cell(USAGE_COLUMN).map(text);
+        let paid = cell(PAID_COLUMN).map(text);
+        match parse_row(claim_date, usage, paid, page.year_view) {
+            Ok(entry) => parsed.rows.push(entry),
+            Err(e) => parsed
+                .skipped
+                .push(format!("Failed to extract data: {}", e)),
+        }
+    }
+    parsed
+}
+
+// 일별 사용량 표 HTML -> 조회한 달의 일자별 사용량 (합계 등 일자가 없는 row 는 무시)
+pub fn parse_daily_page(html: &str, month: NaiveDate) -> Parsed<DailyUsage> {
+    let document = Html::parse_fragment(&format!("<table>{}</table>", html));
+    let rows = Selector::parse("tbody > tr").expect("valid row selector");
+    let cells = Selector::parse("td").expect("valid cell selector");
+
+    let mut parsed = Parsed {
+        rows: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for row in document.select(&rows) {
+        let row_cells: Vec<String> = row
+            .children()
+            .filter_map(ElementRef::wrap)
+            .filter(|cell| cells.matches(cell))
+            .map(text)
+            .collect();
+        let Some(date) = row_cells.get(DAILY_DATE_COLUMN - 1) else {
+            continue;
+        };
+        if !date.chars().any(|c| c.is_ascii_digit()) {
+            continue;
+        }
+        let usage = row_cells.get(DAILY_USAGE_COLUMN - 1);
+        let entry = parse_day(date, month).and_then(|date| {
+            let usage = usage.context("usage cell missing")?;
+            Ok(DailyUsage {
+                date,
+                usage_kwh: parse_use_kwh(usage)?,
+            })
+        });
+        match entry {
+            Ok(entry) => parsed.rows.push(entry),
+            Err(e) => parsed
+                .skipped
+                .push(format!("Failed to extract daily usage '{}': {:#}", date, e)),
+        }
+    }
+    parsed
+}
+
+// 일자 cell (e.g. "2024-05-01", "05.01", "1일") -> 날짜, 빠진 연도, 월은 조회한 달
+fn parse_day(value: &str, view: NaiveDate) -> Result<NaiveDate> {
+    let numbers: Vec<u32> = value
+        .split(|c: char| !c.is_ascii_digit())
+        .filter(|part| !part.is_empty())
+        .map(str::parse)
+        .collect::<Result<_, _>>()
+        .context("Failed to parse day")?;
+    let (year, month, day) = match numbers[..] {
+        [year, month, day] => (year as i32, month, day),
+        [month, day] => (view.year(), month, day),
+        [day] => (view.year(), view.month(), day),
+        _ => anyhow::bail!("Unexpected day format"),
+    };
+    NaiveDate::from_ymd_opt(year, month, day).context("Invalid day")
+}
+
+// 시간대별 사용량 표 HTML -> 하루의 구간별 사용량
+// row 마다 (시각, 사용량) cell 쌍이 1개 이상 (15분 표는 한 row 에 여러 쌍), 시각이 아닌 cell 은 건너뜀
+pub fn parse_hourly_page(html: &str, date: NaiveDate) -> Parsed<HourlyUsage> {
+    let document = Html::parse_fragment(&format!("<table>{}</table>", html));
+    let rows = Selector::parse("tbody > tr").expect("valid row selector");
+    let cells = Selector::parse("td").expect("valid cell selector");
+
+    let mut parsed = Parsed {
+        rows: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for row in document.select(&rows) {
+        let row_cells: Vec<String> = row
+            .children()
+            .filter_map(ElementRef::wrap)
+            .filter(|cell| cells.matches(cell))
+            .map(text)
+            .collect();
+        let mut row_cells = row_cells.iter().peekable();
+        while let Some(cell) = row_cells.next() {
+            let Some(timestamp) = interval_end(cell, date) else {
+                continue;
+            };
+            let entry = row_cells
+                .next_if(|usage| interval_end(usage, date).is_none())
+                .context("usage cell missing")
+                .and_then(|usage| parse_use_kwh(usage));
+            match entry {
+                Ok(kwh) => parsed.rows.push(HourlyUsage { timestamp, kwh }),
+                Err(e) => parsed.skipped.push(format!(
+                    "Failed to extract hourly usage at '{}': {:#}",
+                    cell, e
+                )),
+            }
+        }
+    }
+    parsed.rows.sort_by_key(|entry| entry.timestamp);
+    parsed.rows.dedup_by_key(|entry| entry.timestamp);
+    if !HOURLY_INTERVALS.contains(&parsed.rows.len()) {
+        parsed.skipped.push(format!(
+            "Expected 24 or 96 hourly intervals on {}, found {}",
+            date,
+            parsed.rows.len()
+        ));
+    }
+    parsed
+}
+
+// 구간 시각 cell (e.g. "00:15", "24:00", "1시") -> 구간이 끝나는 시각, 시각이 아니면 None
+fn interval_end(value: &str, date: NaiveDate) -> Option<NaiveDateTime> {
+    let (hour, minute) = match value.split_once(':') {
+        Some((hour, minute)) => (
+            hour.trim().parse::<u32>().ok()?,
+            minute.trim().parse().ok()?,
+        ),
+        None => (value.strip_suffix('시')?.trim().parse().ok()?, 0),
+    };
+    if minute >= 60 || hour * 60 + minute > 24 * 60 {
+        return None;
+    }
+    let midnight = date.and_time(NaiveTime::MIN);
+    Some(midnight + TimeDelta::minutes(i64::from(hour * 60 + minute)))
+}
+
+// 화면에 보이는 문자열 (공백 정리)
+fn text(element: ElementRef) -> String {
+    element
+        .text()
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// cell 문자열 -> row (없는 cell 은 기본값과 경고)
+fn parse_row(
+    claim_date_row: Option<String>,
+    usage_row: Option<String>,
+    paid_row: Option<String>,
+    year_view: Option<i32>,
+) -> Result<PpData> {
+    let claim_date = claim_date_row
+        .as_deref()
+        .map_or(Ok(Default::default()), parse_date)?;
+    let usage = usage_row.as_deref().map_or(Ok(0.0), parse_use_kwh)?;
+    let paid = paid_row.as_deref().map_or(Ok(Decimal::ZERO), parse_paid)?;
+
+    // parsing 경고
+    let mut warnings = Vec::new();
+    if claim_date_row.is_none() {
+        warnings.push("claim_date cell missing, defaulted".to_string());
+    }
+    if usage_row.is_none() {
+        warnings.push("usage cell missing, defaulted to 0".to_string());
+    }
+    // 금액 뒤 납부 상태 외의 text 는 경고
+    let payment_status = paid_row.as_deref().and_then(parse_payment_status);
+    match paid_row.as_deref() {
+        None => warnings.push("paid cell missing, defaulted to 0".to_string()),
+        Some(raw) => {
+            let (_, suffix) = split_paid(raw);
+            if !suffix.is_empty() && payment_status.is_none() {
+                warnings.push(format!("paid cell has trailing text '{}'", suffix));
+            }
+        }
+    }
+
+    Ok(PpData {
+        claim_date,
+        usage,
+        paid,
+        payment_status,
+        provisional: false,
+        breakdown: None,
+        provenance: Some(Provenance {
+            year_view,
+            raw_claim_date: claim_date_row,
+            raw_usage: usage_row,
+            raw_paid: paid_row,
+            warnings,
+        }),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims_table() -> RawPage {
+        let html = include_str!("test.html");
+        let start = html.find("<table").expect("table in test.html");
+        let end = html.rfind("</table>").expect("table in test.html") + "</table>".len();
+        RawPage {
+            html: html[start..end].to_string(),
+            year_view: Some(2024),
+        }
+    }
+
+    // 구간마다 (시각, 사용량) 쌍, row 당 per_row 쌍
+    fn hourly_table(intervals: usize, per_row: usize) -> String {
+        let minutes = 24 * 60 / intervals;
+        let cells: Vec<String> = (1..=intervals)
+            .map(|i| {
+                let end = i * minutes;
+                format!("<td>{:02}:{:02}</td><td>1.5</td>", end / 60, end % 60)
+            })
+            .collect();
+        let rows: String = cells
+            .chunks(per_row)
+            .map(|chunk| format!("<tr>{}</tr>", chunk.concat()))
+            .collect();
+        format!("<tbody>{}</tbody>", rows)
+    }
+
+    #[test]
+    fn parses_claims_table() {
+        let parsed = parse_page(&claims_table());
+        assert!(parsed.skipped().is_empty(), "{:?}", parsed.skipped());
+        let rows = parsed.rows();
+        assert_eq!(rows.len(), 12);
+
+        let first = &rows[0];
+        assert_eq!(
+            first.claim_date,
+            NaiveDate::from_ymd_opt(2024, 5, 1).unwrap()
+        );
+        assert_eq!(first.usage, 102_300.0);
+        assert_eq!(first.paid, Decimal::from(13_190_990));
+        assert_eq!(first.payment_status, None);
+
+        let last = &rows[11];
+        assert_eq!(
+            last.claim_date,
+            NaiveDate::from_ymd_opt(2023, 6, 1).unwrap()
+        );
+        assert_eq!(last.usage, 197_268.0);
+        assert_eq!(last.paid, Decimal::from(25_253_100));
+
+        let provenance = first.provenance.as_ref().unwrap();
+        assert_eq!(provenance.year_view, Some(2024));
+        assert_eq!(provenance.raw_claim_date.as_deref(), Some("2024년 05월"));
+        assert!(provenance.warnings.is_empty());
+    }
+
+    #[test]
+    fn parses_hourly_intervals() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        for (intervals, per_row) in [(24, 1), (96, 4)] {
+            let parsed = parse_hourly_page(&hourly_table(intervals, per_row), date);
+            assert!(parsed.skipped().is_empty(), "{:?}", parsed.skipped());
+            assert_eq!(parsed.rows().len(), intervals);
+            // 마지막 구간 24:00 은 다음 날 00:00
+            assert_eq!(
+                parsed.rows().last().unwrap().timestamp,
+                NaiveDate::from_ymd_opt(2024, 5, 2)
+                    .unwrap()
+                    .and_time(NaiveTime::MIN)
+            );
+        }
+    }
+
+    #[test]
+    fn flags_unexpected_interval_count() {
+        let date = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+        for intervals in [12, 48] {
+            let parsed = parse_hourly_page(&hourly_table(intervals, 2), date);
+            assert_eq!(parsed.rows().len(), intervals);
+            assert_eq!(parsed.skipped().len(), 1);
+            assert!(parsed.skipped()[0].contains(&format!("found {}", intervals)));
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use chrono::{Datelike, NaiveDate};
+use clap::ValueEnum;
+
+use crate::{customer, PpData};
+
+// 진행 중인 (아직 청구되지 않은) 당월 row 처리
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum CurrentMonth {
+    // 포함하고 provisional 표시
+    #[default]
+    Provisional,
+    // 당월 row 제외
+    Exclude,
+    // 청구가 확정된 row 만 (당월, 청구 금액이 비어 있는 row 제외)
+    Final,
+}
+
+impl CurrentMonth {
+    // 연도 page 에 적용 (저장, 출력 전)
+    pub fn apply(self, page: &mut Vec<PpData>, today: NaiveDate) {
+        let current_month = today.with_day(1).unwrap_or(today);
+        for entry in page.iter_mut() {
+            entry.provisional = entry.claim_date >= current_month;
+        }
+        match self {
+            CurrentMonth::Provisional => {}
+            CurrentMonth::Exclude => page.retain(|entry| !entry.provisional),
+            CurrentMonth::Final => page.retain(|entry| !entry.provisional && billed(entry)),
+        }
+    }
+}
+
+// 청구 금액 칸에 값이 있는 row (원본 문자열이 없으면 저장소에서 읽은 확정 row)
+fn billed(entry: &PpData) -> bool {
+    entry.provenance.as_ref().is_none_or(|provenance| {
+        provenance
+            .raw_paid
+            .as_deref()
+            .is_some_and(|raw| !raw.trim().is_empty())
+    })
+}
+
+// 출력 filter (parsing, 저장 이후 적용)
+#[derive(Debug, Clone, Default)]
+pub struct RowFilter {
+    pub min_usage: Option<f64>,
+    pub months: Vec<u32>,
+    pub customers: Vec<String>,
+    // 청구 월 범위 (--from, --to, 각 월 1일)
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl RowFilter {
+    pub fn matches_customer(&self, customer_number: &str) -> bool {
+        self.customers.is_empty()
+            || self
+                .customers
+                .iter()
+                .any(|num| customer::normalize(num) == customer::normalize(customer_number))
+    }
+
+    pub fn matches(&self, entry: &PpData) -> bool {
+        self.min_usage.is_none_or(|min| entry.usage >= min)
+            && (self.months.is_empty() || self.months.contains(&entry.claim_date.month()))
+            && self.from.is_none_or(|from| entry.claim_date >= from)
+            && self.to.is_none_or(|to| entry.claim_date <= to)
+    }
+}
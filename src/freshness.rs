@@ -0,0 +1,173 @@
+use anyhow::Result;
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, Utc};
+use serde::Serialize;
+use std::collections::BTreeSet;
+#[cfg(feature = "server")]
+use std::fmt::Write as _;
+
+use crate::store::Store;
+
+// data 가 최신인지 판단하는 기준 (--stale-after-hours, --max-month-lag)
+#[derive(Debug, Clone, Copy)]
+pub struct Thresholds {
+    // 마지막 성공 조회 후 허용 시간
+    pub scrape_age_hours: u64,
+    // 이번 달과 저장된 최근 청구 월의 허용 차이 (개월)
+    pub month_lag: u32,
+}
+
+// 고객 번호 data 상태 (기준을 하나라도 넘으면 red)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Health {
+    Green,
+    Red,
+}
+
+impl Health {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Health::Green => "green",
+            Health::Red => "red",
+        }
+    }
+}
+
+// 고객 번호별 최근 청구 월, 마지막 조회
+#[derive(Debug, Clone, Serialize)]
+pub struct Freshness {
+    pub customer_number: String,
+    // 저장된 가장 최근 청구 월 (YYYY-MM)
+    pub latest_month: Option<String>,
+    // 이번 달 (KST) 과의 차이 (개월)
+    pub month_lag: Option<u32>,
+    pub last_success_at: Option<DateTime<Utc>>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub last_status: Option<String>,
+    pub health: Health,
+    // red 인 이유
+    pub reasons: Vec<String>,
+}
+
+// 저장소의 모든 고객 번호 (청구 data 나 조회 기록이 있는) 상태
+pub fn compute(
+    store: &Store,
+    thresholds: Thresholds,
+    now: DateTime<Utc>,
+) -> Result<Vec<Freshness>> {
+    let latest = store.latest_claim_dates()?;
+    let statuses = store.account_statuses()?;
+    let customers: BTreeSet<&String> = latest.keys().chain(statuses.keys()).collect();
+    let today = now.with_timezone(&kst()).date_naive();
+
+    Ok(customers
+        .into_iter()
+        .map(|customer_number| {
+            let latest = latest.get(customer_number).copied();
+            let status = statuses.get(customer_number);
+            let month_lag = latest.map(|latest| months_between(latest, today));
+            let last_success_at = status.and_then(|status| status.last_success_at);
+
+            let mut reasons = Vec::new();
+            match (latest, month_lag) {
+                (Some(latest), Some(lag)) if lag > thresholds.month_lag => reasons.push(format!(
+                    "Latest month {} is {} months behind",
+                    latest.format("%Y-%m"),
+                    lag
+                )),
+                (None, _) => reasons.push("No stored claims".to_string()),
+                _ => {}
+            }
+            match last_success_at {
+                Some(at) => {
+                    let hours = (now - at).num_hours();
+                    if hours > thresholds.scrape_age_hours as i64 {
+                        reasons.push(format!("Last successful scrape was {}h ago", hours));
+                    }
+                }
+                None => reasons.push("Never scraped successfully".to_string()),
+            }
+
+            Freshness {
+                customer_number: customer_number.clone(),
+                latest_month: latest.map(|latest| latest.format("%Y-%m").to_string()),
+                month_lag,
+                last_success_at,
+                last_attempt_at: status.map(|status| status.last_attempt_at),
+                last_status: status.map(|status| status.last_status.clone()),
+                health: if reasons.is_empty() {
+                    Health::Green
+                } else {
+                    Health::Red
+                },
+                reasons,
+            }
+        })
+        .collect())
+}
+
+fn kst() -> FixedOffset {
+    FixedOffset::east_opt(9 * 3600).expect("valid KST offset")
+}
+
+// 청구 월에서 오늘까지 개월 수 (같은 달이면 0)
+fn months_between(from: NaiveDate, to: NaiveDate) -> u32 {
+    let months = (to.year() * 12 + to.month() as i32) - (from.year() * 12 + from.month() as i32);
+    months.max(0) as u32
+}
+
+// Prometheus text exposition (GET /metrics)
+#[cfg(feature = "server")]
+pub fn metrics(freshness: &[Freshness]) -> String {
+    let mut text = String::new();
+    let mut gauge = |name: &str, help: &str, value: &dyn Fn(&Freshness) -> Option<f64>| {
+        let _ = writeln!(text, "# HELP rip_hyphen_{} {}", name, help);
+        let _ = writeln!(text, "# TYPE rip_hyphen_{} gauge", name);
+        for entry in freshness {
+            if let Some(value) = value(entry) {
+                let _ = writeln!(
+                    text,
+                    "rip_hyphen_{}{{customer_number=\"{}\"}} {}",
+                    name,
+                    escape_label(&entry.customer_number),
+                    value
+                );
+            }
+        }
+    };
+    gauge(
+        "latest_month_lag",
+        "Months between the current month and the latest stored billing month.",
+        &|entry| entry.month_lag.map(f64::from),
+    );
+    gauge(
+        "last_success_timestamp_seconds",
+        "Unix time of the last successful scrape.",
+        &|entry| entry.last_success_at.map(|at| at.timestamp() as f64),
+    );
+    gauge(
+        "last_attempt_timestamp_seconds",
+        "Unix time of the last scrape attempt.",
+        &|entry| entry.last_attempt_at.map(|at| at.timestamp() as f64),
+    );
+    gauge(
+        "stale",
+        "1 when the stored data is past a staleness threshold.",
+        &|entry| {
+            Some(if entry.health == Health::Red {
+                1.0
+            } else {
+                0.0
+            })
+        },
+    );
+    text
+}
+
+#[cfg(feature = "server")]
+pub fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,256 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use fantoccini::Client;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::endpoints::Endpoint;
+
+// 요청 1건 (CDP Network event 누적)
+#[derive(Debug, Default)]
+struct PendingEntry {
+    started_wall: f64,
+    started_at: f64,
+    // CDP resource type (Document, XHR, Fetch, ...)
+    resource_type: Option<String>,
+    request: Value,
+    response: Option<Value>,
+    finished_at: Option<f64>,
+    encoded_size: Option<f64>,
+    error: Option<String>,
+}
+
+// ChromeDriver performance log -> HAR
+pub struct HarRecorder {
+    log_url: String,
+    http: reqwest::Client,
+    order: Vec<String>,
+    entries: HashMap<String, PendingEntry>,
+}
+
+// performance log 활성화 capability
+pub fn capabilities() -> Value {
+    json!({ "performance": "ALL" })
+}
+
+impl HarRecorder {
+    pub async fn start(client: &Client, driver_url: &str) -> Result<Self> {
+        Ok(Self {
+            log_url: log_url(client, driver_url).await?,
+            // driver 가 응답하지 않는 경우 (watchdog 중단 등) 대비
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()?,
+            order: Vec::new(),
+            entries: HashMap::new(),
+        })
+    }
+
+    // 재시작한 browser session 의 log 를 이어서 수집
+    pub async fn attach(&mut self, client: &Client, driver_url: &str) -> Result<()> {
+        self.log_url = log_url(client, driver_url).await?;
+        Ok(())
+    }
+
+    // 쌓인 performance log 수집 (조회 시 driver 쪽 buffer 는 비워짐)
+    // driver 가 이미 종료된 경우 등 실패는 경고만 남기고 지금까지 수집한 것 유지
+    pub async fn collect(&mut self) {
+        if let Err(e) = self.collect_logs().await {
+            eprintln!("Failed to collect performance log: {:#}", e);
+        }
+    }
+
+    async fn collect_logs(&mut self) -> Result<()> {
+        let response: Value = self
+            .http
+            .post(&self.log_url)
+            .json(&json!({ "type": "performance" }))
+            .send()
+            .await
+            .context("Failed to request performance log")?
+            .json()
+            .await
+            .context("Failed to read performance log")?;
+
+        let logs = response["value"].as_array().cloned().unwrap_or_default();
+        for log in logs {
+            let Some(message) = log["message"].as_str() else {
+                continue;
+            };
+            let Ok(message) = serde_json::from_str::<Value>(message) else {
+                continue;
+            };
+            self.apply(&message["message"]);
+        }
+        Ok(())
+    }
+
+    // CDP Network event 반영
+    fn apply(&mut self, event: &Value) {
+        let params = &event["params"];
+        let Some(request_id) = params["requestId"].as_str() else {
+            return;
+        };
+        let timestamp = params["timestamp"].as_f64().unwrap_or_default();
+
+        match event["method"].as_str() {
+            Some("Network.requestWillBeSent") => {
+                if !self.entries.contains_key(request_id) {
+                    self.order.push(request_id.to_string());
+                }
+                self.entries.insert(
+                    request_id.to_string(),
+                    PendingEntry {
+                        started_wall: params["wallTime"].as_f64().unwrap_or_default(),
+                        started_at: timestamp,
+                        resource_type: params["type"].as_str().map(str::to_string),
+                        request: params["request"].clone(),
+                        ..Default::default()
+                    },
+                );
+            }
+            Some("Network.responseReceived") => {
+                if let Some(entry) = self.entries.get_mut(request_id) {
+                    entry.response = Some(params["response"].clone());
+                }
+            }
+            Some("Network.loadingFinished") => {
+                if let Some(entry) = self.entries.get_mut(request_id) {
+                    entry.finished_at = Some(timestamp);
+                    entry.encoded_size = params["encodedDataLength"].as_f64();
+                }
+            }
+            Some("Network.loadingFailed") => {
+                if let Some(entry) = self.entries.get_mut(request_id) {
+                    entry.finished_at = Some(timestamp);
+                    entry.error = params["errorText"].as_str().map(str::to_string);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // 정상 응답한 XHR/fetch 요청 (조회 화면 뒤의 data endpoint 후보)
+    pub fn endpoints(&self) -> Vec<Endpoint> {
+        self.order
+            .iter()
+            .filter_map(|id| self.entries.get(id))
+            .filter(|entry| matches!(entry.resource_type.as_deref(), Some("XHR" | "Fetch")))
+            .filter_map(|entry| {
+                let response = entry.response.as_ref()?;
+                let status = response["status"].as_i64()?;
+                if !(200..300).contains(&status) {
+                    return None;
+                }
+                let headers = entry.request["headers"].as_object();
+                Some(Endpoint {
+                    method: entry.request["method"].as_str()?.to_string(),
+                    url: entry.request["url"].as_str()?.to_string(),
+                    content_type: headers
+                        .and_then(|headers| {
+                            headers
+                                .iter()
+                                .find(|(name, _)| name.eq_ignore_ascii_case("content-type"))
+                        })
+                        .and_then(|(_, value)| value.as_str())
+                        .map(str::to_string),
+                    post_data: entry.request["postData"].as_str().map(str::to_string),
+                    mime_type: response["mimeType"]
+                        .as_str()
+                        .unwrap_or_default()
+                        .to_string(),
+                })
+            })
+            .collect()
+    }
+
+    // 수집된 요청을 HAR 파일로 저장
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let entries: Vec<Value> = self
+            .order
+            .iter()
+            .filter_map(|id| self.entries.get(id))
+            .map(to_har_entry)
+            .collect();
+
+        let har = json!({
+            "log": {
+                "version": "1.2",
+                "creator": { "name": env!("CARGO_PKG_NAME"), "version": env!("CARGO_PKG_VERSION") },
+                "entries": entries,
+            }
+        });
+
+        fs::write(path, serde_json::to_vec_pretty(&har)?)
+            .context(format!("Failed to write HAR: {}", path.display()))?;
+        eprintln!("HAR saved: {}", path.display());
+        Ok(())
+    }
+}
+
+// WebDriver session 의 performance log url
+async fn log_url(client: &Client, driver_url: &str) -> Result<String> {
+    let session_id = client
+        .session_id()
+        .await
+        .context("Failed to get WebDriver session id")?
+        .context("WebDriver session id is missing")?;
+    Ok(format!("{}/session/{}/se/log", driver_url, session_id))
+}
+
+// CDP header object -> HAR header 배열
+fn to_har_headers(headers: &Value) -> Vec<Value> {
+    headers
+        .as_object()
+        .map(|headers| {
+            headers
+                .iter()
+                .map(|(name, value)| json!({ "name": name, "value": value.as_str().unwrap_or_default() }))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn to_har_entry(entry: &PendingEntry) -> Value {
+    let started = DateTime::<Utc>::from_timestamp_millis((entry.started_wall * 1000.0) as i64)
+        .unwrap_or_default();
+    let elapsed_ms = entry
+        .finished_at
+        .map_or(-1.0, |finished| (finished - entry.started_at) * 1000.0);
+    let response = entry.response.as_ref();
+
+    json!({
+        "startedDateTime": started.to_rfc3339(),
+        "time": elapsed_ms,
+        "request": {
+            "method": entry.request["method"],
+            "url": entry.request["url"],
+            "httpVersion": response.and_then(|response| response["protocol"].as_str()).unwrap_or_default(),
+            "headers": to_har_headers(&entry.request["headers"]),
+            "queryString": [],
+            "cookies": [],
+            "headersSize": -1,
+            "bodySize": entry.request["postData"].as_str().map_or(0, str::len),
+        },
+        "response": {
+            "status": response.map_or(0, |response| response["status"].as_i64().unwrap_or_default()),
+            "statusText": response.and_then(|response| response["statusText"].as_str()).unwrap_or_default(),
+            "httpVersion": response.and_then(|response| response["protocol"].as_str()).unwrap_or_default(),
+            "headers": response.map(|response| to_har_headers(&response["headers"])).unwrap_or_default(),
+            "cookies": [],
+            "content": {
+                "size": entry.encoded_size.unwrap_or(-1.0),
+                "mimeType": response.and_then(|response| response["mimeType"].as_str()).unwrap_or_default(),
+            },
+            "redirectURL": "",
+            "headersSize": -1,
+            "bodySize": entry.encoded_size.unwrap_or(-1.0),
+            "_error": entry.error,
+        },
+        "cache": {},
+        "timings": { "send": 0, "wait": elapsed_ms, "receive": 0 },
+    })
+}
@@ -0,0 +1,54 @@
+use anyhow::{bail, Context, Result};
+use tokio::process::Command;
+
+pub use seppuku_hyphen::sink::RunSummary;
+
+// 조회 성공 후 shell command, webhook 순서로 실행
+pub async fn run(commands: &[String], webhooks: &[String], summary: &RunSummary) -> Result<()> {
+    for command in commands {
+        run_command(command, summary)
+            .await
+            .context(format!("Hook failed: {}", command))?;
+    }
+    for webhook in webhooks {
+        post_webhook(webhook, summary)
+            .await
+            .context(format!("Webhook failed: {}", webhook))?;
+    }
+    Ok(())
+}
+
+// sh -c '<command>' (결과 파일 경로는 $1, 요약은 환경 변수)
+async fn run_command(command: &str, summary: &RunSummary) -> Result<()> {
+    let status = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .arg("sh")
+        .arg(&summary.output)
+        .env("RIP_HYPHEN_RUN_ID", &summary.run_id)
+        .env("RIP_HYPHEN_OUTPUT", &summary.output)
+        .env("RIP_HYPHEN_CUSTOMERS", summary.customers.to_string())
+        .env("RIP_HYPHEN_ROWS", summary.rows.to_string())
+        .env("RIP_HYPHEN_ELAPSED_SECS", summary.elapsed_secs.to_string())
+        .env("RIP_HYPHEN_WARNINGS", summary.warnings.to_string())
+        .env("RIP_HYPHEN_SUMMARY", serde_json::to_string(summary)?)
+        .status()
+        .await
+        .context("Failed to spawn hook")?;
+    if !status.success() {
+        bail!("Hook exited with {}", status);
+    }
+    Ok(())
+}
+
+// 요약 JSON POST
+async fn post_webhook(url: &str, summary: &RunSummary) -> Result<()> {
+    reqwest::Client::new()
+        .post(url)
+        .json(summary)
+        .send()
+        .await
+        .context("Failed to send webhook")?
+        .error_for_status()?;
+    Ok(())
+}
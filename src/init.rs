@@ -0,0 +1,178 @@
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::customer::Meter;
+use crate::output::OutputFormat;
+
+// 한 줄 입력 (빈 입력이면 default)
+pub fn prompt(label: &str, default: Option<&str>) -> Result<String> {
+    match default {
+        Some(default) => print!("{} [{}]: ", label, default),
+        None => print!("{}: ", label),
+    }
+    let line = read_line()?;
+    let line = line.trim();
+    Ok(match default {
+        Some(default) if line.is_empty() => default.to_string(),
+        _ => line.to_string(),
+    })
+}
+
+// 화면에 표시하지 않는 입력 (stty 로 echo 를 끄고 입력 후 복구)
+pub fn prompt_secret(label: &str) -> Result<String> {
+    print!("{}: ", label);
+    let stty = |flag: &str| {
+        Command::new("stty")
+            .arg(flag)
+            .stdin(Stdio::inherit())
+            .status()
+            .is_ok_and(|status| status.success())
+    };
+    let hidden = stty("-echo");
+    let line = read_line();
+    if hidden {
+        stty("echo");
+        println!();
+    }
+    Ok(line?.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn read_line() -> Result<String> {
+    io::stdout().flush()?;
+    let mut line = String::new();
+    if io::stdin().read_line(&mut line)? == 0 {
+        bail!("Input closed before the setup finished");
+    }
+    Ok(line)
+}
+
+// 목록 번호로 고객 번호 선택 (빈 입력이면 전체)
+pub fn pick_meters(meters: &[Meter]) -> Result<Vec<String>> {
+    for (index, meter) in meters.iter().enumerate() {
+        println!(
+            "  {}) {}  {}",
+            index + 1,
+            meter.customer_number,
+            meter.display_name
+        );
+    }
+    loop {
+        let answer = prompt(
+            "Meters to scrape (comma separated numbers, empty for all)",
+            None,
+        )?;
+        if answer.is_empty() {
+            return Ok(meters
+                .iter()
+                .map(|meter| meter.customer_number.clone())
+                .collect());
+        }
+        let picked: Option<Vec<String>> = answer
+            .split(',')
+            .map(|item| {
+                let index = item.trim().parse::<usize>().ok()?.checked_sub(1)?;
+                meters.get(index).map(|meter| meter.customer_number.clone())
+            })
+            .collect();
+        match picked {
+            Some(mut picked) => {
+                picked.sort();
+                picked.dedup();
+                return Ok(picked);
+            }
+            None => println!("Enter numbers between 1 and {}", meters.len()),
+        }
+    }
+}
+
+// 출력 형식 선택
+pub fn pick_format(default: OutputFormat) -> Result<OutputFormat> {
+    let names: Vec<&str> = OutputFormat::value_variants()
+        .iter()
+        .map(|format| format_name(*format))
+        .collect();
+    loop {
+        let answer = prompt(
+            &format!("Output format ({})", names.join(", ")),
+            Some(format_name(default)),
+        )?;
+        match OutputFormat::from_str(&answer, true) {
+            Ok(format) => return Ok(format),
+            Err(_) => println!("Unknown format: {}", answer),
+        }
+    }
+}
+
+fn format_name(format: OutputFormat) -> &'static str {
+    match format {
+        OutputFormat::Json => "json",
+        OutputFormat::Table => "table",
+        OutputFormat::Csv => "csv",
+    }
+}
+
+// .env 내용 (password 가 없으면 비밀번호 대신 keyring 항목 참조)
+pub fn env_file(
+    user_id: &str,
+    password: Option<&str>,
+    customer_numbers: &[String],
+    format: OutputFormat,
+) -> String {
+    let credentials = match password {
+        None => format!(
+            "# Written by init, the portal password is kept in the OS keyring\n\
+             RIP_HYPHEN_CREDENTIALS=keyring:{}\n",
+            user_id
+        ),
+        Some(password) => format!(
+            "# Written by init, no OS keyring was available so the portal password is stored here\n\
+             RIP_HYPHEN_CREDENTIALS=env\n\
+             PP_ID={}\n\
+             PP_PW={}\n",
+            quote(user_id),
+            quote(password)
+        ),
+    };
+    format!(
+        "{}PP_NUMBER={}\n\
+         RIP_HYPHEN_FORMAT={}\n",
+        credentials,
+        customer_numbers.join(","),
+        format_name(format)
+    )
+}
+
+// 큰따옴표 값 (dotenv 가 해석하는 \, ", $ 는 escape)
+fn quote(value: &str) -> String {
+    format!(
+        "\"{}\"",
+        value
+            .replace('\\', "\\\\")
+            .replace('"', "\\\"")
+            .replace('$', "\\$")
+    )
+}
+
+// 비밀번호가 들어갈 수 있으므로 소유자만 읽을 수 있게 생성 (덮어쓸 때도)
+pub fn write_env_file(path: &Path, content: &str) -> Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options
+        .open(path)
+        .context(format!("Failed to write {}", path.display()))?;
+    // mode 는 새로 만들 때만 적용되므로 기존 파일 (init --force) 도 비밀번호를 쓰기 전에 0600 으로
+    #[cfg(unix)]
+    file.set_permissions(std::os::unix::fs::PermissionsExt::from_mode(0o600))
+        .context(format!(
+            "Failed to restrict permissions of {}",
+            path.display()
+        ))?;
+    file.write_all(content.as_bytes())
+        .context(format!("Failed to write {}", path.display()))
+}
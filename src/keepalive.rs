@@ -0,0 +1,40 @@
+use fantoccini::Client;
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, Duration, MissedTickBehavior};
+
+// portal 세션 유지용 요청 (현재 페이지 HEAD)
+const PING_SCRIPT: &str = r#"
+    fetch(window.location.href, { method: 'HEAD', credentials: 'same-origin' }).catch(() => {});
+    return true;
+    "#;
+
+// 긴 로컬 처리 동안 browser, portal 세션 유지 (drop 시 중단)
+pub struct KeepAlive {
+    handle: JoinHandle<()>,
+}
+
+impl KeepAlive {
+    pub fn start(client: Arc<Client>, period: Duration) -> Self {
+        let handle = tokio::spawn(async move {
+            let mut ticker = interval(period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+            // 첫 tick 은 즉시 발생하므로 건너뜀
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                match client.execute(PING_SCRIPT, vec![]).await {
+                    Ok(_) => eprintln!("Keep-alive ping sent"),
+                    Err(e) => eprintln!("Keep-alive ping failed: {}", e),
+                }
+            }
+        });
+        Self { handle }
+    }
+}
+
+impl Drop for KeepAlive {
+    fn drop(&mut self) {
+        self.handle.abort();
+    }
+}
@@ -0,0 +1,77 @@
+use anyhow::{Context, Result};
+use fantoccini::{Client, Locator};
+use tokio::time::Duration;
+
+use crate::error::{ErrorCode, Failure};
+use crate::selectors::{Selector, SelectorSpec};
+
+// 확인할 요소가 나타날 때까지 대기 시간
+const WAIT: Duration = Duration::from_secs(10);
+const POLL: Duration = Duration::from_millis(250);
+// 에러 메시지에 포함할 페이지 문자열 길이
+const EXCERPT_CHARS: usize = 80;
+
+// 로그인 직후 페이지에서 확인할 문자열 (예: header 의 마스킹된 사용자 이름)
+#[derive(Debug, Clone)]
+pub struct LandingCheck {
+    pub text: String,
+    // 문자열을 찾을 요소 (없으면 페이지 전체)
+    pub selector: Option<SelectorSpec>,
+}
+
+impl LandingCheck {
+    pub fn new(text: Option<&str>, selector: Option<&SelectorSpec>) -> Option<Self> {
+        let text = normalize(text?);
+        (!text.is_empty()).then(|| Self {
+            text,
+            selector: selector.cloned(),
+        })
+    }
+
+    // 다른 계정 session (cache 된 session 복원 등) 으로 로그인됐으면 WrongAccount
+    pub async fn verify(&self, client: &Client) -> Result<()> {
+        let found = match &self.selector {
+            Some(spec) => {
+                let selector = Selector(vec![spec.clone()]);
+                let resolved = selector.wait(client, WAIT, POLL).await.context(
+                    Failure::new(
+                        ErrorCode::WrongAccount,
+                        "Landing element not found after login",
+                    )
+                    .with_selector(spec),
+                )?;
+                client
+                    .find(resolved.locator())
+                    .await?
+                    .text()
+                    .await
+                    .context("Failed to read the landing element")?
+            }
+            None => client
+                .find(Locator::Css("body"))
+                .await?
+                .text()
+                .await
+                .context("Failed to read the landing page")?,
+        };
+
+        let found = normalize(&found);
+        if !found.contains(&self.text) {
+            let excerpt: String = found.chars().take(EXCERPT_CHARS).collect();
+            return Err(Failure::new(
+                ErrorCode::WrongAccount,
+                format!(
+                    "Logged in to a different account, expected '{}' but the page shows '{}'",
+                    self.text, excerpt
+                ),
+            )
+            .into());
+        }
+        Ok(())
+    }
+}
+
+// 공백 차이 무시
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
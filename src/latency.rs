@@ -0,0 +1,120 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::Duration;
+
+use crate::ready::{PageReady, ReadyCondition};
+
+// loading 대기 단계 이름
+pub const PAGES: &[&str] = &[
+    "login", "customer", "claims", "year", "bill", "daily", "hourly",
+];
+
+// 관측값이 없을 때 기본 timeout
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+const MIN_TIMEOUT: Duration = Duration::from_secs(3);
+const MAX_TIMEOUT: Duration = Duration::from_secs(60);
+// 관측 최대값 대비 timeout 배수
+const TIMEOUT_FACTOR: u32 = 3;
+// 평균 소요 시간이 이보다 짧으면 빠른 poll 간격 사용
+const FAST_PAGE: Duration = Duration::from_secs(1);
+const FAST_POLL: Duration = Duration::from_millis(100);
+const SLOW_POLL: Duration = Duration::from_millis(500);
+
+// 단계별 고정 timeout (--step-timeout login=20)
+#[derive(Debug, Clone)]
+pub struct StepTimeout {
+    pub page: String,
+    pub timeout: Duration,
+}
+
+impl FromStr for StepTimeout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (page, secs) = s
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid step timeout, expected <step>=<seconds>: {}", s))?;
+        let page = page.trim();
+        if !PAGES.contains(&page) {
+            return Err(format!(
+                "Unknown step '{}', expected one of: {}",
+                page,
+                PAGES.join(", ")
+            ));
+        }
+        let secs: u64 = secs
+            .trim()
+            .parse()
+            .map_err(|_| format!("Invalid seconds for step '{}': {}", page, secs))?;
+        Ok(Self {
+            page: page.to_string(),
+            timeout: Duration::from_secs(secs),
+        })
+    }
+}
+
+// 페이지별 loading 준비 조건, 소요 시간 기록 (이후 대기 timeout/poll 간격 조정)
+#[derive(Debug, Default)]
+pub struct PageLatency {
+    observed: HashMap<&'static str, Vec<Duration>>,
+    // 설정된 단계는 관측값과 무관하게 고정 timeout
+    overrides: HashMap<String, Duration>,
+    // 단계별 준비 조건 (없으면 loading overlay 숨김)
+    ready: HashMap<String, ReadyCondition>,
+    default_ready: ReadyCondition,
+}
+
+impl PageLatency {
+    pub fn new(timeouts: &[StepTimeout], ready: &[PageReady]) -> Self {
+        Self {
+            observed: HashMap::new(),
+            overrides: timeouts
+                .iter()
+                .map(|step| (step.page.clone(), step.timeout))
+                .collect(),
+            ready: ready
+                .iter()
+                .map(|step| (step.page.clone(), step.condition.clone()))
+                .collect(),
+            default_ready: ReadyCondition::default(),
+        }
+    }
+
+    pub fn ready(&self, page: &str) -> &ReadyCondition {
+        self.ready.get(page).unwrap_or(&self.default_ready)
+    }
+
+    pub fn record(&mut self, page: &'static str, elapsed: Duration) {
+        self.observed.entry(page).or_default().push(elapsed);
+    }
+
+    // 설정값, 없으면 관측 최대값 * TIMEOUT_FACTOR (MIN ~ MAX 범위)
+    pub fn timeout(&self, page: &str) -> Duration {
+        if let Some(timeout) = self.overrides.get(page) {
+            return *timeout;
+        }
+        match self
+            .observed
+            .get(page)
+            .and_then(|samples| samples.iter().max())
+        {
+            Some(max) => (*max * TIMEOUT_FACTOR).clamp(MIN_TIMEOUT, MAX_TIMEOUT),
+            None => DEFAULT_TIMEOUT,
+        }
+    }
+
+    // 평균 소요 시간 기준 fast/slow poll 간격
+    pub fn poll_interval(&self, page: &str) -> Duration {
+        match self.observed.get(page) {
+            Some(samples) if !samples.is_empty() => {
+                let average = samples.iter().sum::<Duration>() / samples.len() as u32;
+                if average < FAST_PAGE {
+                    FAST_POLL
+                } else {
+                    SLOW_POLL
+                }
+            }
+            _ => SLOW_POLL,
+        }
+    }
+}
@@ -0,0 +1,246 @@
+// KEPCO 파워플래너 조회 library (로그인, 고객 번호 전환, 월별 청구 요금, 일별, 시간대별 사용량 parsing)
+// 저장소, 기본 제공 sink, 출력 등 실행 관련 기능은 binary (main.rs) 에 있음
+// sink feature 는 조회 결과를 받는 Sink trait 과 deliver (직접 구현한 출력 대상)
+// browser feature 가 없으면 parsing, 검증만 (tokio, WebDriver 없이 wasm 으로 build 가능)
+// blocking feature 는 async runtime 없이 호출하는 동기 client (blocking::KepcoClient)
+pub mod bill;
+pub mod customer;
+pub mod error;
+pub mod extract;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+#[cfg(feature = "browser")]
+pub mod captcha;
+#[cfg(feature = "browser")]
+pub mod client;
+#[cfg(feature = "browser")]
+pub mod config;
+#[cfg(feature = "browser")]
+pub mod landing;
+#[cfg(feature = "browser")]
+pub mod latency;
+#[cfg(feature = "browser")]
+pub mod pacing;
+#[cfg(feature = "browser")]
+pub mod prelogin;
+#[cfg(feature = "browser")]
+pub mod query;
+#[cfg(feature = "browser")]
+pub mod ready;
+#[cfg(feature = "browser")]
+pub mod selectors;
+#[cfg(feature = "sink")]
+pub mod sink;
+#[cfg(feature = "browser")]
+pub mod warnings;
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDate, NaiveDateTime};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "browser")]
+pub use client::{Browser, KepcoClient};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PpData {
+    pub claim_date: NaiveDate,
+    pub usage: f64,
+    // 조정으로 환급 (음수) 인 달, 원 미만 금액도 표현 (직렬화는 10진 문자열, 출력 시 --money-format)
+    pub paid: Decimal,
+    // 청구금액 cell 에 함께 표시된 납부 상태 (저장소에는 저장하지 않음)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payment_status: Option<PaymentStatus>,
+    // 아직 청구가 확정되지 않은 진행 중인 달 (저장소에는 저장하지 않음)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub provisional: bool,
+    // 청구서 상세의 요금 항목 (--detailed)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub breakdown: Option<bill::Breakdown>,
+    // 출처, 원본 문자열, parsing 경고 (--include-raw)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+impl PpData {
+    // provenance, 납부 상태 제외 값 비교
+    pub fn same_values(&self, other: &PpData) -> bool {
+        self.claim_date == other.claim_date && self.usage == other.usage && self.paid == other.paid
+    }
+}
+
+// 일별 사용량 (일별 사용량 화면의 하루)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DailyUsage {
+    pub date: NaiveDate,
+    pub usage_kwh: f64,
+}
+
+// 시간대별 사용량 (시간대별 사용량 화면의 1시간 또는 15분 구간)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HourlyUsage {
+    // 구간이 끝나는 시각 (KST, 24:00 은 다음 날 00:00)
+    pub timestamp: NaiveDateTime,
+    pub kwh: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Provenance {
+    // 조회한 연도 view
+    pub year_view: Option<i32>,
+    pub raw_claim_date: Option<String>,
+    pub raw_usage: Option<String>,
+    pub raw_paid: Option<String>,
+    pub warnings: Vec<String>,
+}
+
+// parsing 청구 기간
+pub fn parse_date(date_str: &str) -> Result<NaiveDate> {
+    // 일자를 1로 설정
+    let date_with_day = format!("{} 01일", date_str);
+    NaiveDate::parse_from_str(&date_with_day, "%Y년 %m월 %d일").context("Failed to parse date")
+}
+
+// parsing 사용량
+pub fn parse_use_kwh(kwh_str: &str) -> Result<f64> {
+    let cleaned_str = kwh_str.replace(",", "").replace("kWh", "");
+    cleaned_str
+        .parse::<f64>()
+        .context("Failed to parse use kWh")
+}
+
+// 청구금액 cell 의 금액 뒤에 붙는 납부 상태 (e.g. "12,340원(납부완료)", "12,340 (미납)")
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentStatus {
+    // 납부완료, 완납
+    Paid,
+    // 미납
+    Unpaid,
+    // 일부납부
+    PartiallyPaid,
+    // 연체
+    Overdue,
+    // 납부예정, 자동이체 예정
+    Scheduled,
+}
+
+impl PaymentStatus {
+    // 상태 text (괄호, 공백 무시), 모르는 text 면 None
+    pub fn parse(text: &str) -> Option<Self> {
+        let text: String = text
+            .chars()
+            .filter(|c| !c.is_whitespace() && !"()[]".contains(*c))
+            .collect();
+        // "일부납부", "납부예정" 도 "납부" 를 포함하므로 먼저 비교
+        if text.contains("일부") || text.contains("부분") {
+            Some(PaymentStatus::PartiallyPaid)
+        } else if text.contains("예정") {
+            Some(PaymentStatus::Scheduled)
+        } else if text.contains("연체") {
+            Some(PaymentStatus::Overdue)
+        } else if text.contains("미납") {
+            Some(PaymentStatus::Unpaid)
+        } else if text.contains("완료") || text.contains("완납") {
+            Some(PaymentStatus::Paid)
+        } else {
+            None
+        }
+    }
+}
+
+// 청구금액 cell -> 금액 부분, 금액 뒤 text (원 제외, 없으면 빈 문자열)
+pub fn split_paid(amount_str: &str) -> (&str, &str) {
+    let amount_str = amount_str.trim();
+    let end = amount_str
+        .find(|c: char| !(c.is_ascii_digit() || c.is_whitespace() || ",.-△".contains(c)))
+        .unwrap_or(amount_str.len());
+    let (amount, rest) = amount_str.split_at(end);
+    (amount.trim(), rest.trim_start_matches('원').trim())
+}
+
+// 청구금액 cell 의 납부 상태 (없거나 모르는 text 면 None)
+pub fn parse_payment_status(amount_str: &str) -> Option<PaymentStatus> {
+    PaymentStatus::parse(split_paid(amount_str).1)
+}
+
+// parsing 요금 (환급은 - 또는 △ 로 표시, 금액 뒤 납부 상태 등은 무시)
+pub fn parse_paid(amount_str: &str) -> Result<Decimal> {
+    let (amount_part, _) = split_paid(amount_str);
+    let (negative, amount_part) = match amount_part.strip_prefix(['-', '△']) {
+        Some(rest) => (true, rest.trim()),
+        None => (false, amount_part),
+    };
+
+    let mut amount = amount_part.replace(",", "");
+    // . 뒤가 3자리면 천 단위 구분자
+    if amount
+        .rsplit_once('.')
+        .is_some_and(|(_, fraction)| fraction.len() == 3)
+    {
+        amount = amount.replace(".", "");
+    }
+    let amount: Decimal = amount.parse().context("Failed to parse amount")?;
+    Ok(if negative { -amount } else { amount })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paid(amount_str: &str) -> Decimal {
+        parse_paid(amount_str).unwrap()
+    }
+
+    #[test]
+    fn parses_paid_with_status_suffix() {
+        assert_eq!(split_paid("12,340원(납부완료)"), ("12,340", "(납부완료)"));
+        assert_eq!(paid("12,340원(납부완료)"), Decimal::from(12_340));
+        assert_eq!(
+            parse_payment_status("12,340원(납부완료)"),
+            Some(PaymentStatus::Paid)
+        );
+        assert_eq!(paid("- 1,200 (미납)"), Decimal::from(-1_200));
+        assert_eq!(
+            parse_payment_status("- 1,200 (미납)"),
+            Some(PaymentStatus::Unpaid)
+        );
+    }
+
+    #[test]
+    fn parses_refunds() {
+        assert_eq!(paid("△1,200"), Decimal::from(-1_200));
+        assert_eq!(paid("-1,200원"), Decimal::from(-1_200));
+        assert_eq!(parse_payment_status("△1,200"), None);
+    }
+
+    #[test]
+    fn parses_dot_as_thousands_separator_only_before_three_digits() {
+        assert_eq!(paid("1.234"), Decimal::from(1_234));
+        assert_eq!(paid("1.234.567원"), Decimal::from(1_234_567));
+        assert_eq!(paid("12.5"), Decimal::new(125, 1));
+    }
+
+    #[test]
+    fn parses_payment_status_before_generic_words() {
+        assert_eq!(
+            PaymentStatus::parse("(일부납부)"),
+            Some(PaymentStatus::PartiallyPaid)
+        );
+        assert_eq!(
+            PaymentStatus::parse("[납부예정]"),
+            Some(PaymentStatus::Scheduled)
+        );
+        assert_eq!(PaymentStatus::parse("연체"), Some(PaymentStatus::Overdue));
+        assert_eq!(PaymentStatus::parse("완납"), Some(PaymentStatus::Paid));
+    }
+
+    #[test]
+    fn ignores_unknown_status() {
+        assert_eq!(PaymentStatus::parse("상세조회"), None);
+        assert_eq!(PaymentStatus::parse(""), None);
+        assert_eq!(parse_payment_status("5,000원 abc"), None);
+        assert_eq!(paid("5,000원 abc"), Decimal::from(5_000));
+    }
+}
@@ -1,507 +1,2854 @@
-use anyhow::{anyhow, Context, Result};
+mod archive;
+mod blackout;
+mod calendar;
+mod canary;
+mod chart;
+mod checkpoint;
+mod cli;
+mod driver;
+mod endpoints;
+#[cfg(feature = "excel")]
+mod excel;
+mod filter;
+mod freshness;
+mod grafana;
+mod har;
+mod hooks;
+mod init;
+mod keepalive;
+mod output;
+mod paths;
+mod pipelines;
+mod pool;
+mod profiles;
+mod report;
+#[cfg(feature = "server")]
+mod scheduler;
+#[cfg(feature = "server")]
+mod server;
+mod session;
+mod sinks;
+mod state;
+mod store;
+#[cfg(feature = "server")]
+mod summary;
+mod tags;
+mod templates;
+mod verify;
+mod watch;
+mod watchdog;
+
+use anyhow::{Context, Result};
 use chrono::{Datelike, NaiveDate};
-use dashmap::DashMap;
+use clap::Parser;
+use cli::ErrorFormat;
 use dotenv::dotenv;
-use fantoccini::{elements::Element, Client, ClientBuilder, Locator};
-use futures::TryFutureExt;
-use serde::{Deserialize, Serialize};
+use error::{ErrorCode, Failure, Step};
+use fantoccini::{Client, ClientBuilder, Locator};
+use keepalive::KeepAlive;
+use latency::PageLatency;
+use seppuku_hyphen::client::{
+    click_element, customer_options, fetch_daily_usage, fetch_hourly_usage, fetch_monthly_claims,
+    fetch_year_claims, get_text_by_locator, login, open_monthly_claims, read_customer_info,
+    read_labeled_fields, select_customer, wait_for_loading, ClaimScope, LoginFlow, TARGET_URL,
+    YEAR_SELECT,
+};
+use seppuku_hyphen::{
+    bill::Breakdown, config, customer, error, extract, landing, latency, pacing, prelogin, ready,
+    selectors, warnings, DailyUsage, HourlyUsage, PpData,
+};
+#[cfg(feature = "excel")]
+use seppuku_hyphen::{parse_date, parse_paid, parse_use_kwh};
 use serde_json::{json, Map, Value};
-use std::collections::HashSet;
-use std::future::Future;
+use std::cell::RefCell;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
+use std::io::{IsTerminal, StdoutLock, Write};
 use std::{
-    env,
-    process::{Child, Command},
+    net::TcpListener,
+    path::{Path, PathBuf},
     sync::Arc,
 };
-use tokio::time::{timeout, Duration};
+use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
 
-#[derive(Debug, Serialize, Deserialize)]
-struct PpData {
-    claim_date: NaiveDate,
-    usage: f64,
-    paid: i64,
-}
+// 로컬 처리 중 세션 유지 ping 주기
+const KEEPALIVE_PERIOD: Duration = Duration::from_secs(60);
+
+// 전체 조회 재시도 첫 대기 시간 (재시도마다 2배, RUN_RETRY_MAX_DELAY 까지)
+const RUN_RETRY_DELAY: Duration = Duration::from_secs(10);
+const RUN_RETRY_MAX_DELAY: Duration = Duration::from_secs(10 * 60);
+
+// ChromeDriver 기동 후 WebDriver 연결을 기다리는 최대 시간
+const DRIVER_CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
 
 #[tokio::main]
 async fn main() -> Result<(), anyhow::Error> {
     dotenv().ok();
+    let cli = cli::Cli::parse();
+    let error_format = cli.error_format;
+    // 실행 중 경고는 발생 즉시 stderr 로, 끝에 개수 요약
+    let warnings = warnings::Warnings::with_callback(|warning| {
+        eprintln!("Warning: {}", warning.message);
+    });
+
+    // ChromeDriver 는 처음 실행할 때 찾음 (--chromedriver, $PATH, --download-driver)
+    driver::resolver::configure(driver::resolver::Settings {
+        path: cli.chromedriver.clone(),
+        download: cli.download_driver,
+        cache_dir: paths::DataDir::resolve(cli.data_dir.as_deref())
+            .ok()
+            .map(|data_dir| data_dir.drivers_dir()),
+    });
+    // panic, Ctrl-C, SIGTERM 에도 ChromeDriver, Chrome 이 남지 않도록 종료
+    driver::install_panic_hook();
+    let result = warnings
+        .scope(async {
+            tokio::select! {
+                result = dispatch(&cli) => result,
+                signal = driver::shutdown_signal() => Err(Failure::new(
+                    ErrorCode::Interrupted,
+                    format!("Interrupted by {}", signal),
+                )
+                .into()),
+            }
+        })
+        .await;
+    driver::kill_all();
 
-    let url = "http://localhost:4444";
-    let target_url = "https://pp.kepco.co.kr";
-    let user_id = env::var("PP_ID").expect("");
-    let user_pw = env::var("PP_PW").expect("");
-    let user_num = env::var("PP_NUMBER").expect("");
-
-    // driver path
-    let chromedriver_path = "/opt/homebrew/bin/chromedriver";
-
-    // driver 실행
-    let mut chromedriver_process = Command::new(chromedriver_path)
-        .arg("--port=4444")
-        .spawn()
-        .expect("failed to start ChromeDriver");
+    let warnings = warnings.take();
+    if !warnings.is_empty() {
+        eprintln!("{} warnings during the run", warnings.len());
+    }
 
-    // driver 대기
-    tokio::time::sleep(Duration::from_secs(2)).await;
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) => {
+            match error_format {
+                ErrorFormat::Json => eprintln!("{}", error::to_json(&e)),
+                ErrorFormat::Text => eprintln!("Error: {:?}", e),
+            }
+            std::process::exit(error::exit_code(&e));
+        }
+    }
+}
 
-    // headless, disable-gpu option
-    let capabilities: Map<String, Value> = serde_json::from_value(json!({
-        "goog:chromeOptions": {
-            "args": ["--headless", "--disable-gpu"]
+// subcommand 실행
+async fn dispatch(cli: &cli::Cli) -> Result<()> {
+    match &cli.command {
+        Some(cli::Command::Clean { older_than_days }) => clean(cli, *older_than_days),
+        // fetch 전후의 조회 옵션은 모두 cli 에 (global)
+        Some(cli::Command::Fetch { command }) => match (command, cli.fetch.dataset) {
+            (Some(cli::FetchCommand::Hourly { date, until }), _) => {
+                let request = UsageRequest::Hourly {
+                    from: *date,
+                    to: until.unwrap_or(*date),
+                };
+                fetch_usage(cli, request).await
+            }
+            (None, cli::Dataset::Monthly) => run_with_retries(cli, None).await,
+            (None, cli::Dataset::Daily) => fetch_usage(cli, UsageRequest::Daily).await,
+        },
+        Some(cli::Command::Backfill { year }) => run_with_retries(cli, Some(*year)).await,
+        Some(cli::Command::Archive { month, zip }) => archive(cli, *month, *zip).await,
+        Some(cli::Command::Verify { from, to }) => {
+            verify_bills(cli, *from, to.unwrap_or(*from)).await
         }
-    }))?;
-    // .capabilities(capabilities.clone())
-    let client = loop {
-        match ClientBuilder::native()
-            .capabilities(capabilities.clone())
-            .connect(url)
+        Some(cli::Command::Meters {
+            command: cli::MetersCommand::List,
+        }) => list_meters(cli).await,
+        Some(cli::Command::Session {
+            command: cli::SessionCommand::Export { out, headed },
+        }) => export_session(cli, out, *headed).await,
+        Some(cli::Command::Session {
+            command: cli::SessionCommand::Import { input },
+        }) => import_session(cli, input),
+        Some(cli::Command::Prune) => prune(cli),
+        #[cfg(feature = "excel")]
+        Some(cli::Command::Import {
+            command: cli::ImportCommand::Excel { files, customer },
+        }) => import_excel(cli, files, customer),
+        #[cfg(not(feature = "excel"))]
+        Some(cli::Command::Import { .. }) => Err(Failure::new(
+            ErrorCode::Config,
+            "import excel requires a build with the excel feature",
+        )
+        .into()),
+        Some(cli::Command::Reparse { dry_run }) => reparse(cli, *dry_run),
+        Some(cli::Command::Watch {
+            reading_day,
+            arrival_lag_days,
+            window_days,
+            poll_minutes,
+            interval_hours,
+        }) => {
+            let schedule = watch::Schedule {
+                reading_day: *reading_day,
+                arrival_lag_days: *arrival_lag_days,
+                window_days: *window_days,
+                poll: Duration::from_secs(poll_minutes * 60),
+                interval: Duration::from_secs(interval_hours * 60 * 60),
+            };
+            watch(cli, &schedule).await
+        }
+        Some(cli::Command::Report {
+            command: cli::ReportCommand::Render { month, months, out },
+        }) => {
+            render_report(
+                cli,
+                &report::ReportOptions {
+                    month: *month,
+                    months: *months,
+                    labels: cli.labels,
+                    locale: cli.locale,
+                },
+                out,
+            )
             .await
-        {
-            Ok(client) => break client,
-            Err(e) => {
-                eprintln!("Retrying to connect to WebDriver: {}", e);
-                tokio::time::sleep(Duration::from_secs(1)).await;
-            }
         }
-    };
-    let client_arc = Arc::new(client);
-
-    // view size
-    client_arc.set_window_rect(0, 0, 774, 857).await?;
-    // 페이지 이동
-    client_arc
-        .goto(&format!("{}/intro.do", target_url))
-        .await
-        .context("Failed to navigate")?;
+        #[cfg(feature = "server")]
+        Some(cli::Command::Serve {
+            listen,
+            pipelines,
+            workers,
+        }) => serve(cli, *listen, pipelines.as_deref(), *workers).await,
+        #[cfg(not(feature = "server"))]
+        Some(cli::Command::Serve { .. }) => Err(Failure::new(
+            ErrorCode::Config,
+            "serve requires a build with the server feature",
+        )
+        .into()),
+        Some(cli::Command::Daemon { pipelines }) => daemon(cli, pipelines).await,
+        Some(cli::Command::Calendar {
+            reading_day,
+            arrival_lag_days,
+            due_day,
+            months,
+            out,
+        }) => write_calendar(
+            cli,
+            &calendar::CalendarOptions {
+                reading_day: *reading_day,
+                arrival_lag_days: *arrival_lag_days,
+                due_day: *due_day,
+                months: *months,
+            },
+            out,
+        ),
+        Some(cli::Command::Grafana {
+            command:
+                cli::GrafanaCommand::Init {
+                    datasource,
+                    clickhouse_url,
+                    out,
+                },
+        }) => grafana_init(cli, *datasource, clickhouse_url.as_deref(), out),
+        Some(cli::Command::Preflight) => {
+            let (mut sinks, mut notify) = build_sinks(cli)?;
+            preflight_sinks(&mut sinks, &mut notify).await
+        }
+        Some(cli::Command::Init { out, force }) => init_wizard(cli, out, *force).await,
+        Some(cli::Command::Config {
+            command: cli::ConfigCommand::Validate { pipelines },
+        }) => validate_config(cli, pipelines.as_deref()),
+        Some(cli::Command::Config {
+            command: cli::ConfigCommand::Migrate { pipelines, dry_run },
+        }) => migrate_config(pipelines, *dry_run),
+        Some(cli::Command::Query { sql, duckdb }) => query(cli, sql, duckdb.as_deref()).await,
+        Some(cli::Command::Runs {
+            command: cli::RunsCommand::List { limit },
+        }) => list_runs(cli, *limit),
+        Some(cli::Command::Status) => status(cli),
+        Some(cli::Command::Canary {
+            account,
+            min_confidence,
+        }) => run_canary(cli, account.as_deref(), *min_confidence).await,
+        Some(cli::Command::Profiles {
+            command: cli::ProfilesCommand::List,
+        }) => list_profiles(cli),
+        Some(cli::Command::Profiles {
+            command: cli::ProfilesCommand::Clean { account },
+        }) => clean_profiles(cli, account.as_deref()),
+        Some(cli::Command::Endpoints {
+            command: cli::EndpointsCommand::Fetch,
+        }) => fetch_endpoints(cli).await,
+        None => match cli.fetch.dataset {
+            cli::Dataset::Monthly => run_with_retries(cli, None).await,
+            cli::Dataset::Daily => fetch_usage(cli, UsageRequest::Daily).await,
+        },
+    }
+}
 
-    // 공지 팝업 로드 대기
-    wait_for_element(
-        &client_arc,
-        Locator::Id("notice_auto_popup"),
-        &mut chromedriver_process,
-    )
-    .await?;
-    //공지 팝업 비활성화
-    click_element(
-        &client_arc,
-        Locator::XPath("/html/body/div[2]/div[3]/label"),
-    )
-    .await?;
+// 오래된 artifact 정리
+fn clean(cli: &cli::Cli, older_than_days: u64) -> Result<()> {
+    let data_dir = paths::DataDir::resolve(cli.data_dir.as_deref())?;
+    let removed = data_dir.clean(Duration::from_secs(older_than_days * 24 * 60 * 60))?;
+    println!(
+        "Removed {} artifacts older than {} days from {}",
+        removed,
+        older_than_days,
+        data_dir.root().display()
+    );
+    Ok(())
+}
 
-    // id 입력 로드 대기
-    wait_for_element(
-        &client_arc,
-        Locator::Id("RSA_USER_ID"),
-        &mut chromedriver_process,
-    )
-    .await?;
-    // id 입력
-    enter_value_in_element(&client_arc, Locator::Id("RSA_USER_ID"), &user_id).await?;
-    // pw 입력
-    enter_value_in_element(&client_arc, Locator::Id("RSA_USER_PWD"), &user_pw).await?;
-    // 로그인 버튼 클릭
-    click_element(
-        &client_arc,
-        Locator::XPath("/html/body/div[1]/div[2]/div[1]/form/fieldset/input[1]"),
-    )
-    .await?;
+// 계정별 Chrome profile 목록 출력
+fn list_profiles(cli: &cli::Cli) -> Result<()> {
+    let data_dir = paths::DataDir::resolve(cli.data_dir.as_deref())?;
+    let profiles = profiles::list(&data_dir.profiles_dir())?;
+    println!("{}", output::render_profiles(&profiles, cli.format)?);
+    Ok(())
+}
 
-    // 로딩 대기
-    wait_for_element_display_none(
-        &client_arc,
-        Locator::Id("backgroundLayer"),
-        &mut chromedriver_process,
-        Duration::from_secs(10),
-    )
-    .await?;
+// 사용 중이 아닌 Chrome profile 삭제
+fn clean_profiles(cli: &cli::Cli, account: Option<&str>) -> Result<()> {
+    let data_dir = paths::DataDir::resolve(cli.data_dir.as_deref())?;
+    let removed = profiles::remove(&data_dir.profiles_dir(), account)?;
+    println!(
+        "Removed {} Chrome profiles from {}",
+        removed,
+        data_dir.profiles_dir().display()
+    );
+    Ok(())
+}
 
-    // user_num selector 클릭
-    click_element(
-        &client_arc,
-        Locator::XPath("/html/body/div[1]/div[1]/div/div/a[2]"),
-    )
-    .await?;
-    // user_num 클릭
-    click_element(
-        &client_arc,
-        Locator::XPath(
-            format!(
-                "/html/body/div[1]/div[1]/div/div/ul/li[1]/a[text()='{}']",
-                user_num
-            )
-            .as_str(),
-        ),
-    )
-    .await?;
+// --chrome-profile 이면 계정 전용 profile 확보 (driver 종료 전까지 유지)
+fn chrome_profile(cli: &cli::Cli, user_id: &str) -> Result<Option<profiles::ChromeProfile>> {
+    if !cli.chrome_profile {
+        return Ok(None);
+    }
+    let data_dir = paths::DataDir::resolve(cli.data_dir.as_deref())?;
+    profiles::ChromeProfile::acquire(&data_dir.profiles_dir(), user_id).map(Some)
+}
 
-    // 로딩 대기
-    wait_for_element_display_none(
-        &client_arc,
-        Locator::Id("backgroundLayer"),
-        &mut chromedriver_process,
-        Duration::from_secs(10),
-    )
-    .await?;
+// 로그인 후 선택 가능한 고객 번호 목록 출력
+async fn list_meters(cli: &cli::Cli) -> Result<()> {
+    let credentials = load_credentials(cli).await?;
+    let meters = discover_meters(cli, &credentials).await?;
+    println!(
+        "{}",
+        output::render_meters(&meters, cli.format, cli.labels)?
+    );
+    Ok(())
+}
 
-    // get 월별 청구 요금 url
-    let monthly_claim_href = get_href_by_locator(
-        &client_arc,
-        Locator::XPath("/html/body/div[1]/div[2]/div[1]/ul[4]/li[5]/a"),
+// 로그인 후 고객 번호 목록의 고객 번호, 표시 이름
+async fn discover_meters(
+    cli: &cli::Cli,
+    credentials: &config::Credentials,
+) -> Result<Vec<customer::Meter>> {
+    with_login(
+        cli,
+        &credentials.user_id,
+        &credentials.user_pw,
+        async |client, profile, _, _| {
+            let options = customer_options(client, profile)
+                .await
+                .context(Step("list_meters"))?;
+            Ok(options
+                .iter()
+                .filter_map(|(_, text)| customer::Meter::from_display(text))
+                .collect())
+        },
     )
     .await
-    .ok_or("");
-
-    let claim_url = format!("{}{}", target_url, monthly_claim_href.unwrap());
-    // 월별 청구 요금 이동
-    client_arc
-        .goto(&claim_url)
-        .await
-        .context("Failed go to monthly_claim_href")?;
-
-    // 로딩 대기
-    wait_for_element_display_none(
-        &client_arc,
-        Locator::Id("backgroundLayer"),
-        &mut chromedriver_process,
-        Duration::from_secs(10),
-    )
-    .await?;
-
-    // data from table -> vec
-    let mut data_vec = parse_data_from_table(&client_arc, "//*[@id='grid']/tbody").await?;
-
-    // select locator
-    let select_locator = Locator::Id("year");
-
-    // 1year over data parsing
-    let mut additional_data_vec =
-        parsing_options_data(&client_arc, select_locator, &1, &mut chromedriver_process).await?;
-
-    // data 병합
-    data_vec.append(&mut additional_data_vec);
-
-    // 중복 제거
-    let mut unique_dates = HashSet::new();
-    data_vec.retain(|entry| unique_dates.insert(entry.claim_date));
+}
 
-    // 정렬
-    data_vec.sort_by(|a, b| b.claim_date.cmp(&a.claim_date));
+// 대화형 초기 설정 (비밀번호는 OS keyring, 없으면 .env, 고객 번호와 출력 형식은 .env)
+async fn init_wizard(cli: &cli::Cli, out: &Path, force: bool) -> Result<()> {
+    if out.exists() && !force {
+        return Err(Failure::new(
+            ErrorCode::Config,
+            format!(
+                "{} already exists, pass --force to overwrite it",
+                out.display()
+            ),
+        )
+        .into());
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(Failure::new(ErrorCode::Config, "init needs an interactive terminal").into());
+    }
 
-    // JSON으로 변환
-    let json_data =
-        serde_json::to_string_pretty(&data_vec).context("Failed to serialize data to JSON")?;
+    let user_id = init::prompt("Portal user ID", cli.id.as_deref())?;
+    let user_pw = init::prompt_secret("Portal password")?;
+    let credentials = config::Credentials::new(user_id, user_pw)
+        .context(Failure::new(ErrorCode::Config, "Invalid credentials"))?;
+
+    // 로그인이 되는 비밀번호만 keyring 에 저장
+    println!("Logging in to find the customer numbers...");
+    let meters = discover_meters(cli, &credentials).await?;
+    if meters.is_empty() {
+        return Err(Failure::new(
+            ErrorCode::ElementNotFound,
+            "No customer number found for this account",
+        )
+        .into());
+    }
+    // keyring 이 없으면 (headless Linux 등) .env 에 비밀번호 저장
+    let password = match config::store_keyring(&credentials.user_id, &credentials.user_pw).await {
+        Ok(()) => {
+            println!("Stored the password in the OS keyring");
+            None
+        }
+        Err(e) => {
+            eprintln!(
+                "Could not store the password in the OS keyring ({:#}), writing it to {} instead; keep that file private",
+                e,
+                out.display()
+            );
+            Some(credentials.user_pw.as_str())
+        }
+    };
 
-    println!("{}", json_data);
+    let customer_numbers = init::pick_meters(&meters)?;
+    let format = init::pick_format(cli.format)?;
+    init::write_env_file(
+        out,
+        &init::env_file(&credentials.user_id, password, &customer_numbers, format),
+    )?;
+    println!(
+        "Wrote {} with {} customer numbers, run config validate or fetch to try it",
+        out.display(),
+        customer_numbers.len()
+    );
+    Ok(())
+}
 
-    // 2분 동안 대기
-    // println!("Waiting for 2 minutes...");
-    // tokio::time::sleep(tokio::time::Duration::from_secs(120)).await;
+// 청구 월 하나의 PDF, data, 검증 결과를 고객 번호별로 보관 (--zip 이면 zip 파일)
+async fn archive(cli: &cli::Cli, month: NaiveDate, zip: bool) -> Result<()> {
+    let config = load_config(cli).await?;
+    let data_dir = paths::DataDir::resolve(cli.data_dir.as_deref())?;
+    let store = cli
+        .store
+        .as_deref()
+        .map(store::Store::open)
+        .transpose()
+        .context(Failure::new(ErrorCode::Store, "Failed to open store"))?;
+    let row_filter = filter::RowFilter {
+        customers: cli.fetch.customer.clone(),
+        ..filter::RowFilter::default()
+    };
+    let archived = with_login(
+        cli,
+        &config.user_id,
+        &config.user_pw,
+        async |client, profile, latency, url| {
+            let mut claim_url = None;
+            let mut archived = Vec::new();
+            for user_num in &config.user_nums {
+                if !row_filter.matches_customer(user_num) {
+                    continue;
+                }
+                select_customer(client, user_num, profile, latency)
+                    .await
+                    .context(Step("select_customer"))?;
+                open_monthly_claims(client, TARGET_URL, &mut claim_url, profile, latency)
+                    .await
+                    .context(Step("navigate_claims"))?;
+
+                // 해당 연도 조회 후 청구 월 row, 청구서 상세 (PDF)
+                let record =
+                    fetch_year_claims(client, YEAR_SELECT, month.year(), profile, latency, None)
+                        .await
+                        .context(Step("archive"))?
+                        .into_iter()
+                        .find(|entry| entry.claim_date == month)
+                        .ok_or_else(|| {
+                            Failure::new(
+                                ErrorCode::ElementNotFound,
+                                format!(
+                                    "No bill for {} of customer {}",
+                                    month.format("%Y-%m"),
+                                    user_num
+                                ),
+                            )
+                        })?;
+                let (detail, pdf) = fetch_bill_detail(client, month, profile, latency, Some(url))
+                    .await
+                    .context(Step("bill_detail"))?;
+                let pdf = pdf.context("Bill detail was not printed")?;
+
+                // 청구서 상세, 저장된 값과 비교
+                let stored = match &store {
+                    Some(store) => store.load_claims(user_num)?.remove(&month),
+                    None => None,
+                };
+                let verification =
+                    verify::Verification::new(user_num, &record, Some(&detail), stored.as_ref());
+                if verification.status == "mismatch" {
+                    eprintln!(
+                        "Stored record differs for {} {}: {:?}",
+                        user_num, verification.month, verification.differences
+                    );
+                }
 
-    // ChromeDriver 프로세스 종료
-    chromedriver_process
-        .kill()
-        .expect("failed to kill ChromeDriver");
+                let dir =
+                    archive::write_bundle(&data_dir.archives_dir(), &record, &pdf, &verification)?;
+                archived.push(if zip { archive::zip_dir(&dir)? } else { dir });
+            }
+            Ok(archived)
+        },
+    )
+    .await?;
 
+    for path in archived {
+        println!("Archived: {}", path.display());
+    }
     Ok(())
 }
 
-// 요소 대기
-async fn wait_for_element(
-    client: &Client,
-    locator: Locator<'_>,
-    chromedriver_process: &mut Child,
-) -> Result<Option<Element>> {
-    match client.wait().for_element(locator).await {
-        Ok(element) => Ok(Some(element)),
-        Err(e) => {
-            eprintln!("Failed to find the element: {:?}\n {}", locator, e);
-            client
-                .clone()
-                .close()
-                .await
-                .context("Failed to close client")?;
-            chromedriver_process
-                .kill()
-                .expect("failed to kill ChromeDriver");
-            Err(anyhow::anyhow!("Failed to find the element: {:?}", e))
-        }
+// 기간 내 청구 월마다 목록 값과 청구서 상세 (저장소가 있으면 저장된 값) 비교
+async fn verify_bills(cli: &cli::Cli, from: NaiveDate, to: NaiveDate) -> Result<()> {
+    let config = load_config(cli).await?;
+    if to < from {
+        return Err(Failure::new(ErrorCode::Config, "--to is before --from").into());
     }
-}
+    let store = cli
+        .store
+        .as_deref()
+        .map(store::Store::open)
+        .transpose()
+        .context(Failure::new(ErrorCode::Store, "Failed to open store"))?;
+    let row_filter = filter::RowFilter {
+        customers: cli.fetch.customer.clone(),
+        ..filter::RowFilter::default()
+    };
+    let verifications = with_login(
+        cli,
+        &config.user_id,
+        &config.user_pw,
+        async |client, profile, latency, _| {
+            let mut claim_url = None;
+            let mut verifications = Vec::new();
+            for user_num in &config.user_nums {
+                if !row_filter.matches_customer(user_num) {
+                    continue;
+                }
+                select_customer(client, user_num, profile, latency)
+                    .await
+                    .context(Step("select_customer"))?;
+                open_monthly_claims(client, TARGET_URL, &mut claim_url, profile, latency)
+                    .await
+                    .context(Step("navigate_claims"))?;
+                let stored = match &store {
+                    Some(store) => store.load_claims(user_num)?,
+                    None => HashMap::new(),
+                };
+
+                let mut month = from;
+                while month <= to {
+                    // 상세 화면에서 돌아오면 조회 조건이 초기화될 수 있어 월마다 연도 조회
+                    let rows = fetch_year_claims(
+                        client,
+                        YEAR_SELECT,
+                        month.year(),
+                        profile,
+                        latency,
+                        None,
+                    )
+                    .await
+                    .context(Step("verify"))?;
+                    match rows.iter().find(|entry| entry.claim_date == month) {
+                        Some(record) => {
+                            let (detail, _) =
+                                fetch_bill_detail(client, month, profile, latency, None)
+                                    .await
+                                    .context(Step("bill_detail"))?;
+                            verifications.push(verify::Verification::new(
+                                user_num,
+                                record,
+                                Some(&detail),
+                                stored.get(&month),
+                            ));
+                        }
+                        None => eprintln!(
+                            "No bill for {} of customer {}, skipping",
+                            month.format("%Y-%m"),
+                            user_num
+                        ),
+                    }
+                    month = month
+                        .checked_add_months(chrono::Months::new(1))
+                        .context("Invalid month")?;
+                }
+            }
+            Ok(verifications)
+        },
+    )
+    .await?;
+    println!("{}", verify::render(&verifications, cli.format)?);
 
-// 요소 클릭
-async fn click_element(client: &Client, locator: Locator<'_>) -> Result<()> {
-    if let Ok(element) = client.find(locator).await {
-        element
-            .click()
-            .await
-            .context(format!("Failed to click the element: {:?}", locator))?;
-        println!("Element clicked successfully: {:?}", locator);
-    } else {
-        eprintln!("Failed to find the element: {:?}", locator);
-        return Err(anyhow::anyhow!("Failed to find the element: {:?}", locator));
+    // 불일치 월이 있으면 일부 실패로 종료
+    let mismatched: Vec<String> = verifications
+        .iter()
+        .filter(|verification| verification.status == "mismatch")
+        .map(|verification| format!("{} {}", verification.customer_number, verification.month))
+        .collect();
+    if !mismatched.is_empty() {
+        return Err(Failure::new(
+            ErrorCode::PartialFailure,
+            format!(
+                "{} of {} months do not match the bill detail: {}",
+                mismatched.len(),
+                verifications.len(),
+                mismatched.join(", ")
+            ),
+        )
+        .into());
     }
     Ok(())
 }
 
-// 요소에 값 입력
-async fn enter_value_in_element(client: &Client, locator: Locator<'_>, text: &str) -> Result<()> {
-    if let Ok(element) = client.find(locator).await {
-        if let Err(e) = element.send_keys(text).await {
-            eprintln!("Failed to enter text: {}", e);
-        } else {
-            println!("Text entered successfully: {:?}", locator);
-        }
-    } else {
-        eprintln!("Failed to find the input element: {:?}", locator);
-    }
-    Ok(())
+// 월별 청구 요금 외에 조회할 사용량
+#[derive(Debug, Clone, Copy)]
+enum UsageRequest {
+    // --from ~ --to (기본 이번 달) 의 월마다 일별 사용량 (--dataset daily)
+    Daily,
+    // --date ~ --until 의 하루마다 시간대별 사용량 (fetch hourly)
+    Hourly { from: NaiveDate, to: NaiveDate },
 }
 
-// 요소 비활성화 대기
-async fn wait_for_element_display_none(
-    client: &Client,
-    locator: Locator<'_>,
-    chromedriver_process: &mut Child,
-    duration: Duration,
-) -> Result<()> {
-    let element = match wait_for_element(client, locator, chromedriver_process).await? {
-        Some(element) => element,
-        None => return Err(anyhow::anyhow!("Failed to find the element: {:?}", locator)),
+// 고객 번호마다 일별 또는 시간대별 사용량 조회, page (달, 하루) 마다 바로 출력
+async fn fetch_usage(cli: &cli::Cli, request: UsageRequest) -> Result<()> {
+    let this_month = chrono::Local::now()
+        .date_naive()
+        .with_day(1)
+        .context("Invalid date")?;
+    let from = cli.fetch.from.unwrap_or(this_month);
+    let to = cli.fetch.to.unwrap_or(this_month.max(from));
+    match request {
+        UsageRequest::Daily if to < from => {
+            return Err(Failure::new(ErrorCode::Config, "--to is before --from").into())
+        }
+        UsageRequest::Hourly { from, to } if to < from => {
+            return Err(Failure::new(ErrorCode::Config, "--until is before --date").into())
+        }
+        _ => {}
+    }
+    // 결과 파일은 1개만 (고객 번호별 파일, sqlite 는 월별 청구 요금 전용)
+    if let Some(path) = &cli.fetch.output {
+        if output::sqlite::path(path).is_some()
+            || path.to_string_lossy().contains(cli::CUSTOMER_PLACEHOLDER)
+        {
+            return Err(Failure::new(
+                ErrorCode::Config,
+                "Daily and hourly usage are written to a single file, sqlite:// and {customer} outputs only support the monthly claims",
+            )
+            .into());
+        }
+    }
+    let config = load_config(cli).await?;
+    let row_filter = filter::RowFilter {
+        customers: cli.fetch.customer.clone(),
+        ..filter::RowFilter::default()
+    };
+    // 고객 번호 순서로 출력
+    let customers: BTreeSet<&String> = config
+        .user_nums
+        .iter()
+        .filter(|user_num| row_filter.matches_customer(user_num))
+        .collect();
+    let keyed = customers.len() > 1;
+
+    // 조회하면서 쓰므로 실패해도 그때까지의 row 는 남음
+    let writer: Box<dyn Write + Send> = match &cli.fetch.output {
+        Some(path) => Box::new(
+            std::fs::File::create(path).context(format!("Failed to write {}", path.display()))?,
+        ),
+        None => Box::new(std::io::stdout()),
     };
 
-    let element_hidden = timeout(duration, async {
-        loop {
-            match element.attr("style").await {
-                Ok(Some(style)) if style.contains("display: none") => {
-                    println!("Element is hidden (style=\"display: none\")");
-                    break;
+    with_login(
+        cli,
+        &config.user_id,
+        &config.user_pw,
+        async |client, profile, latency, _| match request {
+            UsageRequest::Daily => {
+                let mut stream: output::UsageStream<_, DailyUsage> =
+                    output::UsageStream::new(writer, cli.format, cli.stream, cli.locale, keyed)?;
+                let mut daily_url = None;
+                let fetched = async {
+                    for user_num in &customers {
+                        select_customer(client, user_num, profile, latency)
+                            .await
+                            .context(Step("select_customer"))?;
+                        stream.customer(user_num)?;
+                        let mut month = from;
+                        while month <= to {
+                            let days = fetch_daily_usage(
+                                client,
+                                TARGET_URL,
+                                &mut daily_url,
+                                month.year(),
+                                month.month(),
+                                profile,
+                                latency,
+                            )
+                            .await
+                            .context(Step("daily_usage"))?;
+                            stream.write_rows(&days)?;
+                            month = month
+                                .checked_add_months(chrono::Months::new(1))
+                                .context("Invalid month")?;
+                        }
+                    }
+                    Ok::<_, anyhow::Error>(())
                 }
-                Ok(_) => {
-                    eprintln!("Element is not hidden, retrying...");
-                }
-                Err(e) => {
-                    eprintln!("Failed to get style attribute: {}", e);
+                .await;
+                // 실패해도 그때까지의 row 로 문서를 닫음 (json 이 열린 채로 남지 않음)
+                let finished = stream.finish();
+                fetched.and(finished)
+            }
+            UsageRequest::Hourly { from, to } => {
+                let mut stream: output::UsageStream<_, HourlyUsage> =
+                    output::UsageStream::new(writer, cli.format, cli.stream, cli.locale, keyed)?;
+                let mut hourly_url = None;
+                let fetched = async {
+                    for user_num in &customers {
+                        select_customer(client, user_num, profile, latency)
+                            .await
+                            .context(Step("select_customer"))?;
+                        stream.customer(user_num)?;
+                        for date in from.iter_days().take_while(|date| *date <= to) {
+                            let intervals = fetch_hourly_usage(
+                                client,
+                                TARGET_URL,
+                                &mut hourly_url,
+                                date,
+                                profile,
+                                latency,
+                            )
+                            .await
+                            .context(Step("hourly_usage"))?;
+                            stream.write_rows(&intervals)?;
+                        }
+                    }
+                    Ok::<_, anyhow::Error>(())
                 }
+                .await;
+                // 실패해도 그때까지의 row 로 문서를 닫음 (json 이 열린 채로 남지 않음)
+                let finished = stream.finish();
+                fetched.and(finished)
             }
-            tokio::time::sleep(Duration::from_millis(500)).await;
-        }
-    })
-    .await;
-
-    if element_hidden.is_err() {
-        Err(anyhow::anyhow!(
-            "Failed to find the element within the given duration"
-        ))
-    } else {
-        Ok(())
+        },
+    )
+    .await?;
+    if let Some(path) = &cli.fetch.output {
+        eprintln!("Wrote results to {}", path.display());
     }
+    Ok(())
 }
 
-// select 요소에서 옵션 인덱스 찾기
-async fn get_option_index(
-    client: &Client,
-    select_locator: Locator<'_>,
-    text: &str,
-) -> Result<usize> {
-    let element = client
-        .find(select_locator)
-        .await
-        .context("Failed to find select element")?;
+// 고객 번호 1개로 화면, selector, parsing 을 확인하고 저장된 최근 달과 비교 (portal 변경 후 전체 실행 전)
+async fn run_canary(cli: &cli::Cli, account: Option<&str>, min_confidence: f64) -> Result<()> {
+    let config = load_config(cli).await?;
+    let customer_number = match account {
+        Some(account) => customer::validate(account)
+            .context(Failure::new(ErrorCode::Config, "Invalid canary account"))?,
+        None => config.user_nums.first().cloned().ok_or_else(|| {
+            Failure::new(
+                ErrorCode::Config,
+                "No customer number to run the canary with",
+            )
+        })?,
+    };
+    let stored = match cli.store.as_deref() {
+        Some(path) => store::Store::open(path)
+            .context(Failure::new(ErrorCode::Store, "Failed to open store"))?
+            .load_claims(&customer_number)?,
+        None => HashMap::new(),
+    };
+    let last_stored = stored
+        .values()
+        .max_by_key(|entry| entry.claim_date)
+        .cloned();
+    let data_dir = paths::DataDir::resolve(cli.data_dir.as_deref())?;
+    let flow = login_flow(cli)?;
+    let mut checks = canary::Checks::default();
+    let options = DriverOptions {
+        window: cli.window_size,
+        ..DriverOptions::default()
+    };
+    with_browser(cli, &config.user_id, options, async |client, _| {
+        let mut latency = PageLatency::new(&cli.step_timeout, &cli.page_ready);
+        let logged_in = sign_in(
+            client,
+            &config.user_id,
+            &config.user_pw,
+            &flow,
+            &mut latency,
+        )
+        .await;
+        checks.step("login", logged_in)?;
+
+        let warned = warnings::count();
+        let profile = &selectors::detect(client, cli.window_size, &cli.selector).await;
+        checks.layout(profile.name, warnings::count() > warned);
+        // 로그인 후 화면의 메뉴 selector (고객 정보는 --customer-info, 일별 사용량은 --dataset daily 에서만 사용)
+        for (key, critical) in [
+            ("customer_toggle", true),
+            ("monthly_claims", true),
+            ("customer_info", false),
+            ("daily_usage", false),
+        ] {
+            if let Some(selector) = profile.get(key) {
+                let matched = selector.matched(client).await;
+                checks.selector(key, matched, selector.0.len(), critical);
+            }
+        }
+
+        let selected = select_customer(client, &customer_number, profile, &mut latency).await;
+        checks.step("select_customer", selected)?;
+        let mut claim_url = None;
+        let opened =
+            open_monthly_claims(client, TARGET_URL, &mut claim_url, profile, &mut latency).await;
+        checks.step("monthly_claims_page", opened)?;
+        let matched = profile.year_submit.matched(client).await;
+        checks.selector("year_submit", matched, profile.year_submit.0.len(), true);
+
+        // 저장된 최근 달의 연도 (저장소가 없으면 올해)
+        let year = last_stored.as_ref().map_or_else(
+            || chrono::Local::now().year(),
+            |entry| entry.claim_date.year(),
+        );
+        let warned = warnings::count();
+        let rows = fetch_year_claims(client, YEAR_SELECT, year, profile, &mut latency, None).await;
+        let rows = checks.step("claims_table", rows)?;
+        let skipped = warnings::count() - warned;
+        match (rows.len(), skipped) {
+            (0, _) => checks.push(
+                "parse",
+                true,
+                canary::CheckStatus::Fail,
+                format!("No rows parsed for {}", year),
+            ),
+            (parsed, 0) => checks.push(
+                "parse",
+                true,
+                canary::CheckStatus::Pass,
+                format!("{} rows for {}", parsed, year),
+            ),
+            (parsed, skipped) => checks.push(
+                "parse",
+                true,
+                canary::CheckStatus::Warn,
+                format!("{} rows for {}, {} warnings", parsed, year, skipped),
+            ),
+        }
 
-    let options = element.find_all(Locator::XPath(".//option")).await?;
-    for (index, option) in options.iter().enumerate() {
-        if let Ok(option_text) = option.text().await {
-            if option_text == text {
-                return Ok(index);
+        let Some(stored) = &last_stored else {
+            checks.push(
+                "stored_values",
+                true,
+                canary::CheckStatus::Skip,
+                "No stored month to compare with (--store)",
+            );
+            return None;
+        };
+        let month = stored.claim_date;
+        let Some(record) = rows.iter().find(|entry| entry.claim_date == month) else {
+            checks.push(
+                "stored_values",
+                true,
+                canary::CheckStatus::Fail,
+                format!("{} is not listed any more", month.format("%Y-%m")),
+            );
+            return None;
+        };
+        let verification = verify::Verification::new(&customer_number, record, None, Some(stored));
+        checks.push(
+            "stored_values",
+            true,
+            if verification.status == "match" {
+                canary::CheckStatus::Pass
+            } else {
+                canary::CheckStatus::Fail
+            },
+            format!(
+                "{} {}",
+                verification.month,
+                serde_json::to_string(&verification.differences).unwrap_or_default()
+            ),
+        );
+
+        // 청구서 상세 화면 (bill_usage, bill_amount selector), 전체 조회에는 쓰지 않아 optional
+        match fetch_bill_detail(client, month, profile, &mut latency, None).await {
+            Ok((detail, _)) => {
+                let verification =
+                    verify::Verification::new(&customer_number, record, Some(&detail), None);
+                checks.push(
+                    "bill_detail",
+                    false,
+                    if verification.status == "match" {
+                        canary::CheckStatus::Pass
+                    } else {
+                        canary::CheckStatus::Fail
+                    },
+                    serde_json::to_string(&verification.differences).unwrap_or_default(),
+                );
             }
+            Err(e) => checks.push(
+                "bill_detail",
+                false,
+                canary::CheckStatus::Fail,
+                format!("{:#}", e),
+            ),
         }
+        Some(())
+    })
+    .await?;
+
+    let report = checks.finish(data_dir.run_id(), &customer_number, min_confidence);
+    report.save(&data_dir.canary_file())?;
+    println!("{}", canary::render(&report, cli.format)?);
+    if report.verdict == canary::Verdict::Hold {
+        return Err(Failure::new(
+            ErrorCode::Canary,
+            format!(
+                "Canary held the run for {} (confidence {:.2})",
+                customer_number, report.confidence
+            ),
+        )
+        .into());
     }
-    Err(anyhow::anyhow!("Option with text '{}' not found", text))
+    Ok(())
 }
 
-// 자식 요소들의 ID -> DashMap
-async fn get_children_ids_to_map(
+// 목록의 청구 월 링크로 청구서 상세를 열어 사용량, 청구 금액 조회 (driver_url 이 있으면 PDF 로 저장)
+// 상세가 새 창이면 닫고, 같은 창이면 뒤로 가서 목록 화면으로 복귀
+async fn fetch_bill_detail(
     client: &Client,
-    parent_xpath: &str,
-) -> Result<Arc<DashMap<String, ()>>> {
-    let script = format!(
-        r#"
-        let parent = document.evaluate("{}", document, null, XPathResult.FIRST_ORDERED_NODE_TYPE, null).singleNodeValue;
-        if (parent === null) {{
-            throw new Error('Parent element not found');
-        }}
-        let children = parent.querySelectorAll('tr');
-        let ids = [];
-        for (let i = 0; i < children.length; i++) {{
-            ids.push(children[i].id);
-        }}
-        return ids;
-        "#,
-        parent_xpath
+    month: NaiveDate,
+    profile: &selectors::SelectorProfile,
+    latency: &mut PageLatency,
+    driver_url: Option<&str>,
+) -> Result<(verify::BillDetail, Option<Vec<u8>>)> {
+    let link = format!(
+        "//*[@id='grid']/tbody/tr[td[1]/a/span[contains(normalize-space(.), '{}')]]/td[1]/a",
+        month.format("%Y년 %m월")
     );
+    let list_window = client.window().await?;
+    let before = client.windows().await?;
+    click_element(client, Locator::XPath(&link)).await?;
+
+    // 새 창 (popup) 이 열리면 전환
+    let deadline = Instant::now() + Duration::from_secs(3);
+    let popup = loop {
+        let opened = client
+            .windows()
+            .await?
+            .into_iter()
+            .find(|window| !before.contains(window));
+        if opened.is_some() || Instant::now() >= deadline {
+            break opened;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    };
+    if let Some(popup) = &popup {
+        client.switch_to_window(popup.clone()).await?;
+    }
 
-    let result = client
-        .execute(&script, vec![])
-        .await
-        .context("Failed to execute script to get children IDs")?;
+    let started = Instant::now();
+    let detail = async {
+        let (duration, poll) = (latency.timeout("bill"), latency.poll_interval("bill"));
+        let usage = profile.bill_usage.wait(client, duration, poll).await?;
+        latency.record("bill", started.elapsed());
+        let amount = profile.bill_amount.resolve(client).await?;
 
-    let ids: Vec<String> = result
-        .as_array()
-        .context("Expected an array from the script result")?
-        .iter()
-        .filter_map(|v| v.as_str())
-        .filter(|s| !s.is_empty())
-        .map(|s| s.to_string())
-        .collect::<Vec<String>>();
+        let usage_text = get_text_by_locator(client, usage.locator())
+            .await
+            .context("Failed to read the bill usage")?;
+        let amount_text = get_text_by_locator(client, amount.locator())
+            .await
+            .context("Failed to read the bill amount")?;
+        let mut detail = verify::BillDetail::parse(&usage_text, &amount_text)?;
+        // 요금 항목은 --detailed 에서만 쓰므로 읽지 못해도 사용량, 청구 금액은 반환
+        if let Ok(fields) = read_labeled_fields(client, "bill detail").await {
+            detail.breakdown = Breakdown::from_fields(&fields);
+        }
+        let pdf = match driver_url {
+            Some(url) => Some(archive::print_pdf(client, url).await?),
+            None => None,
+        };
+        Ok::<_, anyhow::Error>((detail, pdf))
+    }
+    .await;
 
-    let map = Arc::new(DashMap::new());
-    for id in ids {
-        map.insert(id, ());
+    // 목록 화면으로 복귀 (상세 조회 실패 시에도)
+    match popup {
+        Some(_) => {
+            client.close_window().await?;
+            client.switch_to_window(list_window).await?;
+        }
+        None => {
+            client.back().await?;
+            wait_for_loading(client, latency, "claims").await?;
+        }
     }
+    detail
+}
+
+// 로그인 후 browser cookie 저장 (--headed 면 기기 인증 등을 직접 마친 뒤 저장)
+async fn export_session(cli: &cli::Cli, out: &Path, headed: bool) -> Result<()> {
+    let credentials = load_credentials(cli).await?;
+    let flow = login_flow(cli)?;
+    let options = DriverOptions {
+        headless: !headed,
+        ..DriverOptions::default()
+    };
+    let cookies = with_browser(cli, &credentials.user_id, options, async |client, _| {
+        let mut latency = PageLatency::default();
+        sign_in(
+            client,
+            &credentials.user_id,
+            &credentials.user_pw,
+            &flow,
+            &mut latency,
+        )
+        .await
+        .context(Step("login"))?;
 
-    Ok(map)
+        if headed {
+            println!("Complete any verification in the browser, then press Enter to export");
+            let mut line = String::new();
+            std::io::stdin().read_line(&mut line)?;
+        }
+        session::export(client).await
+    })
+    .await??;
+    session::write(out, &cookies)?;
+    println!("Exported {} cookies to {}", cookies.len(), out.display());
+    Ok(())
 }
 
-// get text from locator
-async fn get_text_by_locator(client: &Client, locator: Locator<'_>) -> Option<String> {
-    match client.find(locator).await.ok() {
-        Some(element) => element.text().await.ok(),
-        None => None,
-    }
+// export 한 cookie 파일을 이 host 의 session 으로 등록
+fn import_session(cli: &cli::Cli, input: &Path) -> Result<()> {
+    let cookies = session::read(input)?;
+    let data_dir = paths::DataDir::resolve(cli.data_dir.as_deref())?;
+    data_dir.ensure()?;
+    let path = data_dir.session_file();
+    session::write(&path, &cookies)?;
+    println!("Imported {} cookies to {}", cookies.len(), path.display());
+    Ok(())
 }
 
-// get href from locator
-async fn get_href_by_locator(client: &Client, locator: Locator<'_>) -> Option<String> {
-    match client.find(locator).await.ok() {
-        Some(element) => element.attr("href").await.ok().flatten(),
-        None => None,
+// 저장된 data endpoint 를 로그인된 세션으로 직접 호출 (browser 는 인증에만 사용)
+async fn fetch_endpoints(cli: &cli::Cli) -> Result<()> {
+    let config = load_config(cli).await?;
+    let data_dir = paths::DataDir::resolve(cli.data_dir.as_deref())?;
+    let stored = endpoints::load(&data_dir.endpoints_file())?;
+    if stored.is_empty() {
+        return Err(Failure::new(
+            ErrorCode::Config,
+            "No stored endpoints, run with --discover-endpoints first",
+        )
+        .into());
     }
-}
+    let responses = with_login(
+        cli,
+        &config.user_id,
+        &config.user_pw,
+        async |client, profile, latency, _| {
+            // 고객 번호별 endpoint 응답
+            let mut responses = BTreeMap::new();
+            for user_num in &config.user_nums {
+                select_customer(client, user_num, profile, latency)
+                    .await
+                    .context(Step("select_customer"))?;
+                let mut by_url = Map::new();
+                for endpoint in &stored {
+                    let response = endpoints::fetch(client, endpoint)
+                        .await
+                        .context(Step("fetch_endpoint"))?;
+                    by_url.insert(endpoint.url.clone(), response);
+                }
+                responses.insert(user_num.clone(), by_url);
+            }
+            Ok(responses)
+        },
+    )
+    .await?;
 
-// parsing 청구 기간
-fn parse_date(date_str: &str) -> Result<NaiveDate> {
-    // 일자를 1로 설정
-    let date_with_day = format!("{} 01일", date_str);
-    NaiveDate::parse_from_str(&date_with_day, "%Y년 %m월 %d일").context("Failed to parse date")
+    println!("{}", serde_json::to_string_pretty(&responses)?);
+    Ok(())
 }
 
-// parsing 사용량
-fn parse_use_kwh(kwh_str: &str) -> Result<f64> {
-    let cleaned_str = kwh_str.replace(",", "").replace("kWh", "");
-    cleaned_str
-        .parse::<f64>()
-        .context("Failed to parse use kWh")
+// 재시도 가능한 실패 (timeout, portal, driver) 면 backoff 후 전체 조회 재시작
+async fn run_with_retries(cli: &cli::Cli, backfill_year: Option<i32>) -> Result<()> {
+    // portal 변경 후 canary 가 통과하기 전에는 전체 조회하지 않음
+    if let Some(max_age_hours) = cli.require_canary {
+        let data_dir = paths::DataDir::resolve(cli.data_dir.as_deref())?;
+        canary::require(&data_dir.canary_file(), max_age_hours)?;
+    }
+    let mut attempt = 0;
+    loop {
+        match run_recorded(cli, backfill_year).await {
+            Err(e) if attempt < cli.run_retries && error::is_retryable(&e) => {
+                attempt += 1;
+                let delay = 2u32
+                    .checked_pow(attempt - 1)
+                    .and_then(|factor| RUN_RETRY_DELAY.checked_mul(factor))
+                    .map_or(RUN_RETRY_MAX_DELAY, |delay| delay.min(RUN_RETRY_MAX_DELAY));
+                // 재시도 시점이 --blackout 시간대면 끝날 때까지 미룸
+                let retry_at =
+                    chrono::Utc::now() + chrono::TimeDelta::seconds(delay.as_secs() as i64);
+                let delay =
+                    delay + blackout::remaining(&cli.blackout, retry_at).unwrap_or_default();
+                warnings::emit(
+                    warnings::WarningKind::Retry,
+                    format!(
+                        "Run failed, retrying in {}s ({}/{}): {:#}",
+                        delay.as_secs(),
+                        attempt,
+                        cli.run_retries,
+                        e
+                    ),
+                );
+                tokio::time::sleep(delay).await;
+            }
+            result => return result,
+        }
+    }
 }
 
-// parsing 요금
-fn parse_paid(amount_str: &str) -> Result<i64> {
-    let amount_part = amount_str.split('원').next().unwrap_or(amount_str);
-
-    let amount = amount_part.replace(",", "").replace(".", "");
-    amount.parse::<i64>().context("Failed to parse amount")
+// --blackout 시간대 안이면 끝날 때까지 대기 (daemon, serve, watch 의 실행 시작 전)
+async fn wait_for_blackout(windows: &[blackout::Window], what: &str) {
+    if let Some(delay) = blackout::remaining(windows, chrono::Utc::now()) {
+        let windows: Vec<String> = windows.iter().map(ToString::to_string).collect();
+        println!(
+            "{} is in a blackout window ({} KST), waiting {} minutes",
+            what,
+            windows.join(", "),
+            delay.as_secs().div_ceil(60)
+        );
+        tokio::time::sleep(delay).await;
+    }
 }
 
-// get_and_parsing_data year
-async fn extract_data_year(client: &Client, parent_id: &str) -> Result<PpData> {
-    let claim_date_row = get_text_by_locator(
-        client,
-        Locator::XPath(&format!("//*[@id='{}']/td[1]/a/span", parent_id)),
-    )
-    .await;
+// 저장소가 있으면 실행 시작, 종료를 runs table 에 기록 (기록 실패는 경고만)
+async fn run_recorded(cli: &cli::Cli, backfill_year: Option<i32>) -> Result<()> {
+    let command = if backfill_year.is_some() {
+        "backfill"
+    } else {
+        "run"
+    };
+    // 실행마다 새 run id (artifact 디렉터리, runs table, hook 에 사용)
+    let data_dir = paths::DataDir::resolve(cli.data_dir.as_deref())?;
+    eprintln!("Run ID: {}", data_dir.run_id());
+    let recorder = cli
+        .store
+        .as_deref()
+        .and_then(|path| store::Store::open(path).ok())
+        .and_then(|store| match store.start_run(command, data_dir.run_id()) {
+            Ok(id) => Some((store, id)),
+            Err(e) => {
+                eprintln!("Failed to record run: {:#}", e);
+                None
+            }
+        });
+
+    let mut stats = store::RunStats::default();
+    let result = match run(cli, backfill_year, &data_dir, &mut stats).await {
+        // CAPTCHA 는 한 번 직접 풀고 저장한 session 으로 다시 실행
+        Err(e) if error::code(&e) == ErrorCode::CaptchaRequired && interactive(cli) => {
+            eprintln!("{:#}", e);
+            stats = store::RunStats::default();
+            match solve_captcha(cli, &data_dir).await {
+                Ok(()) => run(cli, backfill_year, &data_dir, &mut stats).await,
+                Err(e) => Err(e),
+            }
+        }
+        result => result,
+    };
 
-    let usage_row = get_text_by_locator(
-        client,
-        Locator::XPath(&format!("//*[@id='{}']/td[4]", parent_id)),
-    )
-    .await;
+    if let Some((store, id)) = recorder {
+        let (status, error) = match &result {
+            Ok(()) => (output::Status::Ok, None),
+            Err(e) if error::code(e) == ErrorCode::PartialFailure => {
+                (output::Status::Partial, Some(format!("{:#}", e)))
+            }
+            Err(e) => (output::Status::Failed, Some(format!("{:#}", e))),
+        };
+        if let Err(e) = store.finish_run(id, &stats, status, error.as_deref()) {
+            eprintln!("Failed to record run: {:#}", e);
+        }
+    }
+    result
+}
 
-    let paid_row = get_text_by_locator(
-        client,
-        Locator::XPath(&format!("//*[@id='{}']/td[8]", parent_id)),
-    )
-    .await;
+// --interactive 이고 사람이 입력할 수 있는 terminal
+fn interactive(cli: &cli::Cli) -> bool {
+    cli.interactive && std::io::stdin().is_terminal()
+}
 
-    let claim_date = claim_date_row.map_or(Ok(Default::default()), |date| parse_date(&date))?;
-    let usage = usage_row.map_or(Ok(0.0), |kwh| parse_use_kwh(&kwh))?;
-    let paid = paid_row.map_or(Ok(0), |paid| parse_paid(&paid))?;
+// 보이는 browser 에서 CAPTCHA 를 풀고 로그인한 session 을 session import 위치에 저장
+async fn solve_captcha(cli: &cli::Cli, data_dir: &paths::DataDir) -> Result<()> {
+    let credentials = load_credentials(cli).await?;
+    let options = DriverOptions {
+        headless: false,
+        ..DriverOptions::default()
+    };
+    let cookies = with_browser(cli, &credentials.user_id, options, async |client, _| {
+        login_flow(cli)?.pre_login.run(client).await?;
+        client
+            .goto(&format!("{}/intro.do", TARGET_URL))
+            .await
+            .context(Failure::new(ErrorCode::Navigation, "Failed to navigate"))?;
 
-    Ok(PpData {
-        claim_date,
-        usage,
-        paid,
+        println!("Solve the CAPTCHA and log in in the browser, then press Enter to continue");
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        session::export(client).await
     })
+    .await??;
+    data_dir.ensure()?;
+    let path = data_dir.session_file();
+    session::write(&path, &cookies)?;
+    println!(
+        "Saved {} cookies to {}, retrying",
+        cookies.len(),
+        path.display()
+    );
+    Ok(())
 }
 
-// parse_data_from_parent_ids
-async fn parse_data_from_table(client: &Arc<Client>, parent_xpath: &str) -> Result<Vec<PpData>> {
-    let mut tasks = vec![];
-
-    let map = get_children_ids_to_map(&client, parent_xpath).await?;
-
-    for entry in map.iter() {
-        let id = entry.key().clone();
-        let client = Arc::clone(&client);
-        let task = tokio::spawn(async move { extract_data_year(&client, &id).await });
-        tasks.push(task);
+// 보존 기간 (--keep-years, --keep-artifact-days) 이 지난 청구 data, artifact 삭제
+fn apply_retention(
+    cli: &cli::Cli,
+    data_dir: &paths::DataDir,
+    store: Option<&store::Store>,
+) -> Result<()> {
+    if let (Some(years), Some(store)) = (cli.keep_years, store) {
+        let cutoff = chrono::Local::now()
+            .date_naive()
+            .checked_sub_months(chrono::Months::new(years * 12))
+            .context("Invalid --keep-years")?;
+        let removed = store.prune_claims(cutoff)?;
+        println!("Pruned {} monthly claims before {}", removed, cutoff);
     }
+    if let Some(days) = cli.keep_artifact_days {
+        let removed = data_dir.clean(Duration::from_secs(days * 24 * 60 * 60))?;
+        println!("Pruned {} artifacts older than {} days", removed, days);
+    }
+    Ok(())
+}
 
-    let results = futures::future::join_all(tasks).await;
+// 주기적으로 조회, 예상 청구 시점 전후에는 새 청구 월이 나올 때까지 자주 조회
+async fn watch(cli: &cli::Cli, schedule: &watch::Schedule) -> Result<()> {
+    let path = cli
+        .store
+        .as_deref()
+        .ok_or_else(|| Failure::new(ErrorCode::Config, "watch requires --store"))?;
+    let store =
+        store::Store::open(path).context(Failure::new(ErrorCode::Store, "Failed to open store"))?;
+    if cli.notify.is_empty() {
+        eprintln!("No --notify sink configured, new bills will only be stored");
+    }
 
-    let mut data_vec = Vec::new();
-    for result in results {
-        match result {
-            Ok(Ok(data)) => data_vec.push(data),
-            Ok(Err(e)) => eprintln!("Failed to extract data: {}", e),
-            Err(e) => eprintln!("Task failed: {}", e),
+    loop {
+        wait_for_blackout(&cli.blackout, "Watch").await;
+        let before = store.latest_claim_date()?;
+        // 실패해도 다음 주기에 다시 조회
+        if let Err(e) = run_with_retries(cli, None).await {
+            eprintln!("Watch run failed: {:#}", e);
+        }
+        let latest = store.latest_claim_date()?;
+        if let (Some(latest), true) = (latest, latest > before) {
+            println!("New billing month arrived: {}", latest.format("%Y-%m"));
         }
-    }
 
-    Ok(data_vec)
+        let (delay, reason) = schedule.next_poll(latest, chrono::Local::now().date_naive());
+        println!(
+            "Next check in {} minutes ({})",
+            delay.as_secs() / 60,
+            reason
+        );
+        tokio::time::sleep(delay).await;
+    }
 }
 
-// options 들의 결과값 parsing
-async fn parsing_options_data(
-    client: &Arc<Client>,
-    select_locator: Locator<'_>,
-    option_index: &usize,
-    chromedriver_process: &mut Child,
-) -> Result<Vec<PpData>> {
-    // option 요소
-    let options = client
-        .find(select_locator)
-        .await
-        .context("Failed to find select element")?
-        .find_all(Locator::Css("option"))
-        .await
-        .context("Failed to find options")?;
+// pipeline 마다 자기 주기로 조회, 전달 (한 pipeline 이 실패해도 계속)
+async fn daemon(cli: &cli::Cli, path: &Path) -> Result<()> {
+    let pipelines = pipelines::load(path)?;
+    let mut schedule = pipelines::Schedule::new(&pipelines);
+    for pipeline in &pipelines {
+        println!(
+            "Pipeline {}: every {} minutes",
+            pipeline.name, pipeline.every_minutes
+        );
+    }
 
-    let mut vec: Vec<PpData> = Vec::with_capacity(options.len() * 12);
+    loop {
+        let (index, at) = schedule.next();
+        tokio::time::sleep_until(at).await;
 
-    // option_index to last index data parsing
-    for i in *option_index..options.len() {
-        // 옵션 선택
-        options[i]
-            .click()
-            .await
-            .context("Failed to select option")?;
-
-        // 조회 버튼 클릭
-        // /html/body/div[2]/div[3]/div[2]/p/span[1]/a
-        click_element(&client, Locator::XPath("//*[@id='txt']/div[2]/p/span[1]/a")).await?;
-
-        // 로딩 대기
-        wait_for_element_display_none(
-            &client,
-            Locator::Id("backgroundLayer"),
-            chromedriver_process,
-            Duration::from_secs(10),
+        let pipeline = &pipelines[index];
+        let pipeline_cli = pipeline.cli(cli);
+        wait_for_blackout(
+            &pipeline_cli.blackout,
+            &format!("Pipeline {}", pipeline.name),
         )
-        .await?;
+        .await;
+        println!("Running pipeline {}", pipeline.name);
+        let result = match pipeline.fetch {
+            pipelines::Fetcher::MonthlyBills => run_with_retries(&pipeline_cli, None).await,
+        };
+        if let Err(e) = result {
+            eprintln!("Pipeline {} failed: {:#}", pipeline.name, e);
+        }
+        schedule.done(index, pipeline);
+    }
+}
+
+// 저장소 HTTP API, --pipelines 가 있으면 고객 번호 단위 job 을 대기열로 조회
+// tenant 를 돌아가며 조회하고 API 요청 (on-demand) 을 주기 조회보다 먼저 처리
+#[cfg(feature = "server")]
+async fn serve(
+    cli: &cli::Cli,
+    listen: std::net::SocketAddr,
+    pipelines: Option<&Path>,
+    workers: usize,
+) -> Result<()> {
+    let store = cli
+        .store
+        .clone()
+        .ok_or_else(|| Failure::new(ErrorCode::Config, "serve requires --store"))?;
+    let Some(path) = pipelines else {
+        return server::serve(listen, store, cli.money_format, thresholds(cli), None).await;
+    };
 
-        // data parsing
-        let mut data = parse_data_from_table(&client, "//*[@id='grid']/tbody").await?;
-        vec.append(&mut data);
+    let pipelines = pipelines::load(path)?;
+    // customers 가 없는 pipeline 은 --customer (PP_NUMBER) 의 고객 번호
+    let mut assignments = Vec::with_capacity(pipelines.len());
+    for pipeline in &pipelines {
+        let customer_numbers = match (pipeline.customers.is_empty(), cli.fetch.customer.is_empty())
+        {
+            (false, _) => pipeline.customers.clone(),
+            (true, false) => cli.fetch.customer.clone(),
+            (true, true) => config::customer_numbers().context(Failure::new(
+                ErrorCode::Config,
+                format!("Pipeline {} has no customers", pipeline.name),
+            ))?,
+        };
+        println!(
+            "Pipeline {}: {} customers of tenant {}, every {} minutes",
+            pipeline.name,
+            customer_numbers.len(),
+            pipeline.tenant(),
+            pipeline.every_minutes
+        );
+        assignments.push(scheduler::Assignment {
+            pipeline: pipeline.name.clone(),
+            tenant: pipeline.tenant().to_string(),
+            customer_numbers,
+        });
     }
+    let queue = Arc::new(scheduler::Queue::new(assignments));
 
-    Ok(vec)
+    // pipeline 주기마다 고객 번호 전체를 background job 으로 추가
+    let refresh = async {
+        let mut schedule = pipelines::Schedule::new(&pipelines);
+        loop {
+            let (index, at) = schedule.next();
+            tokio::time::sleep_until(at).await;
+            let pipeline = &pipelines[index];
+            let added = queue.refresh(&pipeline.name);
+            println!("Queued {} jobs of pipeline {}", added, pipeline.name);
+            schedule.done(index, pipeline);
+        }
+    };
+
+    // job 마다 고객 번호 1개만 조회 (한 job 이 실패해도 계속)
+    let worker = |_| async {
+        loop {
+            let job = queue.next().await;
+            let Some(pipeline) = pipelines
+                .iter()
+                .find(|pipeline| pipeline.name == job.pipeline)
+            else {
+                let error = format!("Unknown pipeline {}", job.pipeline);
+                queue.finish(job, Err(error));
+                continue;
+            };
+            println!(
+                "Running {} job of pipeline {} for {}",
+                job.priority.as_str(),
+                job.pipeline,
+                job.customer_number
+            );
+            let pipeline_cli = pipeline.cli(cli);
+            // 시간대가 끝날 때까지 tenant 의 다른 job 도 시작하지 않음
+            wait_for_blackout(
+                &pipeline_cli.blackout,
+                &format!("Pipeline {}", pipeline.name),
+            )
+            .await;
+            let job_cli = cli::Cli {
+                fetch: cli::FetchArgs {
+                    customer: vec![job.customer_number.clone()],
+                    ..pipeline_cli.fetch.clone()
+                },
+                ..pipeline_cli
+            };
+            let result = match pipeline.fetch {
+                pipelines::Fetcher::MonthlyBills => run_with_retries(&job_cli, None).await,
+            };
+            if let Err(e) = &result {
+                eprintln!(
+                    "Pipeline {} failed for {}: {:#}",
+                    job.pipeline, job.customer_number, e
+                );
+            }
+            queue.finish(job, result.map_err(|e| format!("{:#}", e)));
+        }
+    };
+    let workers = futures::future::join_all((0..workers.max(1)).map(worker));
+
+    let server = server::serve(
+        listen,
+        store,
+        cli.money_format,
+        thresholds(cli),
+        Some(Arc::clone(&queue)),
+    );
+    tokio::select! {
+        result = server => result,
+        _ = refresh => Ok(()),
+        _ = workers => Ok(()),
+    }
+}
+
+// 설정 파일, 고객 번호를 조회 없이 검사 (잘못된 항목마다 위치 출력, 하나라도 있으면 실패)
+fn validate_config(cli: &cli::Cli, pipelines: Option<&Path>) -> Result<()> {
+    let mut checks: Vec<(String, Result<()>)> = Vec::new();
+    if let Some(path) = pipelines {
+        checks.push((
+            format!("pipelines {}", path.display()),
+            pipelines::load(path).map(|_| ()),
+        ));
+    }
+    if let Some(path) = &cli.pre_login {
+        checks.push((
+            format!("pre-login {}", path.display()),
+            prelogin::Script::load(path).map(|_| ()),
+        ));
+    }
+    for number in &cli.fetch.customer {
+        checks.push((
+            format!("customer {}", number),
+            customer::validate(number).map(|_| ()),
+        ));
+    }
+    if checks.is_empty() {
+        return Err(Failure::new(
+            ErrorCode::Config,
+            "Nothing to validate, pass --pipelines, --pre-login or --customer",
+        )
+        .into());
+    }
+
+    let total = checks.len();
+    let mut failed = 0;
+    for (name, result) in checks {
+        match result {
+            Ok(()) => println!("ok     {}", name),
+            Err(e) => {
+                failed += 1;
+                println!("error  {}: {:#}", name, e);
+            }
+        }
+    }
+    if failed > 0 {
+        return Err(Failure::new(
+            ErrorCode::Config,
+            format!("{} of {} config checks failed", failed, total),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+// 이전 schema version 의 pipelines 파일을 현재 형식으로 다시 씀 (원본은 <파일>.v<version>.bak)
+fn migrate_config(path: &Path, dry_run: bool) -> Result<()> {
+    let text =
+        std::fs::read_to_string(path).context(format!("Failed to read {}", path.display()))?;
+    let value: Value = config::from_json(&text, path, "pipelines file")?;
+    let version = pipelines::schema_version(&value)?;
+    if version == pipelines::SCHEMA_VERSION {
+        println!(
+            "{} is already at schema version {}",
+            path.display(),
+            version
+        );
+        return Ok(());
+    }
+
+    let (migrated, applied) = pipelines::migrate(value, version);
+    let migrated = pipelines::to_text(&migrated)?;
+    // 변환 결과가 현재 version 으로 읽히는지 쓰기 전에 확인
+    pipelines::parse(&migrated, path)?;
+    for step in &applied {
+        eprintln!("- {}", step);
+    }
+    if dry_run {
+        print!("{}", migrated);
+        return Ok(());
+    }
+
+    let backup = PathBuf::from(format!("{}.v{}.bak", path.display(), version));
+    std::fs::copy(path, &backup).context(format!("Failed to write {}", backup.display()))?;
+    std::fs::write(path, migrated).context(format!("Failed to write {}", path.display()))?;
+    println!(
+        "Migrated {} from schema version {} to {} (original kept as {})",
+        path.display(),
+        version,
+        pipelines::SCHEMA_VERSION,
+        backup.display()
+    );
+    Ok(())
+}
+
+// 수동으로 내려받은 Excel 의 청구 이력을 저장소에 병합 (겹치는 달은 --merge-policy)
+#[cfg(feature = "excel")]
+fn import_excel(cli: &cli::Cli, files: &[PathBuf], customer: &str) -> Result<()> {
+    let path = cli
+        .store
+        .as_deref()
+        .ok_or_else(|| Failure::new(ErrorCode::Config, "import requires --store"))?;
+    let customer = customer::validate(customer)
+        .context(Failure::new(ErrorCode::Config, "Invalid --customer"))?;
+    let mut store = store::Store::open(path)
+        .context(Failure::new(ErrorCode::Store, "Failed to open store"))?
+        .with_merge_policy(cli.merge_policy);
+
+    for file in files {
+        let data = excel::read(file)?;
+        let written = store.merge_claims(&customer, &data, store::ClaimSource::Imported)?;
+        println!(
+            "Imported {} of {} months from {} ({} unchanged or kept)",
+            written,
+            data.len(),
+            file.display(),
+            data.len() - written
+        );
+    }
+    Ok(())
+}
+
+// 보관된 연도 page 를 현재 parser 로 다시 읽어 저장소 수정 (같은 달은 최근 실행 값)
+fn reparse(cli: &cli::Cli, dry_run: bool) -> Result<()> {
+    let path = cli
+        .store
+        .as_deref()
+        .ok_or_else(|| Failure::new(ErrorCode::Config, "reparse requires --store"))?;
+    let mut store = store::Store::open(path)
+        .context(Failure::new(ErrorCode::Store, "Failed to open store"))?
+        .with_merge_policy(cli.merge_policy);
+    let data_dir = paths::DataDir::resolve(cli.data_dir.as_deref())?;
+    let archived = extract::archived(&data_dir.raw_pages_dir())?;
+    if archived.is_empty() {
+        return Err(Failure::new(
+            ErrorCode::Config,
+            "No archived pages, run with --archive-raw first",
+        )
+        .into());
+    }
+    let today = chrono::Local::now().date_naive();
+
+    for (customer_number, pages) in archived {
+        let mut months = BTreeMap::new();
+        for (_, page) in &pages {
+            for entry in extract::parse_page(page).into_rows() {
+                months.insert(entry.claim_date, entry);
+            }
+        }
+        let mut data_vec: Vec<PpData> = months.into_values().collect();
+        cli.current_month.apply(&mut data_vec, today);
+
+        let stored = store.load_claims(&customer_number)?;
+        let changes: Vec<store::ClaimChange> = data_vec
+            .into_iter()
+            .filter(|entry| !entry.provisional)
+            .filter(|entry| {
+                !stored
+                    .get(&entry.claim_date)
+                    .is_some_and(|stored| stored.same_values(entry))
+            })
+            .map(|entry| store::ClaimChange {
+                before: stored.get(&entry.claim_date).cloned(),
+                after: entry,
+            })
+            .collect();
+        for change in &changes {
+            println!("{} {}", customer_number, change);
+        }
+        if changes.is_empty() {
+            println!("{}: no changes in {} pages", customer_number, pages.len());
+            continue;
+        }
+        if dry_run {
+            println!(
+                "{}: {} months would change from {} pages",
+                customer_number,
+                changes.len(),
+                pages.len()
+            );
+            continue;
+        }
+
+        let data_vec: Vec<PpData> = changes.iter().map(|change| change.after.clone()).collect();
+        store.merge_claims(&customer_number, &data_vec, store::ClaimSource::Scraped)?;
+        // merge policy 로 유지된 달은 변경 기록에서 제외
+        let merged = store.load_claims(&customer_number)?;
+        let applied: Vec<store::ClaimChange> = changes
+            .into_iter()
+            .filter(|change| {
+                merged
+                    .get(&change.after.claim_date)
+                    .is_some_and(|stored| stored.same_values(&change.after))
+            })
+            .collect();
+        store.log_changes(&customer_number, &applied, "reparse")?;
+        println!(
+            "{}: updated {} months from {} pages",
+            customer_number,
+            applied.len(),
+            pages.len()
+        );
+    }
+    Ok(())
+}
+
+// 저장된 청구 data 로 월간 보고서 작성
+async fn render_report(cli: &cli::Cli, options: &report::ReportOptions, out: &Path) -> Result<()> {
+    let path = cli
+        .store
+        .as_deref()
+        .ok_or_else(|| Failure::new(ErrorCode::Config, "report requires --store"))?;
+    let store =
+        store::Store::open(path).context(Failure::new(ErrorCode::Store, "Failed to open store"))?;
+    let customers = store
+        .customers()?
+        .into_iter()
+        .map(|customer_number| {
+            let rows = store.load_claims(&customer_number)?.into_values().collect();
+            Ok((customer_number, rows))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    report::write(&report::render_html(&customers, options), out).await?;
+    println!(
+        "Wrote report for {} customers: {}",
+        customers.len(),
+        out.display()
+    );
+    Ok(())
+}
+
+// 보존 기간 정책만 적용
+fn prune(cli: &cli::Cli) -> Result<()> {
+    if cli.keep_years.is_none() && cli.keep_artifact_days.is_none() {
+        return Err(Failure::new(
+            ErrorCode::Config,
+            "prune requires --keep-years or --keep-artifact-days",
+        )
+        .into());
+    }
+    if cli.keep_years.is_some() && cli.store.is_none() {
+        return Err(Failure::new(ErrorCode::Config, "--keep-years requires --store").into());
+    }
+
+    let data_dir = paths::DataDir::resolve(cli.data_dir.as_deref())?;
+    let store = cli
+        .store
+        .as_deref()
+        .map(store::Store::open)
+        .transpose()
+        .context(Failure::new(ErrorCode::Store, "Failed to open store"))?;
+    apply_retention(cli, &data_dir, store.as_ref())
+}
+
+// 실행 기록 조회
+fn list_runs(cli: &cli::Cli, limit: usize) -> Result<()> {
+    let path = cli
+        .store
+        .as_deref()
+        .ok_or_else(|| Failure::new(ErrorCode::Config, "runs list requires --store"))?;
+    let store =
+        store::Store::open(path).context(Failure::new(ErrorCode::Store, "Failed to open store"))?;
+    println!(
+        "{}",
+        output::render_runs(&store.list_runs(limit)?, cli.format)?
+    );
+    Ok(())
+}
+
+// data 최신 여부 기준
+fn thresholds(cli: &cli::Cli) -> freshness::Thresholds {
+    freshness::Thresholds {
+        scrape_age_hours: cli.stale_after_hours,
+        month_lag: cli.max_month_lag,
+    }
+}
+
+// 고객 번호별 data 최신 여부 출력, 오래된 고객 번호가 있으면 실패
+fn status(cli: &cli::Cli) -> Result<()> {
+    let path = cli
+        .store
+        .as_deref()
+        .ok_or_else(|| Failure::new(ErrorCode::Config, "status requires --store"))?;
+    let store =
+        store::Store::open(path).context(Failure::new(ErrorCode::Store, "Failed to open store"))?;
+    let entries = freshness::compute(&store, thresholds(cli), chrono::Utc::now())?;
+    println!("{}", output::render_freshness(&entries, cli.format)?);
+
+    let stale: Vec<&str> = entries
+        .iter()
+        .filter(|entry| entry.health == freshness::Health::Red)
+        .map(|entry| entry.customer_number.as_str())
+        .collect();
+    if !stale.is_empty() {
+        return Err(Failure::new(
+            ErrorCode::Stale,
+            format!("Stale data for customers: {}", stale.join(", ")),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+// 고객 번호별 검침일, 예상 청구일, 납기일 calendar 파일 (저장소가 있으면 마지막 청구 월 다음 달부터)
+fn write_calendar(cli: &cli::Cli, options: &calendar::CalendarOptions, out: &Path) -> Result<()> {
+    let customers = if cli.fetch.customer.is_empty() {
+        config::customer_numbers()
+            .context(Failure::new(ErrorCode::Config, "Invalid configuration"))?
+    } else {
+        cli.fetch.customer.clone()
+    };
+    let store = cli
+        .store
+        .as_deref()
+        .map(store::Store::open)
+        .transpose()
+        .context(Failure::new(ErrorCode::Store, "Failed to open store"))?;
+
+    let this_month = chrono::Local::now()
+        .date_naive()
+        .with_day(1)
+        .context("Invalid date")?;
+    let mut starts = Vec::new();
+    for customer_number in customers {
+        let latest = match &store {
+            Some(store) => store.load_claims(&customer_number)?.into_keys().max(),
+            None => None,
+        };
+        let first = latest
+            .and_then(|latest| {
+                latest
+                    .with_day(1)?
+                    .checked_add_months(chrono::Months::new(1))
+            })
+            .unwrap_or(this_month);
+        starts.push((customer_number, first));
+    }
+
+    std::fs::write(out, calendar::render(&starts, options))
+        .context(format!("Failed to write {}", out.display()))?;
+    println!("Calendar saved: {}", out.display());
+    Ok(())
+}
+
+// Grafana dashboard, provisioning 파일 생성
+fn grafana_init(
+    cli: &cli::Cli,
+    source: grafana::GrafanaSource,
+    clickhouse_url: Option<&str>,
+    out: &Path,
+) -> Result<()> {
+    let location = match source {
+        grafana::GrafanaSource::Sqlite => {
+            let store = cli.store.as_deref().ok_or_else(|| {
+                Failure::new(
+                    ErrorCode::Config,
+                    "grafana init requires --store for SQLite",
+                )
+            })?;
+            // Grafana 는 다른 작업 디렉터리에서 실행되므로 절대 경로
+            std::path::absolute(store)
+                .context(format!("Invalid store path: {}", store.display()))?
+                .display()
+                .to_string()
+        }
+        grafana::GrafanaSource::Clickhouse => clickhouse_url
+            .ok_or_else(|| {
+                Failure::new(
+                    ErrorCode::Config,
+                    "grafana init --datasource clickhouse requires --clickhouse-url",
+                )
+            })?
+            .to_string(),
+    };
+
+    for path in grafana::init(&grafana::GrafanaTarget {
+        source,
+        out,
+        location: &location,
+    })? {
+        println!("Saved: {}", path.display());
+    }
+    Ok(())
+}
+
+// 저장된 data 에 SQL 조회 (DuckDB 파일 또는 SQLite 저장소)
+async fn query(cli: &cli::Cli, sql: &str, duckdb: Option<&Path>) -> Result<()> {
+    if let Some(path) = duckdb {
+        println!("{}", sinks::duckdb::query(path, sql, cli.format).await?);
+        return Ok(());
+    }
+
+    let path = cli
+        .store
+        .as_deref()
+        .ok_or_else(|| Failure::new(ErrorCode::Config, "query requires --store or --duckdb"))?;
+    let store =
+        store::Store::open(path).context(Failure::new(ErrorCode::Store, "Failed to open store"))?;
+    let (columns, rows) = store.query(sql)?;
+    println!("{}", output::render_query(&columns, &rows, cli.format)?);
+    Ok(())
+}
+
+// --credentials provider 의 로그인 정보, --customer (PP_NUMBER) 고객 번호
+async fn load_config(cli: &cli::Cli) -> Result<config::Config> {
+    let provider = credential_provider(cli);
+    match cli.fetch.customer.as_slice() {
+        [] => config::Config::load(provider.as_ref()).await,
+        user_nums => config::Config::for_customers(provider.as_ref(), user_nums.to_vec()).await,
+    }
+    .context(Failure::new(ErrorCode::Config, "Invalid configuration"))
+}
+
+// --credentials provider 의 로그인 정보 (고객 번호가 필요 없는 session, meters 등)
+async fn load_credentials(cli: &cli::Cli) -> Result<config::Credentials> {
+    let provider = credential_provider(cli);
+    provider
+        .credentials()
+        .await
+        .context(format!(
+            "Failed to get credentials from {}",
+            provider.name()
+        ))
+        .context(Failure::new(ErrorCode::Config, "Invalid configuration"))
+}
+
+// env 면 --id, --pw (clap 이 PP_ID, PP_PW 로 채움), 둘 다 없으면 환경 변수 provider 의 오류
+fn credential_provider(cli: &cli::Cli) -> Box<dyn config::CredentialProvider> {
+    match (&cli.credentials, &cli.id, &cli.pw) {
+        (config::CredentialSource::Env, Some(user_id), Some(user_pw)) => {
+            Box::new(config::StaticProvider {
+                user_id: user_id.clone(),
+                user_pw: user_pw.clone(),
+            })
+        }
+        (source, ..) => source.build(),
+    }
+}
+
+// --sink, --notify 대상 생성 (--notify-template 은 여기서 미리 읽어 문법 오류를 조회 전에 확인)
+fn build_sinks(cli: &cli::Cli) -> Result<(sinks::Sinks, sinks::Sinks)> {
+    let template = match &cli.notify_template {
+        Some(path) => Some(templates::NotifyTemplate::load(
+            path,
+            cli.notify_subject.as_deref(),
+        )?),
+        None if cli.notify_subject.is_some() => {
+            return Err(Failure::new(
+                ErrorCode::Config,
+                "--notify-subject requires --notify-template",
+            )
+            .into())
+        }
+        None => None,
+    };
+    let build = |targets: &[sinks::SinkTarget]| {
+        targets
+            .iter()
+            .map(|target| target.build(cli.locale, template.as_ref(), &cli.sink_field))
+            .collect()
+    };
+    Ok((build(&cli.sink), build(&cli.notify)))
+}
+
+// 모든 sink 확인, 하나라도 실패하면 실패한 sink 목록과 함께 실패
+async fn preflight_sinks(
+    sinks: &mut [Box<dyn sinks::Sink>],
+    notify: &mut [Box<dyn sinks::Sink>],
+) -> Result<()> {
+    let mut failed = sinks::preflight(sinks).await;
+    failed.extend(sinks::preflight(notify).await);
+    if !failed.is_empty() {
+        return Err(Failure::new(
+            ErrorCode::Sink,
+            format!("Sinks are not ready: {}", failed.join(", ")),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+// 전체 조회, backfill_year 가 있으면 해당 연도만 조회해 저장소에 병합
+async fn run(
+    cli: &cli::Cli,
+    backfill_year: Option<i32>,
+    data_dir: &paths::DataDir,
+    stats: &mut store::RunStats,
+) -> Result<()> {
+    let started = Instant::now();
+
+    // 설정 검증은 driver 실행 전에 (잘못된 설정은 즉시 실패)
+    let config::Config {
+        user_id,
+        user_pw,
+        user_nums,
+    } = load_config(cli).await?;
+    stats.accounts = user_nums.clone();
+
+    // artifact 디렉터리
+    data_dir.ensure()?;
+    // 클릭, 입력 간격 (pipeline 마다 다를 수 있어 실행마다 설정)
+    pacing::configure(cli.pacing);
+
+    // 로컬 저장소 (import 한 row 와 겹치면 --merge-policy)
+    let store = cli
+        .store
+        .as_deref()
+        .map(store::Store::open)
+        .transpose()
+        .context(Failure::new(ErrorCode::Store, "Failed to open store"))?
+        .map(|store| store.with_merge_policy(cli.merge_policy));
+    if backfill_year.is_some() && store.is_none() {
+        return Err(Failure::new(ErrorCode::Config, "backfill requires --store").into());
+    }
+    if !cli.notify.is_empty() && store.is_none() {
+        return Err(Failure::new(ErrorCode::Config, "--notify requires --store").into());
+    }
+    if cli.stream && cli.fetch.output.is_some() {
+        return Err(
+            Failure::new(ErrorCode::Config, "--output can not be used with --stream").into(),
+        );
+    }
+    if cli.stream && cli.fetch.detailed {
+        return Err(Failure::new(
+            ErrorCode::Config,
+            "--detailed can not be used with --stream",
+        )
+        .into());
+    }
+    if cli.sessions > 1 && (cli.har || cli.discover_endpoints) {
+        return Err(Failure::new(
+            ErrorCode::Config,
+            "--har and --discover-endpoints require --sessions 1",
+        )
+        .into());
+    }
+
+    // sink 연결, 권한은 조회 전에 확인 (조회가 끝난 뒤 전달 단계에서 실패하지 않도록)
+    let (mut sinks, mut notify) = build_sinks(cli)?;
+    preflight_sinks(&mut sinks, &mut notify).await?;
+
+    // 조회 전 저장된 청구 월 (--notify 는 이번에 처음 저장된 월만 알림)
+    let stored_months: HashMap<String, HashSet<NaiveDate>> = match &store {
+        Some(store) if !cli.notify.is_empty() => user_nums
+            .iter()
+            .map(|user_num| {
+                Ok((
+                    user_num.clone(),
+                    store.load_claims(user_num)?.into_keys().collect(),
+                ))
+            })
+            .collect::<Result<_>>()?,
+        _ => HashMap::new(),
+    };
+
+    // 출력 filter
+    let row_filter = filter::RowFilter {
+        min_usage: cli.min_usage,
+        months: cli.months.clone(),
+        customers: cli.fetch.customer.clone(),
+        from: cli.fetch.from,
+        to: cli.fetch.to,
+    };
+
+    // row 변환 옵션
+    let row_options = output::RowOptions {
+        date_format: cli.date_format.clone(),
+        year_month: cli.year_month,
+        include_raw: cli.include_raw,
+        money_format: cli.money_format,
+        tags: cli.tag.clone(),
+    };
+
+    // --stream 이면 row 단위로 바로 출력
+    let stream = cli
+        .stream
+        .then(|| output::RowStream::new(std::io::stdout().lock(), row_options.clone()));
+
+    // network 기록 (--har, --discover-endpoints)
+    let har = Mutex::new(None);
+    // 조회할 고객 번호 대기열
+    let queue: RefCell<VecDeque<&String>> = RefCell::new(
+        user_nums
+            .iter()
+            .filter(|user_num| {
+                let matched = row_filter.matches_customer(user_num);
+                if !matched {
+                    eprintln!("Skipping filtered customer: {}", user_num);
+                }
+                matched
+            })
+            .collect(),
+    );
+    // 고객 번호를 나눠 조회할 browser session (--sessions, 조회할 고객 번호 수 이내)
+    // driver 는 모든 검증 이후 worker 가 처음 조회할 때 실행
+    let pool = pool::BrowserPool::new(cli.sessions.min(queue.borrow().len()), cli.recycle_after);
+
+    // 단계 진행 감시 (driver hang 감지)
+    let watchdog = watchdog::Watchdog::new(Duration::from_secs(cli.watchdog_secs));
+
+    // 실패해도 HAR 는 남기도록 조회 구간을 묶어서 실행
+    let results: RefCell<BTreeMap<String, output::AccountResult>> = RefCell::new(BTreeMap::new());
+    let infos = RefCell::new(HashMap::new());
+    let store = RefCell::new(store);
+    let stream = RefCell::new(stream);
+    // 연도 page 마다 중간 결과 기록
+    let checkpoint = RefCell::new(checkpoint::Checkpoint::new(data_dir.checkpoint_file()));
+    let state = RefCell::new(match cli.since_last {
+        true => Some(state::State::load(&data_dir.state_file())?),
+        false => None,
+    });
+    let ctx = ScrapeContext {
+        cli,
+        backfill_year,
+        data_dir,
+        row_filter: &row_filter,
+        watchdog: &watchdog,
+        store: &store,
+        checkpoint: &checkpoint,
+        stream: &stream,
+        results: &results,
+        infos: &infos,
+        state: &state,
+    };
+    let (queue, pool, har, ctx) = (&queue, &pool, &har, &ctx);
+    let (user_id, user_pw) = (&user_id, &user_pw);
+
+    // slot 마다 session 하나로 대기열의 고객 번호를 순서대로 조회
+    let worker = |slot: usize| async move {
+        loop {
+            let Some(user_num) = queue.borrow_mut().pop_front() else {
+                return Ok::<_, anyhow::Error>(());
+            };
+
+            // 처리한 고객 번호 수 초과, 응답 없음이면 session 재시작
+            let mut session = pool.slot(slot).await;
+            if let Some(current) = session.as_ref() {
+                if let Some(reason) = pool.recycle_reason(current).await {
+                    println!(
+                        "Recycling browser session {} after {} customers: {}",
+                        slot, current.jobs, reason
+                    );
+                    if let Some(current) = session.take() {
+                        current.close().await;
+                    }
+                }
+            }
+            let session = match session.as_mut() {
+                Some(current) => current,
+                None => {
+                    let opened = open_session(ctx, slot, user_id, user_pw, har).await?;
+                    ctx.watchdog.tick("login");
+                    session.insert(opened)
+                }
+            };
+
+            // 고객 번호별 조회 (실패해도 이미 조회한 page 는 상태와 함께 결과에 남김)
+            let scraped = scrape_customer(ctx, session, user_num).await;
+            session.jobs += 1;
+
+            // driver 쪽 log buffer 가 커지지 않도록 고객 번호마다 수집
+            if let Some(har) = har.lock().await.as_mut() {
+                har.collect().await;
+            }
+
+            match scraped {
+                Ok(data_vec) => {
+                    ctx.results
+                        .borrow_mut()
+                        .insert(user_num.clone(), output::AccountResult::ok(data_vec));
+                }
+                Err(e) => {
+                    let data_vec = ctx
+                        .checkpoint
+                        .borrow()
+                        .customer_rows(user_num)
+                        .iter()
+                        .filter(|entry| ctx.row_filter.matches(entry))
+                        .cloned()
+                        .collect();
+                    ctx.results.borrow_mut().insert(
+                        user_num.clone(),
+                        output::AccountResult::failed(data_vec, &e),
+                    );
+                    if ctx.cli.fail_fast {
+                        return Err(e);
+                    }
+                    warnings::emit(
+                        warnings::WarningKind::SkippedCustomer,
+                        format!("Failed to scrape {}, continuing: {:#}", user_num, e),
+                    );
+                }
+            }
+        }
+    };
+    let scrape = async {
+        futures::future::try_join_all((0..pool.size()).map(worker)).await?;
+        Ok::<_, anyhow::Error>(())
+    };
+
+    // 진행이 멈추면 조회를 중단하고 진단 정보 저장 후 driver 강제 종료
+    let outcome = match tokio::select! {
+        outcome = scrape => Ok(outcome),
+        step = watchdog.stalled() => Err(step),
+    } {
+        Ok(outcome) => outcome,
+        Err(step) => {
+            let artifacts = match pool.client() {
+                Some(client) => watchdog::capture(&client, &data_dir.screenshots(), step).await,
+                None => Vec::new(),
+            };
+            Err(Failure::new(
+                ErrorCode::Hung,
+                format!(
+                    "No progress for {}s after step '{}', WebDriver killed",
+                    watchdog.window().as_secs(),
+                    step
+                ),
+            )
+            .with_artifacts(artifacts)
+            .into())
+        }
+    };
+
+    if let Some(mut har) = har.lock().await.take() {
+        har.collect().await;
+        // 조회 중 호출된 data endpoint 저장 (이후 endpoints fetch 로 직접 호출)
+        if cli.discover_endpoints {
+            let path = data_dir.endpoints_file();
+            match endpoints::save(&path, har.endpoints()) {
+                Ok(added) => eprintln!("Discovered {} new endpoints: {}", added, path.display()),
+                Err(e) => eprintln!("Failed to save endpoints: {:#}", e),
+            }
+        }
+        if cli.har {
+            let path = data_dir.har_file();
+            if let Err(e) = har.save(&path) {
+                eprintln!("Failed to save HAR: {:#}", e);
+            }
+        }
+    }
+
+    // 2분 동안 대기
+    // println!("Waiting for 2 minutes...");
+    // tokio::time::sleep(tokio::time::Duration::from_secs(120)).await;
+
+    // ChromeDriver 프로세스 종료 (실패 시에도)
+    pool.shutdown().await;
+    let checkpoint = checkpoint.into_inner();
+    let stream = stream.into_inner();
+    let store = store.into_inner();
+    let mut results = results.into_inner();
+    for (customer_number, info) in infos.into_inner() {
+        if let Some(result) = results.get_mut(&customer_number) {
+            result.info = Some(info);
+        }
+    }
+    stats.statuses = results
+        .iter()
+        .map(|(customer_number, result)| {
+            (
+                customer_number.clone(),
+                (result.status, result.error.clone()),
+            )
+        })
+        .collect();
+
+    // stream 모드는 row 를 모아두지 않아 sink 로 보낼 row 는 checkpoint 에서 구성
+    let mut sink_results = BTreeMap::new();
+    if !cli.sink.is_empty() || !cli.notify.is_empty() {
+        sink_results = results.clone();
+        if stream.is_some() {
+            for (customer_number, result) in sink_results.iter_mut() {
+                result.data = checkpoint
+                    .customer_rows(customer_number)
+                    .iter()
+                    .filter(|entry| row_filter.matches(entry))
+                    .cloned()
+                    .collect();
+            }
+        }
+    }
+
+    // 다음 --since-last 실행이 멈출 청구 월 (일부 고객 번호가 실패해도 마친 고객 번호는 저장)
+    if let Some(state) = state.into_inner() {
+        state.save(&data_dir.state_file())?;
+    }
+
+    // 중간 결과 확정 (실패 시 partial 파일 유지)
+    stats.rows = checkpoint.rows();
+    let summary = match outcome {
+        Ok(()) => hooks::RunSummary {
+            run_id: data_dir.run_id().to_string(),
+            customers: checkpoint.customers(),
+            rows: checkpoint.rows(),
+            output: checkpoint.finish()?,
+            elapsed_secs: started.elapsed().as_secs_f64(),
+            warnings: warnings::count(),
+        },
+        Err(e) => {
+            if checkpoint.partial_path().exists() {
+                eprintln!(
+                    "Partial results kept: {}",
+                    checkpoint.partial_path().display()
+                );
+            }
+            // 여러 고객 번호 조회 시 고객 번호별 상태가 담긴 결과는 출력
+            if user_nums.len() > 1 && stream.is_none() {
+                write_results(cli, &results, &row_options)?;
+            }
+            return Err(e);
+        }
+    };
+
+    // 결과 출력 (stream 모드는 이미 출력됨)
+    if stream.is_none() {
+        write_results(cli, &results, &row_options)?;
+    }
+
+    // 지정한 sink 마다 전달 (sink 별로 실패 격리)
+    let mut failed_sinks =
+        sinks::deliver(&mut sinks, &sink_results, &row_options, &summary).await?;
+
+    // 알림 sink 에는 새 청구 월만 전달 (없으면 알리지 않음)
+    if !cli.notify.is_empty() {
+        let new_results: BTreeMap<String, output::AccountResult> = sink_results
+            .iter()
+            .filter_map(|(customer_number, result)| {
+                let stored = stored_months.get(customer_number);
+                let data: Vec<PpData> = result
+                    .data
+                    .iter()
+                    // 진행 중인 달은 확정된 뒤 알림
+                    .filter(|entry| {
+                        !entry.provisional
+                            && !stored.is_some_and(|months| months.contains(&entry.claim_date))
+                    })
+                    .cloned()
+                    .collect();
+                (!data.is_empty()).then(|| {
+                    (
+                        customer_number.clone(),
+                        output::AccountResult {
+                            data,
+                            history: result.data.clone(),
+                            ..result.clone()
+                        },
+                    )
+                })
+            })
+            .collect();
+        if new_results.is_empty() {
+            eprintln!("No new billing months, skipping notifications");
+        } else {
+            failed_sinks
+                .extend(sinks::deliver(&mut notify, &new_results, &row_options, &summary).await?);
+        }
+    }
+
+    // 보존 기간 정책 적용 (실패해도 조회 결과에는 영향 없음)
+    if let Err(e) = apply_retention(cli, data_dir, store.as_ref()) {
+        eprintln!("Failed to apply retention: {:#}", e);
+    }
+
+    // 일부 고객 번호 실패 (--fail-fast 가 아니면 나머지는 계속 조회)
+    let failed: Vec<&str> = results
+        .iter()
+        .filter(|(_, result)| result.status != output::Status::Ok)
+        .map(|(customer_number, _)| customer_number.as_str())
+        .collect();
+    if !failed.is_empty() {
+        return Err(Failure::new(
+            ErrorCode::PartialFailure,
+            format!(
+                "Failed to scrape {} of {} customers: {}",
+                failed.len(),
+                results.len(),
+                failed.join(", ")
+            ),
+        )
+        .into());
+    }
+
+    // 후처리 hook (결과 파일 경로, 실행 요약 전달)
+    hooks::run(&cli.hook, &cli.webhook, &summary).await?;
+
+    // 일부 sink 실패 (나머지 sink 에는 전달됨)
+    if !failed_sinks.is_empty() {
+        return Err(Failure::new(
+            ErrorCode::PartialFailure,
+            format!(
+                "Failed to deliver to {} of {} sinks: {}",
+                failed_sinks.len(),
+                cli.sink.len() + cli.notify.len(),
+                failed_sinks.join(", ")
+            ),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+// 결과 출력 (--output 이면 파일로)
+fn write_results(
+    cli: &cli::Cli,
+    results: &BTreeMap<String, output::AccountResult>,
+    row_options: &output::RowOptions,
+) -> Result<()> {
+    // sqlite://<path> 면 출력 대신 monthly_claims 에 upsert
+    if let Some(db) = cli.fetch.output.as_deref().and_then(output::sqlite::path) {
+        let written = output::sqlite::write(&db, results, cli.merge_policy)?;
+        eprintln!("Upserted {} rows into {}", written, db.display());
+        return Ok(());
+    }
+    let render = |results: &BTreeMap<String, output::AccountResult>| {
+        output::render(results, cli.format, cli.labels, cli.locale, row_options)
+    };
+    let write = |path: &Path, text: String| -> Result<()> {
+        std::fs::write(path, format!("{}\n", text))
+            .context(format!("Failed to write {}", path.display()))?;
+        eprintln!("Wrote results to {}", path.display());
+        Ok(())
+    };
+    match &cli.fetch.output {
+        // 경로에 {customer} 가 있으면 고객 번호마다 파일 1개
+        Some(path) if path.to_string_lossy().contains(cli::CUSTOMER_PLACEHOLDER) => {
+            for (customer_number, result) in results {
+                let single = BTreeMap::from([(customer_number.clone(), result.clone())]);
+                let path = path
+                    .to_string_lossy()
+                    .replace(cli::CUSTOMER_PLACEHOLDER, customer_number);
+                write(Path::new(&path), render(&single)?)?;
+            }
+        }
+        Some(path) => write(path, render(results)?)?,
+        None => println!("{}", render(results)?),
+    }
+    Ok(())
+}
+
+// browser session 간 공유되는 실행 상태 (같은 task 에서 번갈아 사용, await 중에는 borrow 하지 않음)
+struct ScrapeContext<'a> {
+    cli: &'a cli::Cli,
+    backfill_year: Option<i32>,
+    data_dir: &'a paths::DataDir,
+    row_filter: &'a filter::RowFilter,
+    watchdog: &'a watchdog::Watchdog,
+    store: &'a RefCell<Option<store::Store>>,
+    checkpoint: &'a RefCell<checkpoint::Checkpoint>,
+    stream: &'a RefCell<Option<output::RowStream<StdoutLock<'static>>>>,
+    results: &'a RefCell<BTreeMap<String, output::AccountResult>>,
+    // --customer-info 로 읽은 고객 정보 (조회가 실패해도 결과에 포함)
+    infos: &'a RefCell<HashMap<String, customer::CustomerInfo>>,
+    // --since-last 의 고객 번호별 최근 청구 월
+    state: &'a RefCell<Option<state::State>>,
+}
+
+// 조회용 browser session 시작 (driver 실행, cookie 복원, 로그인, layout 판별)
+async fn open_session(
+    ctx: &ScrapeContext<'_>,
+    slot: usize,
+    user_id: &str,
+    user_pw: &str,
+    har: &Mutex<Option<har::HarRecorder>>,
+) -> Result<pool::BrowserSession> {
+    let cli = ctx.cli;
+    // 같은 Chrome profile 은 동시에 열 수 없어 slot 마다 따로 사용
+    let chrome_profile = match slot {
+        0 => chrome_profile(cli, user_id)?,
+        _ => chrome_profile(cli, &format!("{}-{}", user_id, slot))?,
+    };
+    let record_network = cli.har || cli.discover_endpoints;
+    let (process, client, url) = start_driver(DriverOptions {
+        window: cli.window_size,
+        record_network,
+        profile_dir: chrome_profile
+            .as_ref()
+            .map(|profile| profile.dir().to_path_buf()),
+        ..DriverOptions::default()
+    })
+    .await?;
+    // 이후 실패 시 drop 에서 driver 종료
+    let driver = pool::Driver::new(process, client, url, chrome_profile);
+
+    // network 기록 (--har, --discover-endpoints), 재시작한 session 은 기존 기록에 이어서 수집
+    if record_network {
+        let mut har = har.lock().await;
+        match har.as_mut() {
+            Some(har) => har.attach(&driver.client, &driver.url).await?,
+            None => *har = Some(har::HarRecorder::start(&driver.client, &driver.url).await?),
+        }
+    }
+
+    // 가져온 session (session import) 이 있으면 로그인 전에 cookie 복원
+    let session_file = ctx.data_dir.session_file();
+    if session_file.exists() {
+        restore_session(&driver.client, &session_file)
+            .await
+            .context(Step("restore_session"))?;
+    }
+
+    // 로그인
+    let mut latency = PageLatency::new(&cli.step_timeout, &cli.page_ready);
+    login(
+        &driver.client,
+        TARGET_URL,
+        user_id,
+        user_pw,
+        &login_flow(cli)?,
+        &mut latency,
+    )
+    .await
+    .context(Step("login"))?;
+    // window 크기에 따라 달라지는 layout 판별
+    let profile = selectors::detect(&driver.client, cli.window_size, &cli.selector).await;
+
+    Ok(pool::BrowserSession {
+        driver,
+        latency,
+        claim_url: None,
+        profile,
+        jobs: 0,
+    })
+}
+
+// 로그인된 session 에서 고객 번호 하나 조회 (고객 번호 전환 후 월별 청구 요금)
+async fn scrape_customer(
+    ctx: &ScrapeContext<'_>,
+    session: &mut pool::BrowserSession,
+    user_num: &str,
+) -> Result<Vec<PpData>> {
+    let pool::BrowserSession {
+        driver,
+        latency,
+        claim_url,
+        profile,
+        ..
+    } = session;
+    let client = &driver.client;
+    // 당월 판단 기준일
+    let today = chrono::Local::now().date_naive();
+
+    // 고객 번호 전환
+    select_customer(client, user_num, profile, latency)
+        .await
+        .context(Step("select_customer"))?;
+    ctx.watchdog.tick("select_customer");
+
+    // 고객 정보 (읽지 못해도 청구 요금 조회는 계속)
+    if ctx.cli.customer_info {
+        match read_customer_info(client, user_num, profile, latency).await {
+            Ok(info) => {
+                ctx.infos.borrow_mut().insert(user_num.to_string(), info);
+            }
+            Err(e) => warnings::emit(
+                warnings::WarningKind::CustomerInfo,
+                format!("Failed to read customer info of {}: {:#}", user_num, e),
+            ),
+        }
+        ctx.watchdog.tick("customer_info");
+    }
+
+    // 월별 청구 요금 이동
+    open_monthly_claims(client, TARGET_URL, claim_url, profile, latency)
+        .await
+        .context(Step("navigate_claims"))?;
+    ctx.watchdog.tick("navigate_claims");
+
+    // 연도 page 원본 보관 (--archive-raw)
+    let archive = ctx.cli.archive_raw.then(|| {
+        extract::RawArchive::new(
+            &ctx.data_dir.raw_pages_dir(),
+            user_num,
+            ctx.data_dir.run_id(),
+        )
+    });
+
+    // 특정 연도만 조회, 저장소 병합
+    if let Some(year) = ctx.backfill_year {
+        let data_vec = fetch_year_claims(
+            client,
+            YEAR_SELECT,
+            year,
+            profile,
+            latency,
+            archive.as_ref(),
+        )
+        .await
+        .context(Step("backfill"))?;
+        let mut data_vec = data_vec;
+        ctx.cli.current_month.apply(&mut data_vec, today);
+        ctx.watchdog.tick("backfill");
+        ctx.checkpoint.borrow_mut().record(user_num, &data_vec)?;
+        if let Some(store) = ctx.store.borrow_mut().as_mut() {
+            let _keepalive = KeepAlive::start(Arc::clone(client), KEEPALIVE_PERIOD);
+            store.upsert_claims(user_num, &data_vec)?;
+        }
+        println!(
+            "Backfilled {} months of {} for {}",
+            data_vec.len(),
+            year,
+            user_num
+        );
+        return Ok(data_vec);
+    }
+
+    // 저장된 data (--full 이면 무시)
+    let known = match ctx.store.borrow().as_ref() {
+        Some(store) if !ctx.cli.full => Some(store.load_claims(user_num)?),
+        _ => None,
+    };
+
+    // --from, --to 범위의 연도만 조회, --since-last 면 지난 조회의 최근 청구 월까지만
+    let scope = ClaimScope {
+        known: known.as_ref(),
+        from: ctx.row_filter.from,
+        to: ctx.row_filter.to,
+        since: ctx
+            .state
+            .borrow()
+            .as_ref()
+            .and_then(|state| state.latest(user_num)),
+    };
+
+    // stream 모드: 연도 page 마다 저장, 출력 후 버림
+    let streaming = ctx.stream.borrow().is_some();
+    if streaming {
+        fetch_monthly_claims(
+            client,
+            scope,
+            &mut |mut page| {
+                ctx.cli.current_month.apply(&mut page, today);
+                ctx.watchdog.tick("scrape");
+                ctx.checkpoint.borrow_mut().record(user_num, &page)?;
+                if let Some(store) = ctx.store.borrow_mut().as_mut() {
+                    store.upsert_claims(user_num, &page)?;
+                }
+                if let Some(stream) = ctx.stream.borrow_mut().as_mut() {
+                    for entry in page.iter().filter(|entry| ctx.row_filter.matches(entry)) {
+                        stream.write_row(user_num, entry)?;
+                    }
+                    stream.flush()?;
+                }
+                Ok(())
+            },
+            profile,
+            latency,
+            archive.as_ref(),
+        )
+        .await
+        .context(Step("scrape"))?;
+        record_state(ctx, user_num);
+        return Ok(Vec::new());
+    }
+
+    let mut data_vec = Vec::new();
+    fetch_monthly_claims(
+        client,
+        scope,
+        &mut |mut page| {
+            ctx.cli.current_month.apply(&mut page, today);
+            ctx.watchdog.tick("scrape");
+            ctx.checkpoint.borrow_mut().record(user_num, &page)?;
+            data_vec.append(&mut page);
+            Ok(())
+        },
+        profile,
+        latency,
+        archive.as_ref(),
+    )
+    .await
+    .context(Step("scrape"))?;
+    record_state(ctx, user_num);
+    data_vec.sort_by_key(|entry| Reverse(entry.claim_date));
+
+    // 저장소 갱신 후 전체 기간 data 로 출력 (이번에 조회한 row 우선)
+    let data_vec = match ctx.store.borrow_mut().as_mut() {
+        Some(store) => {
+            // 저장 중에도 다음 고객 번호 조회를 위해 세션 유지
+            let _keepalive = KeepAlive::start(Arc::clone(client), KEEPALIVE_PERIOD);
+            store.upsert_claims(user_num, &data_vec)?;
+            let mut merged = store.load_claims(user_num)?;
+            merged.extend(data_vec.into_iter().map(|entry| (entry.claim_date, entry)));
+            let mut merged: Vec<PpData> = merged.into_values().collect();
+            merged.sort_by_key(|entry| Reverse(entry.claim_date));
+            merged
+        }
+        None => data_vec,
+    };
+    let mut data_vec: Vec<PpData> = data_vec
+        .into_iter()
+        .filter(|entry| ctx.row_filter.matches(entry))
+        .collect();
+
+    // 출력할 월의 청구서 상세 요금 항목 (--detailed)
+    if ctx.cli.fetch.detailed {
+        fetch_breakdowns(client, user_num, &mut data_vec, profile, latency).await?;
+        ctx.watchdog.tick("bill_detail");
+    }
+    Ok(data_vec)
+}
+
+// 조회를 마친 고객 번호의 최근 청구 월 기록 (--since-last, 중간에 실패한 고객 번호는 기록하지 않음)
+fn record_state(ctx: &ScrapeContext<'_>, user_num: &str) {
+    if let Some(state) = ctx.state.borrow_mut().as_mut() {
+        state.record(user_num, ctx.checkpoint.borrow().customer_rows(user_num));
+    }
+}
+
+// 월마다 청구서 상세를 열어 요금 항목 채우기 (실패한 월은 경고 후 건너뜀)
+async fn fetch_breakdowns(
+    client: &Arc<Client>,
+    user_num: &str,
+    data_vec: &mut [PpData],
+    profile: &selectors::SelectorProfile,
+    latency: &mut PageLatency,
+) -> Result<()> {
+    for entry in data_vec.iter_mut().filter(|entry| !entry.provisional) {
+        let month = entry.claim_date;
+        // 상세 화면에서 돌아오면 조회 조건이 초기화될 수 있어 월마다 연도 조회
+        let detail = async {
+            fetch_year_claims(client, YEAR_SELECT, month.year(), profile, latency, None).await?;
+            fetch_bill_detail(client, month, profile, latency, None).await
+        }
+        .await;
+        match detail {
+            Ok((detail, _)) if !detail.breakdown.is_empty() => {
+                entry.breakdown = Some(detail.breakdown);
+            }
+            Ok(_) => warnings::emit(
+                warnings::WarningKind::BillDetail,
+                format!(
+                    "No charge items in the bill detail of {} for {}",
+                    month.format("%Y-%m"),
+                    user_num
+                ),
+            ),
+            Err(e) => warnings::emit(
+                warnings::WarningKind::BillDetail,
+                format!(
+                    "Failed to read the bill detail of {} for {}: {:#}",
+                    month.format("%Y-%m"),
+                    user_num,
+                    e
+                ),
+            ),
+        }
+    }
+    Ok(())
+}
+
+// driver 실행 옵션
+#[derive(Debug, Clone)]
+struct DriverOptions {
+    headless: bool,
+    // view size (layout 이 window 크기에 따라 달라짐)
+    window: selectors::WindowSize,
+    // CDP Network event 를 performance log 로 수집
+    record_network: bool,
+    // 계정 전용 Chrome profile (없으면 실행마다 임시 profile)
+    profile_dir: Option<PathBuf>,
+}
+
+impl Default for DriverOptions {
+    fn default() -> Self {
+        Self {
+            headless: true,
+            window: selectors::DEFAULT_WINDOW,
+            record_network: false,
+            profile_dir: None,
+        }
+    }
+}
+
+// 계정 profile 로 driver 실행 후 run 실행, 결과와 관계없이 driver 종료 (browser 를 쓰는 명령 공통)
+async fn with_browser<T>(
+    cli: &cli::Cli,
+    user_id: &str,
+    options: DriverOptions,
+    run: impl AsyncFnOnce(&Arc<Client>, &str) -> T,
+) -> Result<T> {
+    let chrome_profile = chrome_profile(cli, user_id)?;
+    let (driver, client, url) = start_driver(DriverOptions {
+        profile_dir: chrome_profile
+            .as_ref()
+            .map(|profile| profile.dir().to_path_buf()),
+        ..options
+    })
+    .await?;
+    let client = Arc::new(client);
+    let outcome = run(&client, &url).await;
+
+    driver.kill();
+    Ok(outcome)
+}
+
+// 로그인, layout 판별 후 run 실행 (selector profile, page latency, WebDriver url 전달)
+async fn with_login<T>(
+    cli: &cli::Cli,
+    user_id: &str,
+    user_pw: &str,
+    run: impl AsyncFnOnce(
+        &Arc<Client>,
+        &selectors::SelectorProfile,
+        &mut PageLatency,
+        &str,
+    ) -> Result<T>,
+) -> Result<T> {
+    let flow = login_flow(cli)?;
+    let options = DriverOptions {
+        window: cli.window_size,
+        ..DriverOptions::default()
+    };
+    with_browser(cli, user_id, options, async |client, url| {
+        let mut latency = PageLatency::new(&cli.step_timeout, &cli.page_ready);
+        sign_in(client, user_id, user_pw, &flow, &mut latency)
+            .await
+            .context(Step("login"))?;
+        let profile = selectors::detect(client, cli.window_size, &cli.selector).await;
+        run(client, &profile, &mut latency, url).await
+    })
+    .await?
+}
+
+// portal 로그인 (로그인 전 단계 포함)
+async fn sign_in(
+    client: &Client,
+    user_id: &str,
+    user_pw: &str,
+    flow: &LoginFlow,
+    latency: &mut PageLatency,
+) -> Result<()> {
+    login(client, TARGET_URL, user_id, user_pw, flow, latency).await
+}
+
+// ChromeDriver 실행 후 WebDriver session 연결, WebDriver url 반환
+// 동시에 실행되는 다른 계정과 겹치지 않도록 빈 port 에 driver 를 따로 띄움
+async fn start_driver(options: DriverOptions) -> Result<(driver::DriverGuard, Client, String)> {
+    // driver path
+    let chromedriver_path = driver::resolver::resolve().await?;
+
+    // driver 실행
+    let port = TcpListener::bind("127.0.0.1:0")
+        .and_then(|listener| listener.local_addr())
+        .context(Failure::new(
+            ErrorCode::Driver,
+            "Failed to find a free port for ChromeDriver",
+        ))?
+        .port();
+    let url = format!("http://localhost:{}", port);
+    // 이후 실패 시 drop 에서 driver 종료
+    let driver = driver::DriverGuard::spawn(&chromedriver_path, port)?;
+
+    // headless, disable-gpu option
+    let mut args = vec!["--disable-gpu".to_string()];
+    if options.headless {
+        args.insert(0, "--headless".to_string());
+    }
+    // 계정 전용 profile (cookie, session 이 다른 계정과 섞이지 않음)
+    if let Some(dir) = &options.profile_dir {
+        args.push(format!("--user-data-dir={}", dir.display()));
+    }
+    let mut capabilities: Map<String, Value> = serde_json::from_value(json!({
+        "goog:chromeOptions": {
+            "args": args
+        }
+    }))?;
+    // --har, --discover-endpoints: CDP Network event 를 performance log 로 수집
+    if options.record_network {
+        capabilities.insert("goog:loggingPrefs".to_string(), har::capabilities());
+        capabilities["goog:chromeOptions"]["perfLoggingPrefs"] = json!({ "enableNetwork": true });
+    }
+    // driver 기동 대기 (고정 sleep 대신 짧은 간격으로 재시도, driver 가 종료되면 중단)
+    let connect = async {
+        loop {
+            match ClientBuilder::native()
+                .capabilities(capabilities.clone())
+                .connect(&url)
+                .await
+            {
+                Ok(client) => return Ok(client),
+                Err(e) if driver.exited() => {
+                    return Err(Failure::new(
+                        ErrorCode::Driver,
+                        format!("ChromeDriver exited before accepting connections: {}", e),
+                    ))
+                }
+                Err(_) => tokio::time::sleep(Duration::from_millis(200)).await,
+            }
+        }
+    };
+    let client = match tokio::time::timeout(DRIVER_CONNECT_TIMEOUT, connect).await {
+        Ok(client) => client?,
+        Err(_) => {
+            return Err(Failure::new(
+                ErrorCode::Driver,
+                format!(
+                    "ChromeDriver did not accept connections within {}s",
+                    DRIVER_CONNECT_TIMEOUT.as_secs()
+                ),
+            )
+            .into())
+        }
+    };
+    // view size
+    client
+        .set_window_rect(0, 0, options.window.width, options.window.height)
+        .await
+        .context(Failure::new(ErrorCode::Driver, "Failed to set window size"))?;
+    Ok((driver, client, url))
+}
+
+// portal domain 으로 이동 후 저장된 cookie 추가
+async fn restore_session(client: &Client, path: &Path) -> Result<()> {
+    let cookies = session::read(path)?;
+    client
+        .goto(TARGET_URL)
+        .await
+        .context(Failure::new(ErrorCode::Navigation, "Failed to navigate"))?;
+    let restored = session::restore(client, &cookies).await?;
+    eprintln!("Restored {} cookies from {}", restored, path.display());
+    Ok(())
+}
+
+fn login_flow(cli: &cli::Cli) -> Result<LoginFlow> {
+    Ok(LoginFlow {
+        pre_login: match &cli.pre_login {
+            Some(path) => prelogin::Script::load(path)?,
+            None => prelogin::Script::default(),
+        },
+        landing: landing::LandingCheck::new(
+            cli.expect_account.as_deref(),
+            cli.expect_account_selector.as_ref(),
+        ),
+        captcha_dir: paths::DataDir::resolve(cli.data_dir.as_deref())?.captcha_dir(),
+    })
 }
@@ -0,0 +1,964 @@
+use anyhow::{Context, Result};
+use chrono::format::{Item, StrftimeItems};
+use chrono::{Datelike, FixedOffset, NaiveDate, TimeZone};
+use clap::ValueEnum;
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufWriter, Write};
+use std::marker::PhantomData;
+use std::str::FromStr;
+
+use crate::customer::Meter;
+use crate::error::{ErrorCode, Failure};
+use crate::freshness::Freshness;
+use crate::profiles::ProfileInfo;
+use crate::store::RunRecord;
+use crate::tags::{self, Tag};
+use crate::{DailyUsage, HourlyUsage};
+
+pub use seppuku_hyphen::sink::{AccountResult, Status};
+
+mod csv;
+pub mod sqlite;
+
+// row 단위 flush 주기
+const FLUSH_EVERY: usize = 100;
+
+// date_format 적용 대상 field
+const DATE_FIELDS: &[&str] = &["claim_date"];
+
+// money_format 적용 대상 field
+const MONEY_FIELDS: &[&str] = &["paid"];
+
+// 금액 직렬화 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoneyFormat {
+    // 정확한 10진 문자열 ("-1234.5")
+    String,
+    // 10^scale 배 한 정수 (scaled:0 은 원 단위, scaled:2 는 1/100 원 단위)
+    Scaled(u32),
+}
+
+impl Default for MoneyFormat {
+    fn default() -> Self {
+        MoneyFormat::Scaled(0)
+    }
+}
+
+impl FromStr for MoneyFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "string" => Ok(MoneyFormat::String),
+            "scaled" => Ok(MoneyFormat::Scaled(0)),
+            _ => s
+                .strip_prefix("scaled:")
+                .and_then(|scale| scale.parse().ok())
+                .filter(|scale| *scale <= 6)
+                .map(MoneyFormat::Scaled)
+                .ok_or_else(|| {
+                    format!(
+                        "Invalid money format, expected string, scaled or scaled:<0-6>: {}",
+                        s
+                    )
+                }),
+        }
+    }
+}
+
+impl MoneyFormat {
+    pub fn format(self, amount: Decimal) -> Result<Value> {
+        match self {
+            MoneyFormat::String => Ok(Value::String(amount.normalize().to_string())),
+            MoneyFormat::Scaled(scale) => (amount * Decimal::from(10i64.pow(scale)))
+                .round()
+                .to_i64()
+                .map(Value::from)
+                .with_context(|| format!("Amount out of range: {}", amount)),
+        }
+    }
+}
+
+// 날짜 직렬화 형식
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DateFormat {
+    // chrono strftime pattern (e.g. %Y-%m-%d, %Y%m)
+    Pattern(String),
+    // KST 자정 기준 epoch millis
+    EpochMillis,
+}
+
+impl Default for DateFormat {
+    fn default() -> Self {
+        DateFormat::Pattern("%Y-%m-%d".to_string())
+    }
+}
+
+impl FromStr for DateFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "epoch-millis" | "epoch_millis" => Ok(DateFormat::EpochMillis),
+            pattern if StrftimeItems::new(pattern).any(|item| item == Item::Error) => {
+                Err(format!("Invalid date format: {}", pattern))
+            }
+            pattern => Ok(DateFormat::Pattern(pattern.to_string())),
+        }
+    }
+}
+
+impl DateFormat {
+    pub fn format(&self, date: NaiveDate) -> Value {
+        match self {
+            DateFormat::Pattern(pattern) => Value::String(date.format(pattern).to_string()),
+            DateFormat::EpochMillis => {
+                let kst = FixedOffset::east_opt(9 * 3600).expect("valid offset");
+                let midnight = date.and_hms_opt(0, 0, 0).expect("valid time");
+                kst.from_local_datetime(&midnight)
+                    .single()
+                    .map_or(Value::Null, |datetime| datetime.timestamp_millis().into())
+            }
+        }
+    }
+}
+
+// 기계 판독용 row 변환 옵션
+#[derive(Debug, Clone, Default)]
+pub struct RowOptions {
+    pub date_format: DateFormat,
+    // claim_date 에서 year, month 정수 field 추가
+    pub year_month: bool,
+    // provenance (출처, 원본 문자열, 경고) 포함
+    pub include_raw: bool,
+    pub money_format: MoneyFormat,
+    // 고객 번호별로 row 에 붙일 tag (--tag)
+    pub tags: Vec<Tag>,
+}
+
+impl RowOptions {
+    // 고객 번호 1개의 row 변환 후 tag field 추가 (row 의 field 는 덮어쓰지 않음)
+    pub fn customer_row<T: Serialize>(&self, customer_number: &str, row: &T) -> Result<Value> {
+        let mut value = self.to_value(row)?;
+        if let Value::Object(map) = &mut value {
+            for (key, tag) in tags::for_customer(&self.tags, customer_number) {
+                map.entry(key).or_insert(tag.into());
+            }
+        }
+        Ok(value)
+    }
+
+    // row 직렬화 후 날짜 field 변환, 파생 field 추가
+    pub fn to_value<T: Serialize>(&self, row: &T) -> Result<Value> {
+        let mut value = serde_json::to_value(row).context("Failed to serialize row")?;
+        if let Value::Object(map) = &mut value {
+            let claim_date = map
+                .get("claim_date")
+                .and_then(Value::as_str)
+                .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+
+            for field in DATE_FIELDS {
+                let date = map
+                    .get(*field)
+                    .and_then(Value::as_str)
+                    .and_then(|date| NaiveDate::parse_from_str(date, "%Y-%m-%d").ok());
+                if let Some(date) = date {
+                    map.insert(field.to_string(), self.date_format.format(date));
+                }
+            }
+
+            for field in MONEY_FIELDS {
+                let amount = map
+                    .get(*field)
+                    .and_then(Value::as_str)
+                    .and_then(|amount| amount.parse::<Decimal>().ok());
+                if let Some(amount) = amount {
+                    map.insert(field.to_string(), self.money_format.format(amount)?);
+                }
+            }
+
+            // 요금 항목 (--detailed) 도 같은 금액 형식
+            if let Some(Value::Object(items)) = map.get_mut("breakdown") {
+                self.format_amounts(items)?;
+            }
+
+            if !self.include_raw {
+                map.remove("provenance");
+            }
+
+            if let (true, Some(date)) = (self.year_month, claim_date) {
+                map.insert("year".to_string(), date.year().into());
+                map.insert("month".to_string(), date.month().into());
+            }
+        }
+        Ok(value)
+    }
+
+    // 금액 문자열 (그 외 항목 아래 포함) 을 --money-format 으로 변환
+    fn format_amounts(&self, items: &mut Map<String, Value>) -> Result<()> {
+        for value in items.values_mut() {
+            match value {
+                Value::String(amount) => {
+                    if let Ok(amount) = amount.parse::<Decimal>() {
+                        *value = self.money_format.format(amount)?;
+                    }
+                }
+                Value::Object(nested) => self.format_amounts(nested)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+// row 를 모아두지 않고 한 줄씩 출력 (NDJSON)
+pub struct RowStream<W: Write> {
+    writer: BufWriter<W>,
+    options: RowOptions,
+    pending: usize,
+}
+
+#[derive(Serialize)]
+struct StreamRow<'a> {
+    customer_number: &'a str,
+    #[serde(flatten)]
+    row: Value,
+}
+
+impl<W: Write> RowStream<W> {
+    pub fn new(writer: W, options: RowOptions) -> Self {
+        Self {
+            writer: BufWriter::new(writer),
+            options,
+            pending: 0,
+        }
+    }
+
+    // row 1건 출력, 일정 건수마다 flush
+    pub fn write_row<T: Serialize>(&mut self, customer_number: &str, row: &T) -> Result<()> {
+        serde_json::to_writer(
+            &mut self.writer,
+            &StreamRow {
+                customer_number,
+                row: self.options.customer_row(customer_number, row)?,
+            },
+        )
+        .context("Failed to serialize row")?;
+        self.writer.write_all(b"\n")?;
+
+        self.pending += 1;
+        if self.pending >= FLUSH_EVERY {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        self.pending = 0;
+        self.writer.flush().context("Failed to flush output")
+    }
+}
+
+// 결과 출력 형식
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    Json,
+    Table,
+    // 청구 row, 고객 번호 목록, SQL 조회 결과만
+    Csv,
+}
+
+// CSV 로 출력할 수 없는 결과
+pub fn csv_unsupported(what: &str) -> anyhow::Error {
+    Failure::new(
+        ErrorCode::Config,
+        format!("--format csv is not supported for {}", what),
+    )
+    .into()
+}
+
+// 사람이 읽는 출력의 label 언어
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Labels {
+    Ko,
+    En,
+}
+
+// 사람이 읽는 출력 (표, 알림) 의 금액, 사용량 형식 (기계 판독 형식은 원래 숫자 그대로)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Locale {
+    // ₩1,234,560
+    #[value(name = "ko-KR")]
+    KoKr,
+    // KRW 1,234,560
+    #[value(name = "en-US")]
+    EnUs,
+}
+
+impl Locale {
+    pub fn money(self, won: Decimal) -> String {
+        let sign = if won.is_sign_negative() && !won.is_zero() {
+            "-"
+        } else {
+            ""
+        };
+        let text = won.abs().normalize().to_string();
+        let amount = match text.split_once('.') {
+            Some((integer, fraction)) => format!("{}.{}", group_thousands(integer), fraction),
+            None => group_thousands(&text),
+        };
+        match self {
+            Locale::KoKr => format!("{}₩{}", sign, amount),
+            Locale::EnUs => format!("{}KRW {}", sign, amount),
+        }
+    }
+
+    // 소수점 이하는 최대 3자리, 끝의 0 제거 (두 locale 모두 천 단위 , 소수점 .)
+    pub fn usage(self, kwh: f64) -> String {
+        let text = format!("{:.3}", kwh.abs());
+        let (integer, fraction) = text.split_once('.').unwrap_or((&text, ""));
+        let fraction = fraction.trim_end_matches('0');
+        let sign = if kwh < 0.0 && !text.trim_matches(['0', '.']).is_empty() {
+            "-"
+        } else {
+            ""
+        };
+        if fraction.is_empty() {
+            format!("{}{}", sign, group_thousands(integer))
+        } else {
+            format!("{}{}.{}", sign, group_thousands(integer), fraction)
+        }
+    }
+}
+
+// 세 자리마다 , (숫자 문자열)
+fn group_thousands(digits: &str) -> String {
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    grouped
+}
+
+impl Labels {
+    pub fn customer_number(self) -> &'static str {
+        match self {
+            Labels::Ko => "고객번호",
+            Labels::En => "Customer number",
+        }
+    }
+
+    pub fn display_name(self) -> &'static str {
+        match self {
+            Labels::Ko => "표시 이름",
+            Labels::En => "Display name",
+        }
+    }
+
+    pub fn claim_date(self) -> &'static str {
+        match self {
+            Labels::Ko => "청구년월",
+            Labels::En => "Claim month",
+        }
+    }
+
+    pub fn usage(self) -> &'static str {
+        match self {
+            Labels::Ko => "사용량(kWh)",
+            Labels::En => "Usage (kWh)",
+        }
+    }
+
+    pub fn paid(self) -> &'static str {
+        match self {
+            Labels::Ko => "청구금액(원)",
+            Labels::En => "Paid (KRW)",
+        }
+    }
+
+    // 진행 중인 달 표시
+    pub fn provisional(self) -> &'static str {
+        match self {
+            Labels::Ko => "  (잠정)",
+            Labels::En => "  (provisional)",
+        }
+    }
+}
+
+// 고객 번호 목록 -> 출력 문자열
+pub fn render_meters(meters: &[Meter], format: OutputFormat, labels: Labels) -> Result<String> {
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(meters).context("Failed to serialize meters to JSON")
+        }
+        OutputFormat::Csv => csv::meters(meters),
+        OutputFormat::Table => {
+            let mut table = String::new();
+            let _ = writeln!(
+                table,
+                "{}  {}",
+                pad(labels.customer_number(), 14),
+                labels.display_name()
+            );
+            for meter in meters {
+                let _ = writeln!(
+                    table,
+                    "{}  {}",
+                    pad(&meter.customer_number, 14),
+                    meter.display_name
+                );
+            }
+            Ok(table)
+        }
+    }
+}
+
+// 일별, 시간대별 사용량 row (UsageStream 으로 출력)
+pub trait UsageRow: Serialize {
+    // CSV 의 시각, 사용량 column
+    const COLUMNS: [&'static str; 2];
+
+    // CSV 의 시각
+    fn time(&self) -> String;
+
+    // 표의 시각
+    fn label(&self) -> String;
+
+    fn kwh(&self) -> f64;
+}
+
+impl UsageRow for DailyUsage {
+    const COLUMNS: [&'static str; 2] = ["date", "usage_kwh"];
+
+    fn time(&self) -> String {
+        self.date.format("%Y-%m-%d").to_string()
+    }
+
+    fn label(&self) -> String {
+        self.time()
+    }
+
+    fn kwh(&self) -> f64 {
+        self.usage_kwh
+    }
+}
+
+impl UsageRow for HourlyUsage {
+    const COLUMNS: [&'static str; 2] = ["timestamp", "kwh"];
+
+    fn time(&self) -> String {
+        self.timestamp.format("%Y-%m-%dT%H:%M:%S").to_string()
+    }
+
+    fn label(&self) -> String {
+        self.timestamp.format("%Y-%m-%d %H:%M").to_string()
+    }
+
+    fn kwh(&self) -> f64 {
+        self.kwh
+    }
+}
+
+// 일별 (--dataset daily), 시간대별 (fetch hourly) 사용량을 page (달, 하루) 단위로 바로 출력
+// 여러 해의 구간 data 도 모아두지 않음, 일정 row 수마다 flush
+// json 은 고객 번호별 배열 문서, csv 는 header 후 row (고객 번호가 여럿이면 앞에 customer_number)
+// ndjson (--stream) 이면 형식과 관계없이 row 마다 한 줄
+pub struct UsageStream<W: Write, T: UsageRow> {
+    writer: BufWriter<W>,
+    format: OutputFormat,
+    ndjson: bool,
+    locale: Locale,
+    keyed: bool,
+    // 출력 중인 고객 번호, 그 고객 번호의 row 수
+    customer: Option<String>,
+    rows: usize,
+    pending: usize,
+    _row: PhantomData<T>,
+}
+
+impl<W: Write, T: UsageRow> UsageStream<W, T> {
+    // keyed: 고객 번호가 여럿 (csv 의 customer_number column)
+    pub fn new(
+        writer: W,
+        format: OutputFormat,
+        ndjson: bool,
+        locale: Locale,
+        keyed: bool,
+    ) -> Result<Self> {
+        let mut stream = Self {
+            writer: BufWriter::new(writer),
+            format,
+            ndjson,
+            locale,
+            keyed,
+            customer: None,
+            rows: 0,
+            pending: 0,
+            _row: PhantomData,
+        };
+        if format == OutputFormat::Csv && !ndjson {
+            let header = keyed
+                .then_some("customer_number")
+                .into_iter()
+                .chain(T::COLUMNS);
+            stream.writer.write_all(csv::record(header)?.as_bytes())?;
+        }
+        Ok(stream)
+    }
+
+    // 고객 번호의 row 시작
+    pub fn customer(&mut self, customer_number: &str) -> Result<()> {
+        if !self.ndjson && self.format == OutputFormat::Json {
+            let prefix = if self.customer.is_some() {
+                self.close_customer()?;
+                ",\n"
+            } else {
+                "{\n"
+            };
+            write!(
+                self.writer,
+                "{}  {}: [",
+                prefix,
+                serde_json::to_string(customer_number)?
+            )?;
+        }
+        self.customer = Some(customer_number.to_string());
+        self.rows = 0;
+        Ok(())
+    }
+
+    // page 1개의 row 출력
+    pub fn write_rows(&mut self, rows: &[T]) -> Result<()> {
+        let customer_number = self
+            .customer
+            .clone()
+            .context("Usage rows written before a customer number")?;
+        for row in rows {
+            if self.ndjson {
+                serde_json::to_writer(
+                    &mut self.writer,
+                    &StreamRow {
+                        customer_number: &customer_number,
+                        row: serde_json::to_value(row)?,
+                    },
+                )
+                .context("Failed to serialize row")?;
+                self.writer.write_all(b"\n")?;
+            } else {
+                match self.format {
+                    OutputFormat::Json => {
+                        // serde_json pretty 출력과 같은 들여쓰기
+                        let pretty = serde_json::to_string_pretty(row)
+                            .context("Failed to serialize usage to JSON")?;
+                        let separator = if self.rows == 0 { "\n" } else { ",\n" };
+                        write!(self.writer, "{}", separator)?;
+                        for (i, line) in pretty.lines().enumerate() {
+                            if i > 0 {
+                                self.writer.write_all(b"\n")?;
+                            }
+                            write!(self.writer, "    {}", line)?;
+                        }
+                    }
+                    OutputFormat::Csv => {
+                        let cells = [row.time(), row.kwh().to_string()];
+                        let record = if self.keyed {
+                            csv::record(std::iter::once(customer_number.clone()).chain(cells))?
+                        } else {
+                            csv::record(cells)?
+                        };
+                        self.writer.write_all(record.as_bytes())?;
+                    }
+                    OutputFormat::Table => writeln!(
+                        self.writer,
+                        "{}  {}  {} kWh",
+                        pad(&customer_number, 14),
+                        row.label(),
+                        self.locale.usage(row.kwh())
+                    )?,
+                }
+            }
+            self.rows += 1;
+            self.pending += 1;
+        }
+        if self.pending >= FLUSH_EVERY {
+            self.pending = 0;
+            self.writer.flush().context("Failed to flush output")?;
+        }
+        Ok(())
+    }
+
+    // JSON 문서 마무리 후 flush
+    pub fn finish(mut self) -> Result<()> {
+        if !self.ndjson && self.format == OutputFormat::Json {
+            if self.customer.is_some() {
+                self.close_customer()?;
+                self.writer.write_all(b"\n}\n")?;
+            } else {
+                self.writer.write_all(b"{}\n")?;
+            }
+        }
+        self.writer.flush().context("Failed to flush output")
+    }
+
+    fn close_customer(&mut self) -> Result<()> {
+        let close = if self.rows == 0 { "]" } else { "\n  ]" };
+        self.writer.write_all(close.as_bytes())?;
+        Ok(())
+    }
+}
+
+// 실행 기록 -> 출력 문자열
+pub fn render_runs(runs: &[RunRecord], format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(runs).context("Failed to serialize runs to JSON")
+        }
+        OutputFormat::Csv => Err(csv_unsupported("runs")),
+        OutputFormat::Table => {
+            let mut table = String::new();
+            for run in runs {
+                let _ = writeln!(
+                    table,
+                    "{}  {}  {}  {}  {}  {}  {} rows  {}  {}",
+                    pad(&run.id.to_string(), 6),
+                    pad(run.run_id.as_deref().unwrap_or("-"), 26),
+                    run.started_at.format("%Y-%m-%d %H:%M:%S"),
+                    pad(&run.command, 8),
+                    pad(run.status.as_deref().unwrap_or("running"), 8),
+                    pad(&run.version, 8),
+                    run.rows,
+                    run.accounts,
+                    run.error.as_deref().unwrap_or_default()
+                );
+            }
+            Ok(table)
+        }
+    }
+}
+
+// 고객 번호별 data 최신 여부 (status)
+pub fn render_freshness(entries: &[Freshness], format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(entries).context("Failed to serialize status to JSON")
+        }
+        OutputFormat::Csv => Err(csv_unsupported("status")),
+        OutputFormat::Table => {
+            let mut table = String::new();
+            for entry in entries {
+                let _ = writeln!(
+                    table,
+                    "{}  {}  {}  {}  {}  {}",
+                    pad(&entry.customer_number, 14),
+                    pad(entry.health.as_str(), 5),
+                    pad(entry.latest_month.as_deref().unwrap_or("-"), 7),
+                    entry
+                        .last_success_at
+                        .map(|at| at.format("%Y-%m-%d %H:%M:%S").to_string())
+                        .unwrap_or_else(|| pad("-", 19)),
+                    pad(entry.last_status.as_deref().unwrap_or("-"), 7),
+                    entry.reasons.join("; ")
+                );
+            }
+            Ok(table)
+        }
+    }
+}
+
+// 계정별 Chrome profile 목록
+pub fn render_profiles(profiles: &[ProfileInfo], format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_string_pretty(profiles).context("Failed to serialize profiles to JSON")
+        }
+        OutputFormat::Csv => Err(csv_unsupported("profiles")),
+        OutputFormat::Table => {
+            let mut table = String::new();
+            for profile in profiles {
+                let _ = writeln!(
+                    table,
+                    "{}  {}  {}  {}",
+                    pad(&profile.account, 20),
+                    pad(if profile.in_use { "in use" } else { "idle" }, 6),
+                    profile
+                        .modified
+                        .map(|modified| modified.format("%Y-%m-%d %H:%M:%S").to_string())
+                        .unwrap_or_else(|| pad("-", 19)),
+                    profile.path.display()
+                );
+            }
+            Ok(table)
+        }
+    }
+}
+
+// SQL 조회 결과 (json 은 column 이름을 key 로 한 object 배열)
+pub fn render_query(
+    columns: &[String],
+    rows: &[Vec<Value>],
+    format: OutputFormat,
+) -> Result<String> {
+    match format {
+        OutputFormat::Json => {
+            let objects: Vec<serde_json::Map<String, Value>> = rows
+                .iter()
+                .map(|row| columns.iter().cloned().zip(row.iter().cloned()).collect())
+                .collect();
+            serde_json::to_string_pretty(&objects).context("Failed to serialize rows to JSON")
+        }
+        OutputFormat::Csv => csv::query(columns, rows),
+        OutputFormat::Table => {
+            let cells: Vec<Vec<String>> = rows
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|value| match value {
+                            Value::String(text) => text.clone(),
+                            Value::Null => String::new(),
+                            other => other.to_string(),
+                        })
+                        .collect()
+                })
+                .collect();
+            // column 마다 가장 긴 값 기준 폭
+            let widths: Vec<usize> = columns
+                .iter()
+                .enumerate()
+                .map(|(i, column)| {
+                    cells
+                        .iter()
+                        .map(|row| display_width(&row[i]))
+                        .chain([display_width(column)])
+                        .max()
+                        .unwrap_or_default()
+                })
+                .collect();
+
+            let mut table = String::new();
+            for row in [columns.to_vec()].iter().chain(&cells) {
+                let line: Vec<String> = row
+                    .iter()
+                    .zip(&widths)
+                    .map(|(cell, width)| pad(cell, *width))
+                    .collect();
+                let _ = writeln!(table, "{}", line.join("  ").trim_end());
+            }
+            Ok(table)
+        }
+    }
+}
+
+// 고객 번호별 결과 -> 출력 문자열
+pub fn render(
+    results: &BTreeMap<String, AccountResult>,
+    format: OutputFormat,
+    labels: Labels,
+    locale: Locale,
+    options: &RowOptions,
+) -> Result<String> {
+    match format {
+        OutputFormat::Json => to_json(results, options),
+        OutputFormat::Table => Ok(to_table(results, labels, locale)),
+        OutputFormat::Csv => csv::claims(results, options),
+    }
+}
+
+// JSON (고객 번호가 여럿이면 고객 번호별 상태, data 를 담은 map)
+fn to_json(results: &BTreeMap<String, AccountResult>, options: &RowOptions) -> Result<String> {
+    let results = results
+        .iter()
+        .map(|(customer_number, result)| {
+            let rows = result
+                .data
+                .iter()
+                .map(|entry| options.customer_row(customer_number, entry))
+                .collect::<Result<Vec<Value>>>()?;
+            Ok((customer_number, result, rows))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // 고객 정보가 있으면 고객 번호 1개도 고객 번호별 map
+    if let [(_, AccountResult { info: None, .. }, rows)] = &results[..] {
+        serde_json::to_string_pretty(rows)
+    } else {
+        let results: BTreeMap<_, _> = results
+            .into_iter()
+            .map(|(customer_number, result, rows)| {
+                let tags = tags::for_customer(&options.tags, customer_number);
+                (customer_number, result_value(result, rows, &tags))
+            })
+            .collect();
+        serde_json::to_string_pretty(&results)
+    }
+    .context("Failed to serialize data to JSON")
+}
+
+// 고객 번호별 상태, data (sink 전달용, 고객 번호 수와 무관하게 같은 형태)
+pub fn results_json(
+    results: &BTreeMap<String, AccountResult>,
+    options: &RowOptions,
+) -> Result<BTreeMap<String, Value>> {
+    results
+        .iter()
+        .map(|(customer_number, result)| {
+            let rows = result
+                .data
+                .iter()
+                .map(|entry| options.customer_row(customer_number, entry))
+                .collect::<Result<Vec<Value>>>()?;
+            let tags = tags::for_customer(&options.tags, customer_number);
+            Ok((customer_number.clone(), result_value(result, rows, &tags)))
+        })
+        .collect()
+}
+
+// 고객 번호 1건의 상태, 고객 정보, tag, data
+fn result_value(result: &AccountResult, rows: Vec<Value>, tags: &tags::Tags) -> Value {
+    let mut value = json!({
+        "status": result.status,
+        "error": result.error,
+        "data": rows,
+    });
+    if let Some(info) = &result.info {
+        value["customer"] = json!(info);
+    }
+    if !tags.is_empty() {
+        value["tags"] = json!(tags);
+    }
+    value
+}
+
+// 고객 번호별 표
+fn to_table(results: &BTreeMap<String, AccountResult>, labels: Labels, locale: Locale) -> String {
+    let mut table = String::new();
+    for (customer_number, result) in results {
+        let _ = write!(table, "{}: {}", labels.customer_number(), customer_number);
+        match &result.error {
+            Some(error) => {
+                let _ = writeln!(table, " ({}: {})", result.status.as_str(), error);
+            }
+            None => table.push('\n'),
+        }
+        if let Some(info) = &result.info {
+            let fields: Vec<&str> = [
+                &info.address,
+                &info.contract_type,
+                &info.supply_type,
+                &info.meter_number,
+            ]
+            .into_iter()
+            .filter_map(|field| field.as_deref())
+            .collect();
+            if !fields.is_empty() {
+                let _ = writeln!(table, "{}", fields.join(" / "));
+            }
+        }
+        let _ = writeln!(
+            table,
+            "{}  {}  {}",
+            pad(labels.claim_date(), 12),
+            pad(labels.usage(), 14),
+            pad(labels.paid(), 14)
+        );
+        for entry in &result.data {
+            let _ = writeln!(
+                table,
+                "{}  {}  {}{}",
+                pad(&entry.claim_date.format("%Y-%m").to_string(), 12),
+                pad(&locale.usage(entry.usage), 14),
+                pad(&locale.money(entry.paid), 14),
+                if entry.provisional {
+                    labels.provisional()
+                } else {
+                    ""
+                }
+            );
+        }
+        table.push('\n');
+    }
+    table
+}
+
+// 표시 폭 기준 padding (한글은 2칸)
+fn pad(text: &str, width: usize) -> String {
+    format!(
+        "{}{}",
+        text,
+        " ".repeat(width.saturating_sub(display_width(text)))
+    )
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| if c >= '\u{1100}' { 2 } else { 1 })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(day: u32, usage_kwh: f64) -> DailyUsage {
+        DailyUsage {
+            date: NaiveDate::from_ymd_opt(2024, 5, day).unwrap(),
+            usage_kwh,
+        }
+    }
+
+    fn stream(
+        format: OutputFormat,
+        ndjson: bool,
+        customers: &BTreeMap<String, Vec<DailyUsage>>,
+    ) -> String {
+        let mut out = Vec::new();
+        let mut stream: UsageStream<_, DailyUsage> =
+            UsageStream::new(&mut out, format, ndjson, Locale::KoKr, customers.len() > 1).unwrap();
+        for (customer_number, days) in customers {
+            stream.customer(customer_number).unwrap();
+            // page 단위로 나눠서 출력
+            for page in days.chunks(2) {
+                stream.write_rows(page).unwrap();
+            }
+        }
+        stream.finish().unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn usage_stream_json_matches_pretty_document() {
+        let customers = BTreeMap::from([
+            (
+                "0123456789".to_string(),
+                vec![day(1, 1.5), day(2, 2.0), day(3, 0.0)],
+            ),
+            ("0987654321".to_string(), Vec::new()),
+        ]);
+        assert_eq!(
+            stream(OutputFormat::Json, false, &customers),
+            format!("{}\n", serde_json::to_string_pretty(&customers).unwrap())
+        );
+        let empty = BTreeMap::new();
+        assert_eq!(stream(OutputFormat::Json, false, &empty), "{}\n");
+    }
+
+    #[test]
+    fn usage_stream_writes_csv_and_ndjson_rows() {
+        let customers = BTreeMap::from([("0123456789".to_string(), vec![day(1, 1.5)])]);
+        assert_eq!(
+            stream(OutputFormat::Csv, false, &customers),
+            "date,usage_kwh\n2024-05-01,1.5\n"
+        );
+        assert_eq!(
+            stream(OutputFormat::Csv, true, &customers),
+            "{\"customer_number\":\"0123456789\",\"date\":\"2024-05-01\",\"usage_kwh\":1.5}\n"
+        );
+    }
+}
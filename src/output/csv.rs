@@ -0,0 +1,106 @@
+use anyhow::{Context, Result};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+use super::{AccountResult, RowOptions};
+use crate::customer::Meter;
+
+// 청구 row column (고객 번호가 여럿이면 앞에 customer_number)
+const CLAIM_COLUMNS: &[&str] = &["claim_date", "usage", "paid"];
+
+// 요금 항목 column (--detailed 로 요금 항목이 있는 row 가 있을 때만, 그 외 항목은 JSON 에만)
+const BREAKDOWN_COLUMNS: &[&str] = &[
+    "base_charge",
+    "energy_charge",
+    "climate_charge",
+    "fuel_adjustment",
+    "vat",
+    "power_fund",
+    "tv_fee",
+];
+
+// 고객 번호별 결과 -> claim_date,usage,paid CSV (header 포함, 날짜, 금액은 --date-format, --money-format)
+// 납부 상태가 있는 row 가 있으면 paid 뒤에 payment_status column
+pub fn claims(results: &BTreeMap<String, AccountResult>, options: &RowOptions) -> Result<String> {
+    let keyed = results.len() > 1;
+    let entries = || results.values().flat_map(|result| &result.data);
+    let mut columns = CLAIM_COLUMNS.to_vec();
+    if entries().any(|entry| entry.payment_status.is_some()) {
+        columns.push("payment_status");
+    }
+    let detailed = entries().any(|entry| entry.breakdown.is_some());
+    let breakdown_columns = if detailed { BREAKDOWN_COLUMNS } else { &[] };
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    let header = keyed
+        .then_some("customer_number")
+        .into_iter()
+        .chain(columns.iter().copied())
+        .chain(breakdown_columns.iter().copied());
+    writer.write_record(header)?;
+    for (customer_number, result) in results {
+        for entry in &result.data {
+            let row = options.customer_row(customer_number, entry)?;
+            let cells = columns.iter().map(|column| cell(&row[*column])).chain(
+                breakdown_columns
+                    .iter()
+                    .map(|column| cell(&row["breakdown"][*column])),
+            );
+            if keyed {
+                writer.write_record(std::iter::once(customer_number.clone()).chain(cells))?;
+            } else {
+                writer.write_record(cells)?;
+            }
+        }
+    }
+    finish(writer)
+}
+
+// row 1개 -> CSV 한 줄 (줄바꿈 포함, 일별, 시간대별 사용량 stream)
+pub fn record<I>(cells: I) -> Result<String>
+where
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    writer.write_record(cells)?;
+    let bytes = writer.into_inner().context("Failed to write CSV")?;
+    String::from_utf8(bytes).context("CSV is not UTF-8")
+}
+
+// 고객 번호 목록 -> customer_number,display_name CSV
+pub fn meters(meters: &[Meter]) -> Result<String> {
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    writer.write_record(["customer_number", "display_name"])?;
+    for meter in meters {
+        writer.write_record([&meter.customer_number, &meter.display_name])?;
+    }
+    finish(writer)
+}
+
+// SQL 조회 결과 -> column 이름 header 의 CSV
+pub fn query(columns: &[String], rows: &[Vec<Value>]) -> Result<String> {
+    let mut writer = ::csv::Writer::from_writer(Vec::new());
+    writer.write_record(columns)?;
+    for row in rows {
+        writer.write_record(row.iter().map(cell))?;
+    }
+    finish(writer)
+}
+
+// 문자열은 따옴표 없이, null 은 빈 칸
+fn cell(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn finish(writer: ::csv::Writer<Vec<u8>>) -> Result<String> {
+    let bytes = writer.into_inner().context("Failed to write CSV")?;
+    // 출력 시 줄바꿈이 붙으므로 마지막 줄바꿈 제거
+    Ok(String::from_utf8(bytes)
+        .context("CSV is not UTF-8")?
+        .trim_end()
+        .to_string())
+}
@@ -0,0 +1,37 @@
+use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use super::AccountResult;
+use crate::error::{ErrorCode, Failure};
+use crate::store::{ClaimSource, MergePolicy, Store};
+
+const SCHEME: &str = "sqlite://";
+
+// --output sqlite://<path> 의 DB 경로 (파일 출력이면 None)
+pub fn path(output: &Path) -> Option<PathBuf> {
+    output
+        .to_str()
+        .and_then(|output| output.strip_prefix(SCHEME))
+        .map(PathBuf::from)
+}
+
+// 고객 번호별 결과를 monthly_claims (customer_number, claim_date) 에 upsert, 저장한 row 수
+// 같은 달을 다시 조회해도 row 가 늘지 않아 반복 실행해도 같은 결과
+pub fn write(
+    path: &Path,
+    results: &BTreeMap<String, AccountResult>,
+    merge_policy: MergePolicy,
+) -> Result<usize> {
+    let mut store = Store::open(path)
+        .context(Failure::new(
+            ErrorCode::Store,
+            format!("Failed to open {}", path.display()),
+        ))?
+        .with_merge_policy(merge_policy);
+    let mut written = 0;
+    for (customer_number, result) in results {
+        written += store.merge_claims(customer_number, &result.data, ClaimSource::Scraped)?;
+    }
+    Ok(written)
+}
@@ -0,0 +1,133 @@
+use fantoccini::elements::Element;
+use fantoccini::error::CmdError;
+use serde::{Deserialize, Deserializer};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::RwLock;
+use tokio::time::Duration;
+
+// 기본 human pacing 범위 (ms)
+const ACTION_DELAY: (u64, u64) = (400, 1500);
+const KEYSTROKE_DELAY: (u64, u64) = (50, 180);
+
+// 클릭, 입력 사이 대기 방식
+// human: 동작 전 임의 대기, 한 글자씩 간격을 두고 입력 (human:400-1500/50-180)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Pacing {
+    #[default]
+    Off,
+    Human {
+        action: (u64, u64),
+        keystroke: (u64, u64),
+    },
+}
+
+// "400-1500" -> (400, 1500)
+fn parse_range(s: &str) -> Result<(u64, u64), String> {
+    let (min, max) = s
+        .split_once('-')
+        .ok_or_else(|| format!("Invalid delay range, expected <min>-<max> ms: {}", s))?;
+    let min: u64 = min
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid delay: {}", min))?;
+    let max: u64 = max
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid delay: {}", max))?;
+    if min > max {
+        return Err(format!("Delay range min is larger than max: {}", s));
+    }
+    Ok((min, max))
+}
+
+impl FromStr for Pacing {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "off" => Ok(Pacing::Off),
+            "human" => Ok(Pacing::Human {
+                action: ACTION_DELAY,
+                keystroke: KEYSTROKE_DELAY,
+            }),
+            other => {
+                let ranges = other.strip_prefix("human:").ok_or_else(|| {
+                    format!(
+                        "Invalid pacing, expected off, human or human:<min>-<max>[/<min>-<max>]: {}",
+                        other
+                    )
+                })?;
+                let (action, keystroke) = match ranges.split_once('/') {
+                    Some((action, keystroke)) => (parse_range(action)?, parse_range(keystroke)?),
+                    None => (parse_range(ranges)?, KEYSTROKE_DELAY),
+                };
+                Ok(Pacing::Human { action, keystroke })
+            }
+        }
+    }
+}
+
+impl fmt::Display for Pacing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pacing::Off => write!(f, "off"),
+            Pacing::Human { action, keystroke } => write!(
+                f,
+                "human:{}-{}/{}-{}",
+                action.0, action.1, keystroke.0, keystroke.1
+            ),
+        }
+    }
+}
+
+// pipeline 설정 파일의 pacing 문자열
+impl<'de> Deserialize<'de> for Pacing {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+// 실행 중 적용되는 pacing (실행 시작 시 설정)
+static CURRENT: RwLock<Pacing> = RwLock::new(Pacing::Off);
+
+pub fn configure(pacing: Pacing) {
+    if let Ok(mut current) = CURRENT.write() {
+        *current = pacing;
+    }
+}
+
+fn current() -> Pacing {
+    CURRENT.read().map_or(Pacing::Off, |current| *current)
+}
+
+async fn sleep_between((min, max): (u64, u64)) {
+    tokio::time::sleep(Duration::from_millis(fastrand::u64(min..=max))).await;
+}
+
+// 동작 전 대기 (off 면 바로 반환)
+pub async fn pause() {
+    if let Pacing::Human { action, .. } = current() {
+        sleep_between(action).await;
+    }
+}
+
+pub async fn click(element: &Element) -> Result<(), CmdError> {
+    pause().await;
+    element.click().await
+}
+
+// 입력 (human 이면 한 글자씩)
+pub async fn type_text(element: &Element, text: &str) -> Result<(), CmdError> {
+    let Pacing::Human { keystroke, .. } = current() else {
+        return element.send_keys(text).await;
+    };
+    pause().await;
+    for c in text.chars() {
+        element.send_keys(&c.to_string()).await?;
+        sleep_between(keystroke).await;
+    }
+    Ok(())
+}
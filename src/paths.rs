@@ -0,0 +1,163 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use ulid::Ulid;
+
+// data dir 하위 artifact 디렉터리 (checkpoints, screenshots, har 는 실행별 runs/ 이전 위치)
+const ARTIFACT_DIRS: &[&str] = &[
+    "runs",
+    "checkpoints",
+    "sessions",
+    "screenshots",
+    "pdfs",
+    "har",
+    "captcha",
+];
+
+// checkpoint, session cache, screenshot, pdf, HAR 저장 위치 (XDG data dir 기준)
+#[derive(Debug, Clone)]
+pub struct DataDir {
+    root: PathBuf,
+    // 이번 실행 id (ULID), 실행별 artifact 는 runs/<run id>/ 에 저장
+    run_id: String,
+}
+
+impl DataDir {
+    // 지정 경로 > $XDG_DATA_HOME/rip_hyphen (OS 별 data dir)
+    pub fn resolve(custom: Option<&Path>) -> Result<Self> {
+        let root = match custom {
+            Some(path) => path.to_path_buf(),
+            None => dirs::data_dir()
+                .context("Failed to resolve the user data directory")?
+                .join("rip_hyphen"),
+        };
+        Ok(Self {
+            root,
+            run_id: Ulid::new().to_string(),
+        })
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn run_id(&self) -> &str {
+        &self.run_id
+    }
+
+    // 이번 실행의 artifact 디렉터리 (동시에 실행되는 다른 profile 과 겹치지 않도록)
+    pub fn run_dir(&self) -> PathBuf {
+        self.root.join("runs").join(&self.run_id)
+    }
+
+    pub fn screenshots(&self) -> PathBuf {
+        self.run_dir().join("screenshots")
+    }
+
+    // session import 로 가져온 cookie
+    pub fn session_file(&self) -> PathBuf {
+        self.root.join("sessions").join("cookies.json")
+    }
+
+    // 계정별 Chrome profile (수정 시각 기준 clean 대상 아님, profiles clean 으로 삭제)
+    pub fn profiles_dir(&self) -> PathBuf {
+        self.root.join("profiles")
+    }
+
+    // 청구 월별 보관 자료 (archive, clean 대상 아님)
+    pub fn archives_dir(&self) -> PathBuf {
+        self.root.join("archives")
+    }
+
+    // 연도 page 원본 HTML (--archive-raw, 재parsing 용이라 clean 대상 아님)
+    pub fn raw_pages_dir(&self) -> PathBuf {
+        self.root.join("raw_pages")
+    }
+
+    // 발견한 data endpoint 목록 (clean 대상 아님)
+    pub fn endpoints_file(&self) -> PathBuf {
+        self.root.join("endpoints.json")
+    }
+
+    // --download-driver 로 받은 chromedriver (Chrome build 별, clean 대상 아님)
+    pub fn drivers_dir(&self) -> PathBuf {
+        self.root.join("drivers")
+    }
+
+    // --since-last 의 고객 번호별 최근 청구 월 (clean 대상 아님)
+    pub fn state_file(&self) -> PathBuf {
+        self.root.join("state.json")
+    }
+
+    // 마지막 canary 결과 (--require-canary 가 확인, clean 대상 아님)
+    pub fn canary_file(&self) -> PathBuf {
+        self.root.join("canary.json")
+    }
+
+    // 로그인 때 나타난 CAPTCHA 화면
+    pub fn captcha_dir(&self) -> PathBuf {
+        self.root.join("captcha")
+    }
+
+    // 이번 실행의 HAR 파일 경로
+    pub fn har_file(&self) -> PathBuf {
+        self.run_dir().join("network.har")
+    }
+
+    // 이번 실행의 중간 결과 파일 경로
+    pub fn checkpoint_file(&self) -> PathBuf {
+        self.run_dir().join("checkpoint.json")
+    }
+
+    // 하위 디렉터리 생성
+    pub fn ensure(&self) -> Result<()> {
+        let run_dirs = [self.run_dir(), self.screenshots()];
+        for path in ARTIFACT_DIRS
+            .iter()
+            .map(|dir| self.root.join(dir))
+            .chain(run_dirs)
+        {
+            fs::create_dir_all(&path)
+                .context(format!("Failed to create directory: {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    // older_than 보다 오래된 artifact 삭제, 삭제한 파일 수 반환
+    pub fn clean(&self, older_than: Duration) -> Result<usize> {
+        let cutoff = SystemTime::now() - older_than;
+        let mut removed = 0;
+        for dir in ARTIFACT_DIRS {
+            removed += remove_older_than(&self.root.join(dir), cutoff)?;
+        }
+        Ok(removed)
+    }
+}
+
+// 디렉터리 내 cutoff 이전 수정 파일 삭제 (하위 디렉터리 포함)
+fn remove_older_than(dir: &Path, cutoff: SystemTime) -> Result<usize> {
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let mut removed = 0;
+    for entry in fs::read_dir(dir).context(format!("Failed to read {}", dir.display()))? {
+        let path = entry?.path();
+        if path.is_dir() {
+            removed += remove_older_than(&path, cutoff)?;
+            // 비어 있으면 디렉터리도 삭제
+            if fs::read_dir(&path)?.next().is_none() {
+                fs::remove_dir(&path)?;
+            }
+            continue;
+        }
+
+        let modified = fs::metadata(&path)?.modified()?;
+        if modified < cutoff {
+            fs::remove_file(&path).context(format!("Failed to remove {}", path.display()))?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
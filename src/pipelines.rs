@@ -0,0 +1,262 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use tokio::time::{Duration, Instant};
+
+use crate::blackout;
+use crate::cli::{Cli, FetchArgs};
+use crate::config::{self, CredentialSource};
+use crate::customer;
+use crate::error::{ErrorCode, Failure};
+use crate::pacing::Pacing;
+use crate::sinks::SinkTarget;
+
+// 조회 종류 (지금은 월별 청구 요금만)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Fetcher {
+    MonthlyBills,
+}
+
+// 조회 + 전달 sink + 주기
+// 예: {"name": "bills", "fetch": "monthly-bills", "sinks": ["sqlite:kepco.db", "mqtt://broker/kepco"], "every_minutes": 1440, "blackout": ["23:30-01:00"]}
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Pipeline {
+    pub name: String,
+    pub fetch: Fetcher,
+    #[serde(default)]
+    pub sinks: Vec<SinkTarget>,
+    // 새 청구 월만 알릴 sink (--store 필요)
+    #[serde(default)]
+    pub notify: Vec<SinkTarget>,
+    pub every_minutes: u64,
+    // 클릭, 입력 간격 (없으면 --pacing)
+    pub pacing: Option<Pacing>,
+    // serve 의 공정 scheduling 단위 (없으면 pipeline 이름, 같은 tenant 는 동시에 1개만 조회)
+    #[cfg_attr(not(feature = "server"), allow(dead_code))]
+    pub tenant: Option<String>,
+    // tenant 계정 로그인 정보 (없으면 --credentials)
+    pub credentials: Option<CredentialSource>,
+    // 조회할 고객 번호 (없으면 --customer, PP_NUMBER)
+    #[serde(default)]
+    pub customers: Vec<String>,
+    // 조회를 시작하지 않는 KST 시간대 (없으면 --blackout, 예: ["23:30-01:00"])
+    #[serde(default)]
+    pub blackout: Vec<blackout::Window>,
+}
+
+impl Pipeline {
+    // 공통 옵션에 pipeline 의 sink 적용
+    pub fn cli(&self, base: &Cli) -> Cli {
+        Cli {
+            command: None,
+            sink: self.sinks.clone(),
+            notify: self.notify.clone(),
+            pacing: self.pacing.unwrap_or(base.pacing),
+            blackout: if self.blackout.is_empty() {
+                base.blackout.clone()
+            } else {
+                self.blackout.clone()
+            },
+            credentials: self
+                .credentials
+                .clone()
+                .unwrap_or_else(|| base.credentials.clone()),
+            fetch: FetchArgs {
+                customer: if self.customers.is_empty() {
+                    base.fetch.customer.clone()
+                } else {
+                    self.customers.clone()
+                },
+                ..base.fetch.clone()
+            },
+            ..base.clone()
+        }
+    }
+
+    #[cfg(feature = "server")]
+    pub fn tenant(&self) -> &str {
+        self.tenant.as_deref().unwrap_or(&self.name)
+    }
+}
+
+// pipelines 파일 schema version (형식이 바뀌면 올리고 MIGRATIONS 에 이전 version 변환 추가)
+pub const SCHEMA_VERSION: u64 = 2;
+
+// version n -> n + 1 변환 설명, 변환 함수
+type Migration = (&'static str, fn(Value) -> Value);
+
+// index 0 이 version 1 -> 2 (config migrate 가 설명 출력)
+const MIGRATIONS: &[Migration] = &[(
+    "wrap the pipeline array in {\"version\": 2, \"pipelines\": [...]}",
+    wrap_pipelines,
+)];
+
+// version 2 부터의 파일 형식
+// 예: {"version": 2, "pipelines": [{"name": "bills", "fetch": "monthly-bills", "every_minutes": 1440}]}
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PipelinesFile {
+    // schema_version 으로 먼저 확인
+    #[allow(dead_code)]
+    version: u64,
+    pipelines: Vec<Pipeline>,
+}
+
+// config migrate 가 쓰는 형식 (version 을 앞에)
+#[derive(Serialize)]
+struct Migrated<'a> {
+    version: u64,
+    pipelines: &'a Value,
+}
+
+// 파일의 schema version (version 이 없는 JSON 배열은 1)
+pub fn schema_version(value: &Value) -> Result<u64> {
+    let config_error = |message: String| Failure::new(ErrorCode::Config, message);
+    let version = match value {
+        Value::Array(_) => 1,
+        Value::Object(file) => file
+            .get("version")
+            .and_then(Value::as_u64)
+            .context(config_error("Pipelines file has no numeric version".into()))?,
+        _ => {
+            return Err(config_error(
+                "Pipelines file must be an array or an object with version and pipelines".into(),
+            )
+            .into())
+        }
+    };
+    if version == 0 || version > SCHEMA_VERSION {
+        return Err(config_error(format!(
+            "Unsupported pipelines file version {}, this build reads versions 1 to {}",
+            version, SCHEMA_VERSION
+        ))
+        .into());
+    }
+    Ok(version)
+}
+
+// 이전 version 파일을 현재 version 형식으로 변환, 적용한 변환 설명 반환
+pub fn migrate(mut value: Value, version: u64) -> (Value, Vec<&'static str>) {
+    let mut applied = Vec::new();
+    for (description, step) in &MIGRATIONS[(version - 1) as usize..] {
+        value = step(value);
+        applied.push(*description);
+    }
+    (value, applied)
+}
+
+// 변환 결과 JSON 문자열 (key 는 version 외에 이름 순)
+pub fn to_text(migrated: &Value) -> Result<String> {
+    let text = serde_json::to_string_pretty(&Migrated {
+        version: SCHEMA_VERSION,
+        pipelines: &migrated["pipelines"],
+    })?;
+    Ok(format!("{}\n", text))
+}
+
+// version 1 -> 2: JSON 배열을 version, pipelines object 로
+fn wrap_pipelines(value: Value) -> Value {
+    json!({ "version": 2, "pipelines": value })
+}
+
+pub fn load(path: &Path) -> Result<Vec<Pipeline>> {
+    let text = fs::read_to_string(path).context(format!("Failed to read {}", path.display()))?;
+    parse(&text, path)
+}
+
+// 이전 version 은 읽을 때 변환, 이름 중복, 0분 주기는 설정 오류
+pub fn parse(text: &str, path: &Path) -> Result<Vec<Pipeline>> {
+    let value: Value = config::from_json(text, path, "pipelines file")?;
+    let version = schema_version(&value)?;
+    let file: PipelinesFile = if version == SCHEMA_VERSION {
+        config::from_json(text, path, "pipelines file")?
+    } else {
+        eprintln!(
+            "Pipelines file {} uses schema version {}, run `config migrate` to upgrade it to version {}",
+            path.display(),
+            version,
+            SCHEMA_VERSION
+        );
+        let (migrated, _) = migrate(value, version);
+        serde_json::from_value(migrated).context(Failure::new(
+            ErrorCode::Config,
+            format!(
+                "Invalid pipelines file {} (version {})",
+                path.display(),
+                version
+            ),
+        ))?
+    };
+    let mut pipelines = file.pipelines;
+
+    let config_error = |message: String| Failure::new(ErrorCode::Config, message);
+    if pipelines.is_empty() {
+        return Err(config_error(format!("No pipeline in {}", path.display())).into());
+    }
+    // 고객 번호는 숫자만 남긴 형식으로
+    for pipeline in &mut pipelines {
+        pipeline.customers = pipeline
+            .customers
+            .iter()
+            .map(|number| customer::validate(number))
+            .collect::<Result<_>>()
+            .map_err(|e| config_error(format!("Pipeline {}: {:#}", pipeline.name, e)))?;
+    }
+    let mut names = HashSet::new();
+    for pipeline in &pipelines {
+        if !names.insert(pipeline.name.as_str()) {
+            return Err(config_error(format!("Duplicate pipeline name: {}", pipeline.name)).into());
+        }
+        if pipeline.every_minutes == 0 {
+            return Err(
+                config_error(format!("Pipeline {} has every_minutes 0", pipeline.name)).into(),
+            );
+        }
+        if pipeline.sinks.is_empty() && pipeline.notify.is_empty() {
+            eprintln!(
+                "Pipeline {} has no sinks, results will only be printed",
+                pipeline.name
+            );
+        }
+    }
+    Ok(pipelines)
+}
+
+// pipeline 별 다음 실행 시각 (시작 시 모두 실행)
+pub struct Schedule {
+    due: Vec<Instant>,
+}
+
+impl Schedule {
+    pub fn new(pipelines: &[Pipeline]) -> Self {
+        Self {
+            due: vec![Instant::now(); pipelines.len()],
+        }
+    }
+
+    // 가장 먼저 실행할 pipeline 과 시각
+    pub fn next(&self) -> (usize, Instant) {
+        self.due
+            .iter()
+            .copied()
+            .enumerate()
+            .min_by_key(|(_, at)| *at)
+            .expect("at least one pipeline")
+    }
+
+    // 실행을 마친 pipeline 의 다음 시각 (실행 시간과 무관하게 주기 유지)
+    pub fn done(&mut self, index: usize, pipeline: &Pipeline) {
+        let interval = Duration::from_secs(pipeline.every_minutes * 60);
+        let mut next = self.due[index] + interval;
+        // 실행이 주기보다 오래 걸리면 밀린 회차는 건너뜀
+        while next <= Instant::now() {
+            next += interval;
+        }
+        self.due[index] = next;
+    }
+}
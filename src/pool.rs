@@ -0,0 +1,130 @@
+use fantoccini::Client;
+use std::sync::Arc;
+use tokio::sync::{Mutex, MutexGuard};
+use tokio::time::{timeout, Duration};
+
+use crate::driver::DriverGuard;
+use crate::latency::PageLatency;
+use crate::profiles::ChromeProfile;
+use crate::selectors::SelectorProfile;
+
+// health check 응답 대기 시간
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(10);
+
+// 실행 중인 ChromeDriver 와 연결된 client (drop 시 driver 종료)
+pub struct Driver {
+    _process: DriverGuard,
+    pub client: Arc<Client>,
+    // WebDriver url (HAR 수집)
+    pub url: String,
+    // 계정 전용 Chrome profile lock (driver 종료 후 해제)
+    _chrome_profile: Option<ChromeProfile>,
+}
+
+impl Driver {
+    pub fn new(
+        process: DriverGuard,
+        client: Client,
+        url: String,
+        chrome_profile: Option<ChromeProfile>,
+    ) -> Self {
+        Self {
+            _process: process,
+            client: Arc::new(client),
+            url,
+            _chrome_profile: chrome_profile,
+        }
+    }
+}
+
+// 로그인된 browser session (session 별 loading 통계, 월별 청구 요금 url, selector)
+pub struct BrowserSession {
+    pub driver: Driver,
+    pub latency: PageLatency,
+    pub claim_url: Option<String>,
+    pub profile: SelectorProfile,
+    // 처리한 고객 번호 수
+    pub jobs: u32,
+}
+
+impl BrowserSession {
+    pub fn client(&self) -> &Arc<Client> {
+        &self.driver.client
+    }
+
+    // script 실행 응답 확인
+    pub async fn healthy(&self) -> bool {
+        matches!(
+            timeout(
+                HEALTH_TIMEOUT,
+                self.client().execute("return document.readyState", vec![]),
+            )
+            .await,
+            Ok(Ok(_))
+        )
+    }
+
+    // WebDriver session 종료 후 driver 종료 (browser 가 profile 을 놓도록)
+    pub async fn close(self) {
+        let client = (*self.driver.client).clone();
+        if let Ok(Err(e)) = timeout(HEALTH_TIMEOUT, client.close()).await {
+            eprintln!("Failed to close the WebDriver session: {}", e);
+        }
+    }
+}
+
+// 크기가 고정된 browser session pool (slot 마다 worker 1개가 session 을 소유)
+pub struct BrowserPool {
+    slots: Vec<Mutex<Option<BrowserSession>>>,
+    // session 당 처리할 고객 번호 수 (초과 시 재시작)
+    recycle_after: Option<u32>,
+}
+
+impl BrowserPool {
+    pub fn new(size: usize, recycle_after: Option<u32>) -> Self {
+        Self {
+            slots: (0..size.max(1)).map(|_| Mutex::new(None)).collect(),
+            recycle_after,
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.slots.len()
+    }
+
+    // slot 의 session (없으면 None, 호출한 쪽에서 시작)
+    pub async fn slot(&self, slot: usize) -> MutexGuard<'_, Option<BrowserSession>> {
+        self.slots[slot].lock().await
+    }
+
+    // job 수 초과, 응답 없음이면 재시작 사유 반환
+    pub async fn recycle_reason(&self, session: &BrowserSession) -> Option<&'static str> {
+        if self
+            .recycle_after
+            .is_some_and(|limit| session.jobs >= limit)
+        {
+            return Some("job limit reached");
+        }
+        if !session.healthy().await {
+            return Some("health check failed");
+        }
+        None
+    }
+
+    // 진단 정보 수집용 client (사용 중이 아닌 첫 session)
+    pub fn client(&self) -> Option<Arc<Client>> {
+        self.slots.iter().find_map(|slot| {
+            slot.try_lock()
+                .ok()?
+                .as_ref()
+                .map(|session| Arc::clone(session.client()))
+        })
+    }
+
+    // 모든 session 의 driver 종료
+    pub async fn shutdown(&self) {
+        for slot in &self.slots {
+            slot.lock().await.take();
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use anyhow::{Context, Result};
+use fantoccini::Client;
+use serde::Deserialize;
+use std::env;
+use std::fs;
+use std::path::Path;
+use tokio::time::Duration;
+
+use crate::error::{ErrorCode, Failure};
+use crate::selectors::{Selector, SelectorSpec};
+use crate::{config, pacing};
+
+// 요소 대기 기본값
+const WAIT: Duration = Duration::from_secs(30);
+const POLL: Duration = Duration::from_millis(250);
+
+// 로그인 전 이동 단계 (사내 SSO 경유 페이지 등)
+// 값의 ${NAME} 은 환경 변수로 치환 (비밀번호를 파일에 두지 않도록)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Step {
+    // url 이동
+    Goto(String),
+    // 요소가 나타날 때까지 대기 (초 단위 timeout 지정 가능)
+    Wait {
+        selector: SelectorSpec,
+        timeout_secs: Option<u64>,
+    },
+    Click(SelectorSpec),
+    // 입력 요소에 값 입력
+    Fill {
+        selector: SelectorSpec,
+        value: String,
+    },
+    // 고정 대기 (ms)
+    Sleep(u64),
+}
+
+// JSON 배열 파일 (예: [{"goto": "https://sso.example.com"}, {"fill": {"selector": "css:#id", "value": "${SSO_ID}"}}])
+#[derive(Debug, Clone, Default)]
+pub struct Script {
+    steps: Vec<Step>,
+}
+
+impl Script {
+    pub fn load(path: &Path) -> Result<Self> {
+        let text =
+            fs::read_to_string(path).context(format!("Failed to read {}", path.display()))?;
+        let steps: Vec<Step> = config::from_json(&text, path, "pre-login script")?;
+        // 환경 변수 누락은 driver 실행 전에 확인
+        for step in &steps {
+            if let Step::Goto(value) | Step::Fill { value, .. } = step {
+                expand(value)?;
+            }
+        }
+        Ok(Self { steps })
+    }
+
+    // 순서대로 실행 (실패한 단계 번호 포함)
+    pub async fn run(&self, client: &Client) -> Result<()> {
+        if self.steps.is_empty() {
+            return Ok(());
+        }
+        for (index, step) in self.steps.iter().enumerate() {
+            run_step(client, step)
+                .await
+                .context(format!("Pre-login step {} failed", index + 1))?;
+        }
+        Ok(())
+    }
+}
+
+async fn run_step(client: &Client, step: &Step) -> Result<()> {
+    match step {
+        Step::Goto(url) => client
+            .goto(&expand(url)?)
+            .await
+            .context(Failure::new(ErrorCode::Navigation, "Failed to navigate")),
+        Step::Wait {
+            selector,
+            timeout_secs,
+        } => {
+            let duration = timeout_secs.map_or(WAIT, Duration::from_secs);
+            Selector(vec![selector.clone()])
+                .wait(client, duration, POLL)
+                .await?;
+            Ok(())
+        }
+        Step::Click(selector) => {
+            let resolved = Selector(vec![selector.clone()])
+                .wait(client, WAIT, POLL)
+                .await?;
+            pacing::click(&client.find(resolved.locator()).await?)
+                .await
+                .context(format!("Failed to click the element: {:?}", selector))
+        }
+        Step::Fill { selector, value } => {
+            let resolved = Selector(vec![selector.clone()])
+                .wait(client, WAIT, POLL)
+                .await?;
+            pacing::type_text(&client.find(resolved.locator()).await?, &expand(value)?)
+                .await
+                .context(format!("Failed to enter text: {:?}", selector))
+        }
+        Step::Sleep(millis) => {
+            tokio::time::sleep(Duration::from_millis(*millis)).await;
+            Ok(())
+        }
+    }
+}
+
+// ${NAME} -> 환경 변수 값
+fn expand(value: &str) -> Result<String> {
+    let mut expanded = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        let Some(end) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 2..start + end];
+        let replacement = env::var(name).map_err(|_| {
+            Failure::new(
+                ErrorCode::Config,
+                format!("Pre-login script uses {} which is not set", name),
+            )
+        })?;
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&replacement);
+        rest = &rest[start + end + 1..];
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
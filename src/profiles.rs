@@ -0,0 +1,154 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use crate::error::{ErrorCode, Failure};
+
+// 사용 중 표시 (실행 중인 process id)
+const LOCK_FILE: &str = "rip_hyphen.lock";
+
+// 계정 전용 Chrome profile (--user-data-dir), 실행 동안 lock 유지
+#[derive(Debug)]
+pub struct ChromeProfile {
+    dir: PathBuf,
+}
+
+impl ChromeProfile {
+    // 계정 profile 생성 또는 재사용, 다른 실행이 사용 중이면 실패
+    pub fn acquire(root: &Path, user_id: &str) -> Result<Self> {
+        let dir = root.join(account_key(user_id));
+        fs::create_dir_all(&dir)
+            .context(format!("Failed to create directory: {}", dir.display()))?;
+
+        let lock = dir.join(LOCK_FILE);
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&lock) {
+                Ok(mut file) => {
+                    write!(file, "{}", std::process::id())?;
+                    break;
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    if let Some(pid) = lock_owner(&lock) {
+                        return Err(Failure::new(
+                            ErrorCode::Config,
+                            format!(
+                                "Chrome profile {} is in use by process {}",
+                                dir.display(),
+                                pid
+                            ),
+                        )
+                        .into());
+                    }
+                    // 비정상 종료로 남은 lock
+                    eprintln!("Removing stale profile lock: {}", lock.display());
+                    fs::remove_file(&lock)
+                        .context(format!("Failed to remove {}", lock.display()))?;
+                }
+                Err(e) => {
+                    return Err(e).context(format!("Failed to lock {}", lock.display()));
+                }
+            }
+        }
+
+        println!("Using Chrome profile: {}", dir.display());
+        Ok(Self { dir })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+}
+
+impl Drop for ChromeProfile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(self.dir.join(LOCK_FILE));
+    }
+}
+
+// 저장된 profile 정보 (profiles list)
+#[derive(Debug, Serialize)]
+pub struct ProfileInfo {
+    pub account: String,
+    pub path: PathBuf,
+    pub in_use: bool,
+    pub modified: Option<chrono::DateTime<chrono::Local>>,
+}
+
+// 저장된 계정 profile 목록
+pub fn list(root: &Path) -> Result<Vec<ProfileInfo>> {
+    if !root.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut profiles = Vec::new();
+    for entry in fs::read_dir(root).context(format!("Failed to read {}", root.display()))? {
+        let path = entry?.path();
+        if !path.is_dir() {
+            continue;
+        }
+        profiles.push(ProfileInfo {
+            account: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            in_use: lock_owner(&path.join(LOCK_FILE)).is_some(),
+            modified: fs::metadata(&path)
+                .and_then(|metadata| metadata.modified())
+                .ok()
+                .map(Into::into),
+            path,
+        });
+    }
+    profiles.sort_by(|a, b| a.account.cmp(&b.account));
+    Ok(profiles)
+}
+
+// profile 삭제 (account 지정 시 해당 계정만, 사용 중인 profile 제외), 삭제한 수 반환
+pub fn remove(root: &Path, user_id: Option<&str>) -> Result<usize> {
+    let key = user_id.map(account_key);
+    let mut removed = 0;
+    for profile in list(root)? {
+        if key.as_ref().is_some_and(|key| *key != profile.account) {
+            continue;
+        }
+        if profile.in_use {
+            eprintln!("Skipping Chrome profile in use: {}", profile.path.display());
+            continue;
+        }
+        fs::remove_dir_all(&profile.path)
+            .context(format!("Failed to remove {}", profile.path.display()))?;
+        removed += 1;
+    }
+    Ok(removed)
+}
+
+// 계정 id 를 디렉터리 이름으로 (영문, 숫자, -, _ 외 문자는 _)
+fn account_key(user_id: &str) -> String {
+    user_id
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+// lock 을 가진 process 가 살아 있으면 process id 반환
+fn lock_owner(lock: &Path) -> Option<u32> {
+    let pid: u32 = fs::read_to_string(lock).ok()?.trim().parse().ok()?;
+    let alive = Command::new("kill")
+        .arg("-0")
+        .arg(pid.to_string())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success());
+    alive.then_some(pid)
+}
@@ -0,0 +1,116 @@
+use anyhow::{Context, Result};
+use fantoccini::{elements::Element, Client, Locator};
+
+use crate::client::{click_element, wait_for_loading};
+use crate::error::{ErrorCode, Failure};
+use crate::latency::PageLatency;
+use crate::pacing;
+use crate::selectors::Resolved;
+
+// 조회 조건 입력 요소 (Select, Text, Radio 는 일별/시간별 view 용)
+#[derive(Debug, Clone, Copy)]
+pub enum Input<'a> {
+    // 이미 찾은 option 요소 선택
+    Option(&'a Element),
+    // select box 에서 표시 문자열이 일치하는 option 선택
+    #[allow(dead_code)]
+    Select {
+        select: Locator<'a>,
+        text: &'a str,
+    },
+    // date picker 등 text 입력 (기존 값 삭제 후 입력)
+    Text {
+        locator: Locator<'a>,
+        value: &'a str,
+    },
+    // radio group 에서 value 가 일치하는 항목 선택
+    #[allow(dead_code)]
+    Radio {
+        locator: Locator<'a>,
+        value: &'a str,
+    },
+}
+
+impl Input<'_> {
+    async fn apply(&self, client: &Client) -> Result<()> {
+        pacing::pause().await;
+        match *self {
+            Input::Option(option) => option.click().await.context("Failed to select option"),
+            Input::Select { select, text } => {
+                let options = select_options(client, select).await?;
+                let mut available = Vec::with_capacity(options.len());
+                for option in &options {
+                    let option_text = option.text().await?;
+                    if option_text.trim() == text {
+                        return option.click().await.context("Failed to select option");
+                    }
+                    available.push(option_text.trim().to_string());
+                }
+                Err(Failure::new(
+                    ErrorCode::ElementNotFound,
+                    format!("Option '{}' not found in {:?}", text, available),
+                )
+                .with_selector(select)
+                .into())
+            }
+            Input::Text { locator, value } => {
+                let element = client.find(locator).await.context(
+                    Failure::new(ErrorCode::ElementNotFound, "Failed to find input")
+                        .with_selector(locator),
+                )?;
+                element.clear().await.context("Failed to clear input")?;
+                pacing::type_text(&element, value)
+                    .await
+                    .context("Failed to enter text")
+            }
+            Input::Radio { locator, value } => {
+                for radio in client.find_all(locator).await? {
+                    if radio.attr("value").await?.as_deref() == Some(value) {
+                        return radio.click().await.context("Failed to select radio");
+                    }
+                }
+                Err(Failure::new(
+                    ErrorCode::ElementNotFound,
+                    format!("Radio value '{}' not found", value),
+                )
+                .with_selector(locator)
+                .into())
+            }
+        }
+    }
+}
+
+// 조회 조건 입력 -> 조회 버튼 클릭 -> loading 대기 (view 별 공통 흐름)
+#[derive(Debug, Clone)]
+pub struct QueryControl {
+    // 조회 버튼 (resolve 된 selector)
+    pub submit: Resolved,
+    // loading 대기 단계 이름 (PageLatency 기록, timeout 설정)
+    pub page: &'static str,
+}
+
+impl QueryControl {
+    pub async fn query(
+        &self,
+        client: &Client,
+        inputs: &[Input<'_>],
+        latency: &mut PageLatency,
+    ) -> Result<()> {
+        for input in inputs {
+            input.apply(client).await?;
+        }
+        click_element(client, self.submit.locator()).await?;
+        wait_for_loading(client, latency, self.page).await
+    }
+}
+
+// select box 의 option 요소 목록
+pub async fn select_options(client: &Client, select: Locator<'_>) -> Result<Vec<Element>> {
+    client
+        .find(select)
+        .await
+        .context("Failed to find select element")?
+        .find_all(Locator::Css("option"))
+        .await
+        .context("Failed to find options")
+}
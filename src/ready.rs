@@ -0,0 +1,75 @@
+use std::str::FromStr;
+
+use crate::latency::PAGES;
+
+// 기본 page 준비 조건: loading overlay 가 display: none
+const DEFAULT_OVERLAY: &str = "#backgroundLayer";
+
+// page loading 완료 판단 조건
+#[derive(Debug, Clone, PartialEq)]
+pub enum ReadyCondition {
+    // CSS selector 요소가 숨겨짐 (loading overlay, spinner)
+    Hidden(String),
+    // CSS selector 요소가 나타남
+    Present(String),
+    // JS 식이 true
+    Script(String),
+}
+
+impl Default for ReadyCondition {
+    fn default() -> Self {
+        ReadyCondition::Hidden(DEFAULT_OVERLAY.to_string())
+    }
+}
+
+impl FromStr for ReadyCondition {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (kind, arg) = s
+            .split_once(':')
+            .ok_or_else(|| format!("Invalid ready condition, expected <kind>:<arg>: {}", s))?;
+        let arg = arg.trim().to_string();
+        if arg.is_empty() {
+            return Err(format!("Empty ready condition: {}", s));
+        }
+        match kind.trim() {
+            "hidden" => Ok(ReadyCondition::Hidden(arg)),
+            "present" => Ok(ReadyCondition::Present(arg)),
+            "js" => Ok(ReadyCondition::Script(arg)),
+            kind => Err(format!(
+                "Unknown ready condition '{}', expected hidden, present or js",
+                kind
+            )),
+        }
+    }
+}
+
+// 단계별 준비 조건 (--page-ready year=present:#grid)
+#[derive(Debug, Clone)]
+pub struct PageReady {
+    pub page: String,
+    pub condition: ReadyCondition,
+}
+
+impl FromStr for PageReady {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (page, condition) = s
+            .split_once('=')
+            .ok_or_else(|| format!("Invalid page ready, expected <step>=<kind>:<arg>: {}", s))?;
+        let page = page.trim();
+        if !PAGES.contains(&page) {
+            return Err(format!(
+                "Unknown step '{}', expected one of: {}",
+                page,
+                PAGES.join(", ")
+            ));
+        }
+        Ok(Self {
+            page: page.to_string(),
+            condition: condition.parse()?,
+        })
+    }
+}
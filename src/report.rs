@@ -0,0 +1,197 @@
+use anyhow::{bail, Context, Result};
+use chrono::{Local, NaiveDate};
+use rust_decimal::prelude::ToPrimitive;
+use std::fmt::Write as _;
+use std::path::Path;
+use tokio::process::Command;
+
+use crate::chart;
+use crate::output::{Labels, Locale};
+use crate::PpData;
+
+// PDF 변환 도구 (HTML -> PDF)
+const WEASYPRINT: &str = "weasyprint";
+
+// 보고서 옵션
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    // 보고 기준 월 (없으면 저장된 가장 최근 달)
+    pub month: Option<NaiveDate>,
+    // chart, 표에 포함할 개월 수
+    pub months: usize,
+    pub labels: Labels,
+    pub locale: Locale,
+}
+
+// 보고서 문구
+struct Text {
+    title: &'static str,
+    latest: &'static str,
+    usage_history: &'static str,
+    cost_history: &'static str,
+    no_data: &'static str,
+    generated: &'static str,
+}
+
+fn text(labels: Labels) -> Text {
+    match labels {
+        Labels::Ko => Text {
+            title: "월간 전기 요금 보고서",
+            latest: "이번 달",
+            usage_history: "사용량 추이",
+            cost_history: "청구금액 추이",
+            no_data: "저장된 청구 data 가 없습니다",
+            generated: "생성",
+        },
+        Labels::En => Text {
+            title: "Monthly electricity report",
+            latest: "This month",
+            usage_history: "Usage history",
+            cost_history: "Cost history",
+            no_data: "No stored claims",
+            generated: "Generated",
+        },
+    }
+}
+
+// 고객 번호 (건물) 별 사용량, 청구금액 chart 와 표 (HTML, chart 는 inline SVG)
+pub fn render_html(customers: &[(String, Vec<PpData>)], options: &ReportOptions) -> String {
+    let labels = options.labels;
+    let text = text(labels);
+    let month = options.month.or_else(|| {
+        customers
+            .iter()
+            .flat_map(|(_, rows)| rows.iter().map(|entry| entry.claim_date))
+            .max()
+    });
+
+    let mut html = String::new();
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n",
+        match labels {
+            Labels::Ko => "ko",
+            Labels::En => "en",
+        },
+        text.title,
+        STYLE
+    );
+    let _ = writeln!(
+        html,
+        "<h1>{}{}</h1>",
+        text.title,
+        month.map_or(String::new(), |month| format!(
+            " <small>{}</small>",
+            month.format("%Y-%m")
+        ))
+    );
+
+    for (customer_number, rows) in customers {
+        let _ = writeln!(
+            html,
+            "<section>\n<h2>{}: {}</h2>",
+            labels.customer_number(),
+            escape(customer_number)
+        );
+        // 기준 월까지 최근 N 개월 (오래된 달부터)
+        let mut rows: Vec<&PpData> = rows
+            .iter()
+            .filter(|entry| month.is_none_or(|month| entry.claim_date <= month))
+            .collect();
+        rows.sort_by_key(|entry| entry.claim_date);
+        let rows = &rows[rows.len().saturating_sub(options.months)..];
+
+        let Some(latest) = rows.last() else {
+            let _ = writeln!(html, "<p>{}</p>\n</section>", text.no_data);
+            continue;
+        };
+        let _ = writeln!(
+            html,
+            "<p class=\"latest\">{} ({}): <b>{} kWh</b>, <b>{}</b></p>",
+            text.latest,
+            latest.claim_date.format("%Y-%m"),
+            options.locale.usage(latest.usage),
+            options.locale.money(latest.paid)
+        );
+
+        let _ = writeln!(html, "<h3>{}</h3>", text.usage_history);
+        html.push_str(&chart::bar_svg(rows, |entry| entry.usage));
+        let _ = writeln!(html, "<h3>{}</h3>", text.cost_history);
+        html.push_str(&chart::line_svg(rows, |entry| {
+            entry.paid.to_f64().unwrap_or_default()
+        }));
+
+        let _ = writeln!(
+            html,
+            "<table>\n<tr><th>{}</th><th>{}</th><th>{}</th></tr>",
+            labels.claim_date(),
+            labels.usage(),
+            labels.paid()
+        );
+        for entry in rows.iter().rev() {
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+                entry.claim_date.format("%Y-%m"),
+                options.locale.usage(entry.usage),
+                options.locale.money(entry.paid)
+            );
+        }
+        html.push_str("</table>\n</section>\n");
+    }
+
+    let _ = write!(
+        html,
+        "<footer>{} {}</footer>\n</body>\n</html>\n",
+        text.generated,
+        Local::now().format("%Y-%m-%d %H:%M")
+    );
+    html
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = "body{font-family:sans-serif;margin:2em;color:#222}\
+h1 small{color:#666;font-weight:normal}\
+section{page-break-inside:avoid;margin-bottom:2em}\
+.latest{font-size:1.1em}\
+.bar{fill:#4e79a7}\
+.line{fill:none;stroke:#e15759;stroke-width:2}\
+.axis{font-size:10px;text-anchor:middle;fill:#666}\
+table{border-collapse:collapse}\
+th,td{border:1px solid #ccc;padding:2px 8px}\
+.num{text-align:right}\
+footer{color:#999;font-size:0.8em}";
+
+// out 확장자가 .pdf 면 weasyprint 로 변환, 아니면 HTML 저장
+pub async fn write(html: &str, out: &Path) -> Result<()> {
+    let is_pdf = out
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("pdf"));
+    if !is_pdf {
+        return std::fs::write(out, html).context(format!("Failed to write {}", out.display()));
+    }
+
+    let source = out.with_extension("html");
+    std::fs::write(&source, html).context(format!("Failed to write {}", source.display()))?;
+    let output = Command::new(WEASYPRINT)
+        .arg(&source)
+        .arg(out)
+        .output()
+        .await
+        .context("Failed to run weasyprint, is it installed?")?;
+    std::fs::remove_file(&source).ok();
+    if !output.status.success() {
+        bail!(
+            "weasyprint exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
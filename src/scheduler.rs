@@ -0,0 +1,474 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::fmt::Write as _;
+use std::sync::Mutex;
+use tokio::sync::{oneshot, Notify};
+use tokio::time::Instant;
+
+use crate::freshness::escape_label;
+
+// job 우선순위 (앞이 먼저)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Priority {
+    // API 로 요청한 조회 (POST /scrape)
+    OnDemand,
+    // pipeline 주기 조회
+    Background,
+}
+
+const PRIORITIES: [Priority; 2] = [Priority::OnDemand, Priority::Background];
+
+impl Priority {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Priority::OnDemand => "on_demand",
+            Priority::Background => "background",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+// pipeline 하나의 tenant, 고객 번호
+#[derive(Debug, Clone)]
+pub struct Assignment {
+    pub pipeline: String,
+    pub tenant: String,
+    pub customer_numbers: Vec<String>,
+}
+
+// job 결과 (실패하면 에러 메시지)
+pub type JobResult = Result<(), String>;
+
+// 고객 번호 1개 조회
+#[derive(Debug)]
+pub struct Job {
+    pub pipeline: String,
+    pub tenant: String,
+    pub customer_number: String,
+    pub priority: Priority,
+    enqueued_at: Instant,
+    // 끝나기를 기다리는 API 요청 (GET /customers/{customer}?refresh=true)
+    waiters: Vec<oneshot::Sender<JobResult>>,
+}
+
+// 우선순위 하나의 대기열 (tenant 를 돌아가며 하나씩)
+#[derive(Default)]
+struct Class {
+    jobs: BTreeMap<String, VecDeque<Job>>,
+    // 대기 중인 job 이 있는 tenant 차례
+    turn: VecDeque<String>,
+}
+
+// tenant, 우선순위별 누적 처리 수, 대기 시간
+#[derive(Default)]
+struct Counters {
+    ok: u64,
+    failed: u64,
+    wait_secs: f64,
+}
+
+#[derive(Default)]
+struct State {
+    classes: [Class; 2],
+    // 조회 중인 tenant (같은 계정 중복 로그인 방지)
+    running: HashSet<String>,
+    counters: BTreeMap<(String, Priority), Counters>,
+}
+
+// GET /queue 응답
+#[derive(Debug, Serialize)]
+pub struct Snapshot {
+    pub running: Vec<String>,
+    pub queued: Vec<QueuedJob>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueuedJob {
+    pub pipeline: String,
+    pub tenant: String,
+    pub customer_number: String,
+    pub priority: Priority,
+    pub waiting_secs: u64,
+}
+
+// serve 의 조회 대기열
+// on-demand 먼저, 같은 우선순위 안에서는 tenant 를 돌아가며 꺼내 고객 번호가 많은 tenant 가 다른 tenant 를 막지 않음
+pub struct Queue {
+    assignments: Vec<Assignment>,
+    state: Mutex<State>,
+    ready: Notify,
+}
+
+impl Queue {
+    pub fn new(assignments: Vec<Assignment>) -> Self {
+        Self {
+            assignments,
+            state: Mutex::new(State::default()),
+            ready: Notify::new(),
+        }
+    }
+
+    // pipeline 의 모든 고객 번호를 background 로 추가, 추가한 job 수 반환
+    pub fn refresh(&self, pipeline: &str) -> usize {
+        self.enqueue(
+            |assignment| assignment.pipeline == pipeline,
+            None,
+            Priority::Background,
+        )
+        .len()
+    }
+
+    // 고객 번호가 속한 모든 pipeline 에 on-demand 로 추가, pipeline 마다 job 결과를 받을 receiver (없으면 빈 목록)
+    pub fn request(&self, customer_number: &str) -> Vec<oneshot::Receiver<JobResult>> {
+        self.enqueue(
+            |assignment| {
+                assignment
+                    .customer_numbers
+                    .iter()
+                    .any(|number| number == customer_number)
+            },
+            Some(customer_number),
+            Priority::OnDemand,
+        )
+    }
+
+    // 같은 pipeline, 고객 번호가 이미 대기 중이면 그 job 의 결과를 기다림 (on-demand 요청이면 background 에서 옮김)
+    fn enqueue(
+        &self,
+        matches: impl Fn(&Assignment) -> bool,
+        customer_number: Option<&str>,
+        priority: Priority,
+    ) -> Vec<oneshot::Receiver<JobResult>> {
+        let mut state = self.state.lock().expect("queue lock");
+        let mut receivers = Vec::new();
+        let mut added = false;
+        for assignment in self
+            .assignments
+            .iter()
+            .filter(|assignment| matches(assignment))
+        {
+            let numbers = assignment
+                .customer_numbers
+                .iter()
+                .filter(|number| customer_number.is_none_or(|customer| customer == *number));
+            for number in numbers {
+                let same = |job: &Job| {
+                    job.pipeline == assignment.pipeline && job.customer_number == *number
+                };
+                let (sender, receiver) = oneshot::channel();
+                receivers.push(receiver);
+                if let Some(job) = state.find(&assignment.tenant, priority, same) {
+                    job.waiters.push(sender);
+                    continue;
+                }
+                let mut waiters = vec![sender];
+                if priority == Priority::OnDemand {
+                    for job in state.remove(&assignment.tenant, Priority::Background, same) {
+                        waiters.extend(job.waiters);
+                    }
+                }
+                state.push(Job {
+                    pipeline: assignment.pipeline.clone(),
+                    tenant: assignment.tenant.clone(),
+                    customer_number: number.clone(),
+                    priority,
+                    enqueued_at: Instant::now(),
+                    waiters,
+                });
+                added = true;
+            }
+        }
+        drop(state);
+        if added {
+            self.ready.notify_one();
+        }
+        receivers
+    }
+
+    // 다음 job 이 나올 때까지 대기 (조회 중인 tenant 의 job 은 끝날 때까지 남겨 둠)
+    pub async fn next(&self) -> Job {
+        loop {
+            let notified = self.ready.notified();
+            if let Some(job) = self.take() {
+                return job;
+            }
+            notified.await;
+        }
+    }
+
+    fn take(&self) -> Option<Job> {
+        let mut state = self.state.lock().expect("queue lock");
+        let State {
+            classes,
+            running,
+            counters,
+        } = &mut *state;
+        for priority in PRIORITIES {
+            let class = &mut classes[priority.index()];
+            let Some(position) = class
+                .turn
+                .iter()
+                .position(|tenant| !running.contains(tenant))
+            else {
+                continue;
+            };
+            let tenant = class.turn.remove(position).expect("tenant in turn");
+            let jobs = class.jobs.get_mut(&tenant).expect("tenant jobs");
+            let job = jobs.pop_front().expect("queued job");
+            // 남은 job 이 있으면 차례 맨 뒤로
+            if jobs.is_empty() {
+                class.jobs.remove(&tenant);
+            } else {
+                class.turn.push_back(tenant.clone());
+            }
+            running.insert(tenant.clone());
+            counters.entry((tenant, priority)).or_default().wait_secs +=
+                job.enqueued_at.elapsed().as_secs_f64();
+            // 다른 worker 가 꺼낼 job 이 남아 있을 수 있음
+            self.ready.notify_one();
+            return Some(job);
+        }
+        None
+    }
+
+    // job 종료 (기다리는 요청에 결과 전달, tenant 의 다음 job 을 꺼낼 수 있게 됨)
+    pub fn finish(&self, job: Job, result: JobResult) {
+        let mut state = self.state.lock().expect("queue lock");
+        state.running.remove(&job.tenant);
+        let counters = state
+            .counters
+            .entry((job.tenant, job.priority))
+            .or_default();
+        if result.is_ok() {
+            counters.ok += 1;
+        } else {
+            counters.failed += 1;
+        }
+        drop(state);
+        for waiter in job.waiters {
+            // 요청이 먼저 끊겼으면 무시
+            let _ = waiter.send(result.clone());
+        }
+        self.ready.notify_one();
+    }
+
+    pub fn snapshot(&self) -> Snapshot {
+        let state = self.state.lock().expect("queue lock");
+        let mut running: Vec<String> = state.running.iter().cloned().collect();
+        running.sort();
+        let queued = PRIORITIES
+            .iter()
+            .flat_map(|priority| state.classes[priority.index()].jobs.values().flatten())
+            .map(|job| QueuedJob {
+                pipeline: job.pipeline.clone(),
+                tenant: job.tenant.clone(),
+                customer_number: job.customer_number.clone(),
+                priority: job.priority,
+                waiting_secs: job.enqueued_at.elapsed().as_secs(),
+            })
+            .collect();
+        Snapshot { running, queued }
+    }
+
+    // Prometheus text exposition (GET /metrics 에 추가)
+    pub fn metrics(&self) -> String {
+        let state = self.state.lock().expect("queue lock");
+        let mut text = String::new();
+        header(
+            &mut text,
+            "queue_jobs",
+            "gauge",
+            "Scrape jobs waiting in the queue.",
+        );
+        for priority in PRIORITIES {
+            for (tenant, jobs) in &state.classes[priority.index()].jobs {
+                let _ = writeln!(
+                    text,
+                    "rip_hyphen_queue_jobs{{{}}} {}",
+                    labels(tenant, Some(priority)),
+                    jobs.len()
+                );
+            }
+        }
+
+        header(
+            &mut text,
+            "running_jobs",
+            "gauge",
+            "Scrape jobs currently running.",
+        );
+        let mut running: Vec<&String> = state.running.iter().collect();
+        running.sort();
+        for tenant in running {
+            let _ = writeln!(
+                text,
+                "rip_hyphen_running_jobs{{{}}} 1",
+                labels(tenant, None)
+            );
+        }
+
+        header(
+            &mut text,
+            "jobs_total",
+            "counter",
+            "Finished scrape jobs by result.",
+        );
+        for ((tenant, priority), counters) in &state.counters {
+            for (status, count) in [("ok", counters.ok), ("failed", counters.failed)] {
+                let _ = writeln!(
+                    text,
+                    "rip_hyphen_jobs_total{{{},status=\"{}\"}} {}",
+                    labels(tenant, Some(*priority)),
+                    status,
+                    count
+                );
+            }
+        }
+
+        header(
+            &mut text,
+            "queue_wait_seconds_total",
+            "counter",
+            "Total time started jobs spent waiting in the queue.",
+        );
+        for ((tenant, priority), counters) in &state.counters {
+            let _ = writeln!(
+                text,
+                "rip_hyphen_queue_wait_seconds_total{{{}}} {}",
+                labels(tenant, Some(*priority)),
+                counters.wait_secs
+            );
+        }
+        text
+    }
+}
+
+impl State {
+    fn push(&mut self, job: Job) {
+        let class = &mut self.classes[job.priority.index()];
+        let jobs = class.jobs.entry(job.tenant.clone()).or_default();
+        if jobs.is_empty() {
+            class.turn.push_back(job.tenant.clone());
+        }
+        jobs.push_back(job);
+    }
+
+    fn find(
+        &mut self,
+        tenant: &str,
+        priority: Priority,
+        same: impl Fn(&Job) -> bool,
+    ) -> Option<&mut Job> {
+        self.classes[priority.index()]
+            .jobs
+            .get_mut(tenant)?
+            .iter_mut()
+            .find(|job| same(job))
+    }
+
+    // 대기 중인 job 제거 후 반환
+    fn remove(
+        &mut self,
+        tenant: &str,
+        priority: Priority,
+        same: impl Fn(&Job) -> bool,
+    ) -> Vec<Job> {
+        let class = &mut self.classes[priority.index()];
+        let Some(jobs) = class.jobs.get_mut(tenant) else {
+            return Vec::new();
+        };
+        let (removed, kept): (VecDeque<Job>, VecDeque<Job>) =
+            std::mem::take(jobs).into_iter().partition(|job| same(job));
+        *jobs = kept;
+        if jobs.is_empty() {
+            class.jobs.remove(tenant);
+            class.turn.retain(|queued| queued != tenant);
+        }
+        removed.into()
+    }
+}
+
+fn header(text: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(text, "# HELP rip_hyphen_{} {}", name, help);
+    let _ = writeln!(text, "# TYPE rip_hyphen_{} {}", name, kind);
+}
+
+fn labels(tenant: &str, priority: Option<Priority>) -> String {
+    match priority {
+        Some(priority) => format!(
+            "tenant=\"{}\",priority=\"{}\"",
+            escape_label(tenant),
+            priority.as_str()
+        ),
+        None => format!("tenant=\"{}\"", escape_label(tenant)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assignment(pipeline: &str, tenant: &str, count: usize) -> Assignment {
+        Assignment {
+            pipeline: pipeline.to_string(),
+            tenant: tenant.to_string(),
+            customer_numbers: (0..count).map(|i| format!("{}-{:03}", tenant, i)).collect(),
+        }
+    }
+
+    // 꺼낸 job 을 바로 끝내며 tenant 순서 기록
+    async fn drain(queue: &Queue, count: usize) -> Vec<String> {
+        let mut tenants = Vec::new();
+        for _ in 0..count {
+            let job = queue.next().await;
+            tenants.push(job.tenant.clone());
+            queue.finish(job, Ok(()));
+        }
+        tenants
+    }
+
+    #[tokio::test]
+    async fn interleaves_tenants() {
+        let queue = Queue::new(vec![
+            assignment("large", "a", 200),
+            assignment("small", "b", 1),
+        ]);
+        assert_eq!(queue.refresh("large"), 200);
+        assert_eq!(queue.refresh("small"), 1);
+        // 200건 뒤가 아니라 a 의 첫 job 다음에 b
+        assert_eq!(drain(&queue, 3).await, ["a", "b", "a"]);
+        assert_eq!(queue.snapshot().queued.len(), 198);
+    }
+
+    #[tokio::test]
+    async fn on_demand_jumps_the_queue() {
+        let queue = Queue::new(vec![assignment("pipeline", "a", 5)]);
+        queue.refresh("pipeline");
+        let receivers = queue.request("a-003");
+        assert_eq!(receivers.len(), 1);
+        let job = queue.next().await;
+        assert_eq!(job.customer_number, "a-003");
+        assert_eq!(job.priority, Priority::OnDemand);
+        // background 에 있던 같은 고객 번호는 옮겨져 중복 조회하지 않음
+        let queued = queue.snapshot().queued;
+        assert_eq!(queued.len(), 4);
+        assert!(queued.iter().all(|job| job.customer_number != "a-003"));
+    }
+
+    #[tokio::test]
+    async fn duplicate_request_joins_waiter() {
+        let queue = Queue::new(vec![assignment("pipeline", "a", 2)]);
+        let first = queue.request("a-001");
+        let second = queue.request("a-001");
+        assert_eq!(queue.snapshot().queued.len(), 1);
+        let job = queue.next().await;
+        queue.finish(job, Err("login failed".to_string()));
+        for receiver in first.into_iter().chain(second) {
+            assert_eq!(receiver.await.unwrap(), Err("login failed".to_string()));
+        }
+    }
+}
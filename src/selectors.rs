@@ -0,0 +1,484 @@
+use anyhow::Result;
+use fantoccini::{Client, Locator};
+use serde::{Deserialize, Deserializer};
+use std::borrow::Cow;
+use std::str::FromStr;
+use tokio::time::{Duration, Instant};
+
+use crate::error::{ErrorCode, Failure};
+use crate::query::QueryControl;
+use crate::warnings::{self, WarningKind};
+
+// portal 이 기준 layout 으로 그려지는 window 크기
+pub const DEFAULT_WINDOW: WindowSize = WindowSize {
+    width: 774,
+    height: 857,
+};
+
+// browser window 크기 (--window-size 774x857)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowSize {
+    pub width: u32,
+    pub height: u32,
+}
+
+impl FromStr for WindowSize {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (width, height) = s
+            .split_once('x')
+            .ok_or_else(|| format!("Invalid window size, expected <width>x<height>: {}", s))?;
+        let parse = |value: &str| {
+            value
+                .trim()
+                .parse::<u32>()
+                .map_err(|_| format!("Invalid window size: {}", s))
+        };
+        Ok(Self {
+            width: parse(width)?,
+            height: parse(height)?,
+        })
+    }
+}
+
+// selector 한 단계 (xpath:..., css:..., text:표시 문자열)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectorSpec {
+    XPath(Cow<'static, str>),
+    Css(Cow<'static, str>),
+    // 표시 문자열을 포함하는 가장 안쪽 요소 (실행 시 XPath 로 변환)
+    Text(Cow<'static, str>),
+}
+
+impl FromStr for SelectorSpec {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let spec = match s.split_once(':') {
+            Some(("xpath", xpath)) => SelectorSpec::XPath(xpath.trim().to_string().into()),
+            Some(("css", css)) => SelectorSpec::Css(css.trim().to_string().into()),
+            Some(("text", label)) => SelectorSpec::Text(label.trim().to_string().into()),
+            _ if s.starts_with('/') || s.starts_with('(') => {
+                SelectorSpec::XPath(s.to_string().into())
+            }
+            _ => {
+                return Err(format!(
+                    "Invalid selector, expected xpath:<expr>, css:<expr> or text:<label>: {}",
+                    s
+                ))
+            }
+        };
+        match &spec {
+            SelectorSpec::XPath(value) | SelectorSpec::Css(value) | SelectorSpec::Text(value)
+                if value.is_empty() =>
+            {
+                Err(format!("Empty selector: {}", s))
+            }
+            _ => Ok(spec),
+        }
+    }
+}
+
+// 설정 파일의 selector 문자열 (xpath:..., css:..., text:...)
+impl<'de> Deserialize<'de> for SelectorSpec {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+// XPath 문자열 literal (따옴표 포함 문자열은 concat 으로 조합)
+fn xpath_literal(value: &str) -> String {
+    if !value.contains('\'') {
+        format!("'{}'", value)
+    } else if !value.contains('"') {
+        format!("\"{}\"", value)
+    } else {
+        let parts: Vec<String> = value
+            .split('\'')
+            .map(|part| format!("'{}'", part))
+            .collect();
+        format!("concat({})", parts.join(", \"'\", "))
+    }
+}
+
+// 앞에서부터 시도하는 selector 목록 (처음 요소를 찾은 selector 사용)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Selector(pub Vec<SelectorSpec>);
+
+impl Selector {
+    // 현재 페이지에서 요소를 찾는 첫 selector 반환
+    pub async fn resolve(&self, client: &Client) -> Result<Resolved> {
+        for spec in &self.0 {
+            let resolved = Resolved::from(spec);
+            if !client
+                .find_all(resolved.locator())
+                .await
+                .unwrap_or_default()
+                .is_empty()
+            {
+                return Ok(resolved);
+            }
+        }
+        warnings::emit(
+            WarningKind::MissingElement,
+            format!("Failed to find the element: {:?}", self),
+        );
+        Err(Failure::new(
+            ErrorCode::ElementNotFound,
+            "No selector in the chain matched",
+        )
+        .with_selector(self)
+        .into())
+    }
+
+    // 요소가 나타날 때까지 resolve 재시도
+    pub async fn wait(
+        &self,
+        client: &Client,
+        duration: Duration,
+        poll: Duration,
+    ) -> Result<Resolved> {
+        let deadline = Instant::now() + duration;
+        loop {
+            if self.exists(client).await || Instant::now() >= deadline {
+                return self.resolve(client).await;
+            }
+            tokio::time::sleep(poll).await;
+        }
+    }
+
+    // 요소를 찾는 첫 selector 의 chain 내 위치 (0 이 아니면 fallback 으로 찾음)
+    pub async fn matched(&self, client: &Client) -> Option<usize> {
+        for (index, spec) in self.0.iter().enumerate() {
+            if client.find(Resolved::from(spec).locator()).await.is_ok() {
+                return Some(index);
+            }
+        }
+        None
+    }
+
+    // 요소 존재 여부 (대기 없음)
+    pub async fn exists(&self, client: &Client) -> bool {
+        for spec in &self.0 {
+            let resolved = Resolved::from(spec);
+            if client.find(resolved.locator()).await.is_ok() {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+// resolve 된 selector (text 는 변환된 XPath 보관)
+#[derive(Debug, Clone)]
+pub enum Resolved {
+    XPath(String),
+    Css(String),
+}
+
+impl Resolved {
+    pub fn locator(&self) -> Locator<'_> {
+        match self {
+            Resolved::XPath(xpath) => Locator::XPath(xpath),
+            Resolved::Css(css) => Locator::Css(css),
+        }
+    }
+}
+
+impl From<&SelectorSpec> for Resolved {
+    fn from(spec: &SelectorSpec) -> Self {
+        match spec {
+            SelectorSpec::XPath(xpath) => Resolved::XPath(xpath.to_string()),
+            SelectorSpec::Css(css) => Resolved::Css(css.to_string()),
+            SelectorSpec::Text(label) => Resolved::XPath(format!(
+                "//*[contains(normalize-space(.), {0}) and not(*[contains(normalize-space(.), {0})])]",
+                xpath_literal(label)
+            )),
+        }
+    }
+}
+
+// selector key 별 fallback chain 지정 (--selector monthly_claims=text:월별 청구요금||xpath://a[2])
+#[derive(Debug, Clone)]
+pub struct SelectorOverride {
+    pub key: String,
+    pub chain: Vec<SelectorSpec>,
+}
+
+impl FromStr for SelectorOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (key, chain) = s.split_once('=').ok_or_else(|| {
+            format!(
+                "Invalid selector override, expected <key>=<selector>: {}",
+                s
+            )
+        })?;
+        let key = key.trim();
+        if !KEYS.contains(&key) {
+            return Err(format!(
+                "Unknown selector key '{}', expected one of: {}",
+                key,
+                KEYS.join(", ")
+            ));
+        }
+        let chain = chain
+            .split("||")
+            .map(str::parse)
+            .collect::<Result<Vec<SelectorSpec>, _>>()?;
+        Ok(Self {
+            key: key.to_string(),
+            chain,
+        })
+    }
+}
+
+// 설정 가능한 selector key
+pub const KEYS: [&str; 12] = [
+    "customer_toggle",
+    "customer_options",
+    "monthly_claims",
+    "customer_info",
+    "year_submit",
+    "bill_usage",
+    "bill_amount",
+    "daily_usage",
+    "daily_submit",
+    "hourly_usage",
+    "hourly_date",
+    "hourly_submit",
+];
+
+// layout 별 selector 묶음
+#[derive(Debug, Clone)]
+pub struct SelectorProfile {
+    pub name: &'static str,
+    // 고객 번호 목록 열기 (layout 판별에도 사용)
+    pub customer_toggle: Selector,
+    // 고객 번호 목록 항목
+    pub customer_options: Selector,
+    // 월별 청구 요금 메뉴
+    pub monthly_claims: Selector,
+    // 고객 정보 조회 메뉴 (--customer-info)
+    pub customer_info: Selector,
+    // 연도 조회 버튼
+    pub year_submit: Selector,
+    // 청구서 상세의 사용량, 청구 금액 값
+    pub bill_usage: Selector,
+    pub bill_amount: Selector,
+    // 일별 사용량 메뉴, 조회 버튼
+    pub daily_usage: Selector,
+    pub daily_submit: Selector,
+    // 시간대별 사용량 메뉴, 조회 일자 입력, 조회 버튼
+    pub hourly_usage: Selector,
+    pub hourly_date: Selector,
+    pub hourly_submit: Selector,
+}
+
+impl SelectorProfile {
+    // 월별 청구 요금 연도 조회
+    pub async fn year_query(&self, client: &Client) -> Result<QueryControl> {
+        let submit = self.year_submit.resolve(client).await?;
+        Ok(QueryControl {
+            submit,
+            page: "year",
+        })
+    }
+
+    // 일별 사용량 연도, 월 조회
+    pub async fn daily_query(&self, client: &Client) -> Result<QueryControl> {
+        let submit = self.daily_submit.resolve(client).await?;
+        Ok(QueryControl {
+            submit,
+            page: "daily",
+        })
+    }
+
+    // 시간대별 사용량 일자 조회
+    pub async fn hourly_query(&self, client: &Client) -> Result<QueryControl> {
+        let submit = self.hourly_submit.resolve(client).await?;
+        Ok(QueryControl {
+            submit,
+            page: "hourly",
+        })
+    }
+
+    // key 의 selector (canary 확인용)
+    pub fn get(&self, key: &str) -> Option<&Selector> {
+        match key {
+            "customer_toggle" => Some(&self.customer_toggle),
+            "customer_options" => Some(&self.customer_options),
+            "monthly_claims" => Some(&self.monthly_claims),
+            "customer_info" => Some(&self.customer_info),
+            "year_submit" => Some(&self.year_submit),
+            "bill_usage" => Some(&self.bill_usage),
+            "bill_amount" => Some(&self.bill_amount),
+            "daily_usage" => Some(&self.daily_usage),
+            "daily_submit" => Some(&self.daily_submit),
+            "hourly_usage" => Some(&self.hourly_usage),
+            "hourly_date" => Some(&self.hourly_date),
+            "hourly_submit" => Some(&self.hourly_submit),
+            _ => None,
+        }
+    }
+
+    // 지정된 key 의 chain 을 앞에 두고 profile 기본값은 마지막 fallback 으로 유지
+    fn apply(mut self, overrides: &[SelectorOverride]) -> Self {
+        for item in overrides.iter().rev() {
+            let selector = match item.key.as_str() {
+                "customer_toggle" => &mut self.customer_toggle,
+                "customer_options" => &mut self.customer_options,
+                "monthly_claims" => &mut self.monthly_claims,
+                "customer_info" => &mut self.customer_info,
+                "year_submit" => &mut self.year_submit,
+                "bill_usage" => &mut self.bill_usage,
+                "bill_amount" => &mut self.bill_amount,
+                "daily_usage" => &mut self.daily_usage,
+                "daily_submit" => &mut self.daily_submit,
+                "hourly_usage" => &mut self.hourly_usage,
+                "hourly_date" => &mut self.hourly_date,
+                "hourly_submit" => &mut self.hourly_submit,
+                _ => continue,
+            };
+            selector.0.splice(0..0, item.chain.iter().cloned());
+        }
+        self
+    }
+}
+
+fn xpath(value: &'static str) -> SelectorSpec {
+    SelectorSpec::XPath(Cow::Borrowed(value))
+}
+
+fn text(value: &'static str) -> SelectorSpec {
+    SelectorSpec::Text(Cow::Borrowed(value))
+}
+
+// 기준 window 크기의 layout (절대 XPath, 표시 문자열 fallback)
+fn compact() -> SelectorProfile {
+    SelectorProfile {
+        name: "compact",
+        customer_toggle: Selector(vec![xpath("/html/body/div[1]/div[1]/div/div/a[2]")]),
+        customer_options: Selector(vec![xpath("/html/body/div[1]/div[1]/div/div/ul/li/a")]),
+        monthly_claims: Selector(vec![
+            xpath("/html/body/div[1]/div[2]/div[1]/ul[4]/li[5]/a"),
+            text("월별 청구요금"),
+        ]),
+        customer_info: customer_info(),
+        year_submit: Selector(vec![xpath("//*[@id='txt']/div[2]/p/span[1]/a")]),
+        bill_usage: bill_usage(),
+        bill_amount: bill_amount(),
+        daily_usage: daily_usage(),
+        daily_submit: usage_submit(),
+        hourly_usage: hourly_usage(),
+        hourly_date: hourly_date(),
+        hourly_submit: usage_submit(),
+    }
+}
+
+// 다른 window 크기에서 요소 위치가 바뀐 layout (구조, 표시 문자열 기준)
+fn relative() -> SelectorProfile {
+    SelectorProfile {
+        name: "relative",
+        customer_toggle: Selector(vec![xpath("//div[a[2] and ul/li/a]/a[2]")]),
+        customer_options: Selector(vec![xpath("//div[a[2] and ul/li/a]/ul/li/a")]),
+        monthly_claims: Selector(vec![
+            xpath("//a[contains(normalize-space(.), '월별 청구요금')]"),
+            text("월별 청구요금"),
+        ]),
+        customer_info: customer_info(),
+        year_submit: Selector(vec![xpath(
+            "//*[@id='txt']//a[contains(normalize-space(.), '조회')]",
+        )]),
+        bill_usage: bill_usage(),
+        bill_amount: bill_amount(),
+        daily_usage: daily_usage(),
+        daily_submit: usage_submit(),
+        hourly_usage: hourly_usage(),
+        hourly_date: hourly_date(),
+        hourly_submit: usage_submit(),
+    }
+}
+
+// 고객 정보 조회 메뉴는 layout 과 무관하게 표시 문자열 기준
+fn customer_info() -> Selector {
+    Selector(vec![
+        xpath("//a[contains(normalize-space(.), '고객정보조회')]"),
+        text("고객정보"),
+    ])
+}
+
+// 일별, 시간대별 사용량 메뉴, 조회 버튼은 layout 과 무관하게 표시 문자열 기준
+fn daily_usage() -> Selector {
+    Selector(vec![
+        xpath("//a[contains(normalize-space(.), '일별 사용량')]"),
+        text("일별"),
+    ])
+}
+
+fn hourly_usage() -> Selector {
+    Selector(vec![
+        xpath("//a[contains(normalize-space(.), '시간대별 사용량')]"),
+        text("시간대별"),
+    ])
+}
+
+// 조회 영역의 날짜 입력 (date picker)
+fn hourly_date() -> Selector {
+    Selector(vec![
+        xpath("//*[@id='txt']//input[@type='date']"),
+        xpath("//*[@id='txt']//input[contains(@class, 'date')]"),
+        xpath("//*[@id='txt']//input[@type='text']"),
+    ])
+}
+
+// 일별, 시간대별 사용량 조회 버튼
+fn usage_submit() -> Selector {
+    Selector(vec![xpath(
+        "//*[@id='txt']//a[contains(normalize-space(.), '조회')]",
+    )])
+}
+
+// 청구서 상세는 layout 과 무관하게 항목 이름 옆 값
+fn bill_usage() -> Selector {
+    Selector(vec![
+        xpath("//th[contains(normalize-space(.), '사용량')]/following-sibling::td[1]"),
+        xpath("//dt[contains(normalize-space(.), '사용량')]/following-sibling::dd[1]"),
+    ])
+}
+
+fn bill_amount() -> Selector {
+    Selector(vec![
+        xpath("//th[contains(normalize-space(.), '청구금액')]/following-sibling::td[1]"),
+        xpath("//th[contains(normalize-space(.), '청구요금')]/following-sibling::td[1]"),
+        xpath("//dt[contains(normalize-space(.), '청구금액')]/following-sibling::dd[1]"),
+    ])
+}
+
+// 로그인 후 그려진 layout 판별, 맞는 selector profile 에 지정된 selector 적용
+pub async fn detect(
+    client: &Client,
+    window: WindowSize,
+    overrides: &[SelectorOverride],
+) -> SelectorProfile {
+    // 판별 순서
+    for profile in [compact(), relative()] {
+        if profile.customer_toggle.exists(client).await {
+            return profile.apply(overrides);
+        }
+    }
+
+    warnings::emit(
+        WarningKind::FallbackSelector,
+        format!(
+            "Unknown layout at {}x{} (expected {}x{}), falling back to 'compact' selectors",
+            window.width, window.height, DEFAULT_WINDOW.width, DEFAULT_WINDOW.height
+        ),
+    );
+    compact().apply(overrides)
+}
@@ -0,0 +1,244 @@
+use anyhow::{Context, Result};
+use axum::extract::{Path, Query, State};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use chrono::Utc;
+use futures::future::join_all;
+use serde::Deserialize;
+use serde_json::json;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+use crate::customer;
+use crate::error::{self, ErrorCode, Failure};
+use crate::freshness::{self, Thresholds};
+use crate::output::MoneyFormat;
+use crate::scheduler::{JobResult, Queue};
+use crate::store::Store;
+use crate::summary;
+
+// ?refresh=true 가 조회 결과를 기다리는 최대 시간 (넘으면 저장된 data 로 응답)
+const REFRESH_TIMEOUT: Duration = Duration::from_secs(600);
+
+// 요청마다 저장소를 열어 조회 (실행 중인 scrape 의 쓰기와 독립)
+struct ServerState {
+    store: PathBuf,
+    money_format: MoneyFormat,
+    thresholds: Thresholds,
+    // --pipelines 의 조회 대기열
+    queue: Option<Arc<Queue>>,
+}
+
+// 저장소 기반 HTTP API (--pipelines 가 있으면 조회 요청, 대기열 확인)
+pub async fn serve(
+    listen: SocketAddr,
+    store: PathBuf,
+    money_format: MoneyFormat,
+    thresholds: Thresholds,
+    queue: Option<Arc<Queue>>,
+) -> Result<()> {
+    let mut app = Router::new()
+        .route("/summary", get(get_summary))
+        .route("/customers/:customer_number", get(get_customer))
+        .route("/metrics", get(get_metrics));
+    if queue.is_some() {
+        app = app
+            .route("/scrape/:customer_number", post(post_scrape))
+            .route("/queue", get(get_queue));
+    }
+    let app = app.with_state(Arc::new(ServerState {
+        store,
+        money_format,
+        thresholds,
+        queue,
+    }));
+
+    let listener = tokio::net::TcpListener::bind(listen)
+        .await
+        .context(Failure::new(
+            ErrorCode::Config,
+            format!("Failed to listen on {}", listen),
+        ))?;
+    println!("Listening on http://{}", listen);
+    axum::serve(listener, app).await.context("Server failed")
+}
+
+// GET /summary: 고객 번호별 최근 달, 올해 누적, 전년 대비
+async fn get_summary(State(state): State<Arc<ServerState>>) -> Response {
+    let result = tokio::task::spawn_blocking(move || {
+        let store = Store::open(&state.store)?;
+        summary::compute(&store, state.money_format, state.thresholds)
+    })
+    .await
+    .context("Summary task failed")
+    .and_then(|result| result);
+
+    match result {
+        Ok(summary) => Json(summary).into_response(),
+        Err(e) => {
+            eprintln!("Failed to build summary: {:#}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error::to_json(&e))).into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CustomerQuery {
+    // 응답 전에 on-demand 조회 (--pipelines 필요)
+    #[serde(default)]
+    refresh: bool,
+}
+
+// GET /customers/{customer_number}: 고객 번호 1개 요약
+// ?refresh=true 면 먼저 조회하고, 조회가 실패해도 (포털 장애 등) 500 대신 저장된 data 를 stale 로 표시해 응답
+async fn get_customer(
+    State(state): State<Arc<ServerState>>,
+    Path(customer_number): Path<String>,
+    Query(query): Query<CustomerQuery>,
+) -> Response {
+    let customer_number = customer::normalize(&customer_number);
+    let refresh_error = if query.refresh {
+        let Some(queue) = &state.queue else {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(json!({ "error": "refresh requires serve --pipelines" })),
+            )
+                .into_response();
+        };
+        let receivers = queue.request(&customer_number);
+        if receivers.is_empty() {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(
+                    json!({ "error": format!("No pipeline scrapes customer {}", customer_number) }),
+                ),
+            )
+                .into_response();
+        }
+        refresh(receivers).await
+    } else {
+        None
+    };
+
+    let number = customer_number.clone();
+    let error = refresh_error.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let store = Store::open(&state.store)?;
+        summary::customer(
+            &store,
+            &number,
+            state.money_format,
+            state.thresholds,
+            error.as_deref(),
+        )
+    })
+    .await
+    .context("Customer task failed")
+    .and_then(|result| result);
+
+    // 조회가 실패했는데 저장된 청구 data 도 없으면 보여줄 것이 없음
+    let cached = |meter: &Option<serde_json::Value>| {
+        meter
+            .as_ref()
+            .is_some_and(|meter| !meter["current_month"].is_null())
+    };
+    match (result, refresh_error) {
+        (Ok(meter), Some(error)) if !cached(&meter) => (
+            StatusCode::BAD_GATEWAY,
+            Json(json!({ "error": format!("Refresh failed and no data is cached: {}", error) })),
+        )
+            .into_response(),
+        (Ok(Some(meter)), _) => Json(meter).into_response(),
+        (Ok(None), _) => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": format!("No data for customer {}", customer_number) })),
+        )
+            .into_response(),
+        (Err(e), _) => {
+            eprintln!("Failed to load customer {}: {:#}", customer_number, e);
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(error::to_json(&e))).into_response()
+        }
+    }
+}
+
+// on-demand job 결과를 기다림, 하나라도 실패하거나 시간을 넘기면 에러 메시지
+async fn refresh(receivers: Vec<oneshot::Receiver<JobResult>>) -> Option<String> {
+    let results = match tokio::time::timeout(REFRESH_TIMEOUT, join_all(receivers)).await {
+        Ok(results) => results,
+        Err(_) => {
+            return Some(format!(
+                "Refresh did not finish within {}s",
+                REFRESH_TIMEOUT.as_secs()
+            ))
+        }
+    };
+    results.into_iter().find_map(|result| match result {
+        Ok(Ok(())) => None,
+        Ok(Err(e)) => Some(e),
+        Err(_) => Some("Refresh job was dropped".to_string()),
+    })
+}
+
+// GET /metrics: 고객 번호별 data 최신 여부 (Prometheus text 형식)
+// 조회 대기열이 있으면 tenant 별 대기, 실행 중, 처리한 job 수도 포함
+async fn get_metrics(State(state): State<Arc<ServerState>>) -> Response {
+    let queue = state.queue.clone();
+    let result = tokio::task::spawn_blocking(move || {
+        let store = Store::open(&state.store)?;
+        freshness::compute(&store, state.thresholds, Utc::now())
+    })
+    .await
+    .context("Metrics task failed")
+    .and_then(|result| result);
+
+    match result {
+        Ok(entries) => {
+            let mut text = freshness::metrics(&entries);
+            if let Some(queue) = queue {
+                text.push_str(&queue.metrics());
+            }
+            ([(CONTENT_TYPE, "text/plain; version=0.0.4")], text).into_response()
+        }
+        Err(e) => {
+            eprintln!("Failed to build metrics: {:#}", e);
+            (StatusCode::INTERNAL_SERVER_ERROR, format!("{:#}\n", e)).into_response()
+        }
+    }
+}
+
+// POST /scrape/{customer_number}: 고객 번호가 속한 pipeline 마다 on-demand job 추가 (주기 조회보다 먼저)
+async fn post_scrape(
+    State(state): State<Arc<ServerState>>,
+    Path(customer_number): Path<String>,
+) -> Response {
+    let Some(queue) = &state.queue else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let customer_number = customer::normalize(&customer_number);
+    match queue.request(&customer_number).len() {
+        0 => (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": format!("No pipeline scrapes customer {}", customer_number) })),
+        )
+            .into_response(),
+        queued => (
+            StatusCode::ACCEPTED,
+            Json(json!({ "customer_number": customer_number, "queued": queued })),
+        )
+            .into_response(),
+    }
+}
+
+// GET /queue: 실행 중인 tenant, 대기 중인 job (우선순위 순)
+async fn get_queue(State(state): State<Arc<ServerState>>) -> Response {
+    match &state.queue {
+        Some(queue) => Json(queue.snapshot()).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
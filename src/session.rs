@@ -0,0 +1,93 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use fantoccini::{cookies::Cookie, Client};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+// 다른 host 로 옮길 수 있는 cookie (session export/import)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredCookie {
+    pub name: String,
+    pub value: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(default)]
+    pub http_only: bool,
+    // 만료 시각 (unix seconds, 없으면 session cookie)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<i64>,
+}
+
+impl StoredCookie {
+    fn from_cookie(cookie: &Cookie<'_>) -> Self {
+        Self {
+            name: cookie.name().to_string(),
+            value: cookie.value().to_string(),
+            domain: cookie.domain().map(str::to_string),
+            path: cookie.path().map(str::to_string),
+            secure: cookie.secure().unwrap_or_default(),
+            http_only: cookie.http_only().unwrap_or_default(),
+            expires: cookie
+                .expires()
+                .and_then(|expires| expires.datetime())
+                .map(|datetime| datetime.unix_timestamp()),
+        }
+    }
+
+    // browser 에는 만료 시각 없이 추가 (이번 실행 동안만 사용)
+    fn to_cookie(&self) -> Cookie<'static> {
+        let mut cookie = Cookie::new(self.name.clone(), self.value.clone());
+        if let Some(domain) = &self.domain {
+            cookie.set_domain(domain.clone());
+        }
+        if let Some(path) = &self.path {
+            cookie.set_path(path.clone());
+        }
+        cookie.set_secure(self.secure);
+        cookie.set_http_only(self.http_only);
+        cookie
+    }
+
+    fn is_expired(&self) -> bool {
+        self.expires
+            .is_some_and(|expires| expires <= Utc::now().timestamp())
+    }
+}
+
+// 현재 browser cookie
+pub async fn export(client: &Client) -> Result<Vec<StoredCookie>> {
+    let cookies = client
+        .get_all_cookies()
+        .await
+        .context("Failed to read cookies")?;
+    Ok(cookies.iter().map(StoredCookie::from_cookie).collect())
+}
+
+pub fn read(path: &Path) -> Result<Vec<StoredCookie>> {
+    let text =
+        fs::read_to_string(path).context(format!("Failed to read cookies: {}", path.display()))?;
+    serde_json::from_str(&text).context(format!("Invalid cookie file: {}", path.display()))
+}
+
+pub fn write(path: &Path, cookies: &[StoredCookie]) -> Result<()> {
+    fs::write(path, serde_json::to_vec_pretty(cookies)?)
+        .context(format!("Failed to write cookies: {}", path.display()))
+}
+
+// 만료되지 않은 cookie 를 현재 domain 의 browser 에 추가, 추가한 수 반환
+pub async fn restore(client: &Client, cookies: &[StoredCookie]) -> Result<usize> {
+    let mut restored = 0;
+    for cookie in cookies.iter().filter(|cookie| !cookie.is_expired()) {
+        client
+            .add_cookie(cookie.to_cookie())
+            .await
+            .context(format!("Failed to add cookie: {}", cookie.name))?;
+        restored += 1;
+    }
+    Ok(restored)
+}
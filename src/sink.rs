@@ -0,0 +1,150 @@
+use anyhow::Result;
+use async_trait::async_trait;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::customer::CustomerInfo;
+use crate::PpData;
+
+// 고객 번호별 조회 상태
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Ok,
+    // 실패 전까지 일부 page 만 조회됨
+    Partial,
+    Failed,
+}
+
+impl Status {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Partial => "partial",
+            Status::Failed => "failed",
+        }
+    }
+}
+
+// 고객 번호 1건의 조회 결과
+#[derive(Debug, Clone)]
+pub struct AccountResult {
+    pub status: Status,
+    pub data: Vec<PpData>,
+    pub error: Option<String>,
+    // 알림 chart 용 전체 이력 (새 청구 월만 담은 알림 결과에서만 채움)
+    pub history: Vec<PpData>,
+    // --customer-info 로 읽은 계약 정보
+    pub info: Option<CustomerInfo>,
+}
+
+impl AccountResult {
+    pub fn ok(data: Vec<PpData>) -> Self {
+        Self {
+            status: Status::Ok,
+            data,
+            error: None,
+            history: Vec::new(),
+            info: None,
+        }
+    }
+
+    // 실패 전까지 조회된 data 가 있으면 partial
+    pub fn failed(data: Vec<PpData>, error: &anyhow::Error) -> Self {
+        Self {
+            status: if data.is_empty() {
+                Status::Failed
+            } else {
+                Status::Partial
+            },
+            data,
+            error: Some(format!("{:#}", error)),
+            history: Vec::new(),
+            info: None,
+        }
+    }
+}
+
+// hook, 알림 template 에 전달하는 실행 요약
+#[derive(Debug, Clone, Serialize)]
+pub struct RunSummary {
+    pub run_id: String,
+    pub output: PathBuf,
+    pub customers: usize,
+    pub rows: usize,
+    pub elapsed_secs: f64,
+    // 건너뛴 row, 재시도 등 실행 중 경고 수
+    pub warnings: usize,
+}
+
+// 조회 결과를 받는 출력 대상
+// 기본 제공 sink 외에 사내 REST, Kafka 등 직접 구현한 sink 도 deliver 에 넘길 수 있음
+// 호출 순서: 고객 번호마다 write_rows -> flush -> finalize (한 실행에 한 번)
+#[async_trait]
+pub trait Sink: Send {
+    // 로그, 실패 메시지에 표시할 이름
+    fn name(&self) -> String;
+
+    // 고객 번호 1개의 조회 결과 (value 는 출력 옵션이 적용된 JSON)
+    async fn write_rows(
+        &mut self,
+        customer_number: &str,
+        result: &AccountResult,
+        value: &Value,
+    ) -> Result<()>;
+
+    // 조회 전 연결, 권한 확인 (실패하면 조회를 시작하지 않음)
+    async fn preflight(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    // 전달 시작 (실행 요약이 필요한 sink 만 사용)
+    fn begin(&mut self, _run: &RunSummary) {}
+
+    // 쌓아둔 row 전송
+    async fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    // 마지막 정리 (파일 확정, 연결 종료)
+    async fn finalize(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+// 조회 전 모든 sink 확인, sink 이름별 결과 (출력은 호출하는 쪽에서)
+pub async fn preflight(sinks: &mut [Box<dyn Sink>]) -> Vec<(String, Result<()>)> {
+    let mut checked = Vec::new();
+    for sink in sinks.iter_mut() {
+        let ready = sink.preflight().await;
+        checked.push((sink.name(), ready));
+    }
+    checked
+}
+
+// 모든 sink 에 전달 (한 sink 가 실패해도 나머지 sink 는 계속), sink 이름별 결과
+// values 는 고객 번호별 write_rows 에 넘길 JSON (없는 고객 번호는 null)
+pub async fn deliver(
+    sinks: &mut [Box<dyn Sink>],
+    results: &BTreeMap<String, AccountResult>,
+    values: &BTreeMap<String, Value>,
+    run: &RunSummary,
+) -> Vec<(String, Result<()>)> {
+    let mut delivered = Vec::new();
+    for sink in sinks.iter_mut() {
+        sink.begin(run);
+        let outcome = async {
+            for (customer_number, result) in results {
+                let value = values.get(customer_number).unwrap_or(&Value::Null);
+                sink.write_rows(customer_number, result, value).await?;
+            }
+            sink.flush().await?;
+            sink.finalize().await
+        }
+        .await;
+        delivered.push((sink.name(), outcome));
+    }
+    delivered
+}
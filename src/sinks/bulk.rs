@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+
+use super::Sink;
+use crate::output::AccountResult;
+use crate::PpData;
+
+const DATA_FILE: &str = "monthly_claims.csv";
+
+// Timescale (PostgreSQL) 적재 control 파일
+const TIMESCALE_CONTROL: &str = r#"-- psql -f timescale.sql (monthly_claims.csv 와 같은 디렉터리에서)
+CREATE TABLE IF NOT EXISTS monthly_claims (
+    customer_number TEXT NOT NULL,
+    claim_date DATE NOT NULL,
+    usage DOUBLE PRECISION NOT NULL,
+    paid NUMERIC(18, 2) NOT NULL,
+    tags JSONB NOT NULL DEFAULT '{}',
+    PRIMARY KEY (customer_number, claim_date)
+);
+ALTER TABLE monthly_claims ADD COLUMN IF NOT EXISTS tags JSONB NOT NULL DEFAULT '{}';
+CREATE TEMP TABLE monthly_claims_load (LIKE monthly_claims);
+\copy monthly_claims_load FROM 'monthly_claims.csv' WITH (FORMAT csv, HEADER true)
+INSERT INTO monthly_claims SELECT * FROM monthly_claims_load
+ON CONFLICT (customer_number, claim_date) DO UPDATE
+SET usage = EXCLUDED.usage, paid = EXCLUDED.paid, tags = EXCLUDED.tags;
+"#;
+
+// ClickHouse 적재 control 파일 (같은 청구 월은 merge 시 최신 row 만 남음)
+const CLICKHOUSE_CONTROL: &str = r#"-- clickhouse-client --multiquery < clickhouse.sql
+-- clickhouse-client --query "INSERT INTO monthly_claims FORMAT CSVWithNames" < monthly_claims.csv
+CREATE TABLE IF NOT EXISTS monthly_claims (
+    customer_number String,
+    claim_date Date,
+    usage Float64,
+    paid Decimal(18, 2),
+    tags String DEFAULT '{}'
+) ENGINE = ReplacingMergeTree
+ORDER BY (customer_number, claim_date);
+ALTER TABLE monthly_claims ADD COLUMN IF NOT EXISTS tags String DEFAULT '{}';
+"#;
+
+// 대량 적재용 CSV 와 control 파일 (bulk:<dir>)
+pub struct BulkSink {
+    pub dir: PathBuf,
+    // 고객 번호, row, tag JSON
+    pub rows: Vec<(String, PpData, String)>,
+}
+
+#[async_trait]
+impl Sink for BulkSink {
+    fn name(&self) -> String {
+        format!("bulk:{}", self.dir.display())
+    }
+
+    async fn preflight(&mut self) -> Result<()> {
+        super::check_writable(&self.dir)
+    }
+
+    async fn write_rows(
+        &mut self,
+        customer_number: &str,
+        result: &AccountResult,
+        value: &Value,
+    ) -> Result<()> {
+        let tags = super::tags_json(value);
+        self.rows.extend(
+            result
+                .data
+                .iter()
+                .map(|entry| (customer_number.to_string(), entry.clone(), tags.clone())),
+        );
+        Ok(())
+    }
+
+    async fn finalize(&mut self) -> Result<()> {
+        fs::create_dir_all(&self.dir).context(format!(
+            "Failed to create directory: {}",
+            self.dir.display()
+        ))?;
+
+        let mut csv = String::from("customer_number,claim_date,usage,paid,tags\n");
+        for (customer_number, entry, tags) in &self.rows {
+            let _ = writeln!(
+                csv,
+                "\"{}\",{},{},{},\"{}\"",
+                customer_number.replace('"', "\"\""),
+                entry.claim_date.format("%Y-%m-%d"),
+                entry.usage,
+                entry.paid,
+                tags.replace('"', "\"\"")
+            );
+        }
+        fs::write(self.dir.join(DATA_FILE), csv)
+            .context(format!("Failed to write {}", DATA_FILE))?;
+        fs::write(self.dir.join("timescale.sql"), TIMESCALE_CONTROL)?;
+        fs::write(self.dir.join("clickhouse.sql"), CLICKHOUSE_CONTROL)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+use super::Sink;
+use crate::error::{ErrorCode, Failure};
+use crate::output::{AccountResult, OutputFormat};
+use crate::PpData;
+
+// DuckDB CLI (별도 설치, PATH 에서 찾음)
+const DUCKDB: &str = "duckdb";
+
+// 청구 월 row 를 모아서 finalize 에 DuckDB 파일로 upsert (SQLite 저장소와 같은 table)
+// row 값은 SQL 에 넣지 않고 CSV 로 써서 DuckDB 가 read_csv 로 읽음
+pub struct DuckDbSink {
+    pub path: PathBuf,
+    // 고객 번호, row, tag JSON
+    pub rows: Vec<(String, PpData, String)>,
+}
+
+#[async_trait]
+impl Sink for DuckDbSink {
+    fn name(&self) -> String {
+        format!("duckdb:{}", self.path.display())
+    }
+
+    async fn preflight(&mut self) -> Result<()> {
+        let output = Command::new(DUCKDB)
+            .arg("-version")
+            .output()
+            .await
+            .context(not_installed())?;
+        if !output.status.success() {
+            bail!("duckdb exited with {}", output.status);
+        }
+        super::check_writable(super::parent_dir(&self.path))
+    }
+
+    async fn write_rows(
+        &mut self,
+        customer_number: &str,
+        result: &AccountResult,
+        value: &Value,
+    ) -> Result<()> {
+        let tags = super::tags_json(value);
+        self.rows.extend(
+            result
+                .data
+                .iter()
+                .map(|entry| (customer_number.to_string(), entry.clone(), tags.clone())),
+        );
+        Ok(())
+    }
+
+    async fn finalize(&mut self) -> Result<()> {
+        let rows = std::mem::take(&mut self.rows);
+        let mut sql = String::from(
+            "CREATE TABLE IF NOT EXISTS monthly_claims (
+                customer_number VARCHAR NOT NULL,
+                claim_date DATE NOT NULL,
+                usage DOUBLE NOT NULL,
+                paid DECIMAL(18, 2) NOT NULL,
+                tags VARCHAR NOT NULL DEFAULT '{}',
+                PRIMARY KEY (customer_number, claim_date)
+            );
+            ALTER TABLE monthly_claims ADD COLUMN IF NOT EXISTS tags VARCHAR DEFAULT '{}';\n",
+        );
+        let staged = if rows.is_empty() {
+            None
+        } else {
+            let staged = stage_rows(&rows)?;
+            sql.push_str(&format!(
+                "INSERT OR REPLACE INTO monthly_claims (customer_number, claim_date, usage, paid, tags)
+                SELECT customer_number, claim_date, usage, paid, tags FROM read_csv({}, header = true, quote = '\"', escape = '\"', columns = {{
+                    'customer_number': 'VARCHAR',
+                    'claim_date': 'DATE',
+                    'usage': 'DOUBLE',
+                    'paid': 'DECIMAL(18, 2)',
+                    'tags': 'VARCHAR'
+                }});\n",
+                literal(&staged.to_string_lossy())
+            ));
+            Some(staged)
+        };
+
+        let result = run(&self.path, &sql).await;
+        if let Some(staged) = staged {
+            let _ = fs::remove_file(staged);
+        }
+        result
+    }
+}
+
+// upsert 할 row 를 임시 CSV 로 저장 (값의 quote, escape 는 CSV writer 가 처리)
+fn stage_rows(rows: &[(String, PpData, String)]) -> Result<PathBuf> {
+    let path = std::env::temp_dir().join(format!(
+        "rip_hyphen-duckdb-{}-{}.csv",
+        std::process::id(),
+        ulid::Ulid::new()
+    ));
+    let mut writer =
+        ::csv::Writer::from_path(&path).context(format!("Failed to write {}", path.display()))?;
+    writer.write_record(["customer_number", "claim_date", "usage", "paid", "tags"])?;
+    for (customer_number, entry, tags) in rows {
+        writer.write_record([
+            customer_number.clone(),
+            entry.claim_date.format("%Y-%m-%d").to_string(),
+            entry.usage.to_string(),
+            entry.paid.to_string(),
+            tags.clone(),
+        ])?;
+    }
+    writer
+        .flush()
+        .context(format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+// SQL 문자열 literal (임시 파일 경로만 넣음)
+fn literal(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+// DuckDB 파일에 SQL 실행 (stdin 으로 전달, 첫 오류에서 중단)
+async fn run(path: &Path, sql: &str) -> Result<()> {
+    let mut child = Command::new(DUCKDB)
+        .arg("-bail")
+        .arg(path)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context(not_installed())?;
+    let mut stdin = child
+        .stdin
+        .take()
+        .context("duckdb stdin is not available")?;
+    stdin
+        .write_all(sql.as_bytes())
+        .await
+        .context("Failed to write SQL to duckdb")?;
+    drop(stdin);
+
+    let output = child
+        .wait_with_output()
+        .await
+        .context("Failed to wait for duckdb")?;
+    if !output.status.success() {
+        bail!(
+            "duckdb exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+// duckdb 를 실행하지 못함 (대부분 CLI 가 설치되지 않음)
+fn not_installed() -> Failure {
+    Failure::new(
+        ErrorCode::Sink,
+        "Failed to run duckdb: duckdb: sinks and query --duckdb need the DuckDB CLI on $PATH (https://duckdb.org/docs/installation)",
+    )
+}
+
+// DuckDB 파일에 읽기 전용으로 SQL 실행, 결과 문자열 (json, 표 또는 csv)
+pub async fn query(path: &Path, sql: &str, format: OutputFormat) -> Result<String> {
+    let output = Command::new(DUCKDB)
+        .arg("-readonly")
+        .arg(match format {
+            OutputFormat::Json => "-json",
+            OutputFormat::Table => "-box",
+            OutputFormat::Csv => "-csv",
+        })
+        .arg(path)
+        .arg("-c")
+        .arg(sql)
+        .output()
+        .await
+        .context(not_installed())?;
+    if !output.status.success() {
+        bail!(
+            "duckdb exited with {}: {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .trim_end()
+        .to_string())
+}
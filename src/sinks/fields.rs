@@ -0,0 +1,298 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde_json::{Number, Value};
+use std::str::FromStr;
+
+use super::Sink;
+use crate::hooks::RunSummary;
+use crate::output::AccountResult;
+
+// sink 종류 대신 쓰면 모든 sink 에 적용
+const ALL_SINKS: &str = "*";
+// field 이름, 단위를 바꿀 수 있는 sink 종류
+const MAPPED_SINKS: &[&str] = &[
+    "file",
+    "webhook",
+    "mqtt",
+    "redis",
+    "kafka",
+    "bulk",
+    "clickhouse",
+    "duckdb",
+    "homeassistant",
+];
+
+// 사용량, 요금 단위 (내부 model 은 kWh, 원)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Wh,
+    Kwh,
+    Mwh,
+    Krw,
+    // 천 원
+    Kkrw,
+}
+
+const UNITS: &[Unit] = &[Unit::Wh, Unit::Kwh, Unit::Mwh, Unit::Krw, Unit::Kkrw];
+
+impl Unit {
+    pub fn label(self) -> &'static str {
+        match self {
+            Unit::Wh => "Wh",
+            Unit::Kwh => "kWh",
+            Unit::Mwh => "MWh",
+            Unit::Krw => "KRW",
+            Unit::Kkrw => "kKRW",
+        }
+    }
+
+    // 단위를 바꿀 수 있는 field
+    fn field(self) -> &'static str {
+        match self {
+            Unit::Wh | Unit::Kwh | Unit::Mwh => "usage",
+            Unit::Krw | Unit::Kkrw => "paid",
+        }
+    }
+
+    // 내부 model 값에 곱하는 값
+    fn factor(self) -> Decimal {
+        match self {
+            Unit::Wh => Decimal::from(1000),
+            Unit::Kwh | Unit::Krw => Decimal::ONE,
+            Unit::Mwh | Unit::Kkrw => Decimal::new(1, 3),
+        }
+    }
+}
+
+impl FromStr for Unit {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        UNITS
+            .iter()
+            .copied()
+            .find(|unit| unit.label().eq_ignore_ascii_case(s))
+            .ok_or_else(|| format!("Invalid unit, expected Wh, kWh, MWh, KRW or kKRW: {}", s))
+    }
+}
+
+// sink 별 field 이름, 단위 변환 (--sink-field homeassistant:usage@Wh, --sink-field bulk:paid@kKRW)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldRule {
+    // sink 종류 (file, webhook, mqtt, ...) 또는 *
+    pub sink: String,
+    pub field: String,
+    pub rename: Option<String>,
+    pub unit: Option<Unit>,
+}
+
+impl FromStr for FieldRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "Invalid sink field, expected <sink|*>:<field>[=<name>][@<unit>]: {}",
+                s
+            )
+        };
+        let (sink, spec) = s.split_once(':').ok_or_else(invalid)?;
+        let (mapping, unit) = match spec.split_once('@') {
+            Some((mapping, unit)) => (mapping, Some(unit.trim().parse::<Unit>()?)),
+            None => (spec, None),
+        };
+        let (field, rename) = match mapping.split_once('=') {
+            Some((field, rename)) => (field.trim(), Some(rename.trim())),
+            None => (mapping.trim(), None),
+        };
+
+        let sink = sink.trim();
+        if sink != ALL_SINKS && !MAPPED_SINKS.contains(&sink) {
+            return Err(format!(
+                "Sink '{}' does not support field mapping, expected * or one of {}",
+                sink,
+                MAPPED_SINKS.join(", ")
+            ));
+        }
+        // JSON field, CSV column 이름으로 쓸 수 있는 문자만
+        for name in std::iter::once(field).chain(rename) {
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(format!(
+                    "Invalid field name '{}': only letters, digits and underscores are allowed",
+                    name
+                ));
+            }
+        }
+        if let Some(unit) = unit.filter(|unit| unit.field() != field) {
+            return Err(format!(
+                "Unit {} only applies to the {} field: {}",
+                unit.label(),
+                unit.field(),
+                s
+            ));
+        }
+        if rename.is_none() && unit.is_none() {
+            return Err(invalid());
+        }
+        Ok(Self {
+            sink: sink.to_string(),
+            field: field.to_string(),
+            rename: rename.map(str::to_string),
+            unit,
+        })
+    }
+}
+
+// sink 1개에 적용할 field 이름, 단위 (* 규칙 위에 sink 종류 규칙)
+#[derive(Debug, Clone, Default)]
+pub struct FieldMap {
+    renames: Vec<(String, String)>,
+    usage: Option<Unit>,
+    paid: Option<Unit>,
+}
+
+impl FieldMap {
+    pub fn for_sink(rules: &[FieldRule], kind: &str) -> Self {
+        let mut map = Self::default();
+        if !MAPPED_SINKS.contains(&kind) {
+            return map;
+        }
+        let matched = rules
+            .iter()
+            .filter(|rule| rule.sink == ALL_SINKS)
+            .chain(rules.iter().filter(|rule| rule.sink == kind));
+        for rule in matched {
+            if let Some(rename) = &rule.rename {
+                map.renames.retain(|(field, _)| *field != rule.field);
+                map.renames.push((rule.field.clone(), rename.clone()));
+            }
+            match rule.unit {
+                Some(unit) if unit.field() == "usage" => map.usage = Some(unit),
+                Some(unit) => map.paid = Some(unit),
+                None => {}
+            }
+        }
+        map
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.renames.is_empty() && self.usage.is_none() && self.paid.is_none()
+    }
+
+    pub fn usage_unit(&self) -> Unit {
+        self.usage.unwrap_or(Unit::Kwh)
+    }
+
+    pub fn paid_unit(&self) -> Unit {
+        self.paid.unwrap_or(Unit::Krw)
+    }
+
+    // 조회 결과 row 의 단위 변환 (row 를 직접 직렬화하는 sink 용)
+    pub fn convert(&self, result: &AccountResult) -> AccountResult {
+        let mut result = result.clone();
+        let usage = self.usage_unit().factor().to_f64().unwrap_or(1.0);
+        let paid = self.paid_unit().factor();
+        for entry in result.data.iter_mut().chain(result.history.iter_mut()) {
+            entry.usage *= usage;
+            entry.paid *= paid;
+        }
+        result
+    }
+
+    // 결과 JSON 의 data row 단위 변환 후 field 이름 변경
+    pub fn apply(&self, value: &Value) -> Result<Value> {
+        let mut value = value.clone();
+        let rows = value["data"].as_array_mut().into_iter().flatten();
+        for row in rows.filter_map(Value::as_object_mut) {
+            for (field, unit) in [("usage", self.usage), ("paid", self.paid)] {
+                if let (Some(unit), Some(amount)) = (unit, row.get_mut(field)) {
+                    *amount = scale(amount, unit.factor()).context(format!(
+                        "Failed to convert {} to {}",
+                        field,
+                        unit.label()
+                    ))?;
+                }
+            }
+            for (field, rename) in &self.renames {
+                if let Some(amount) = row.remove(field) {
+                    row.insert(rename.clone(), amount);
+                }
+            }
+        }
+        Ok(value)
+    }
+}
+
+// 직렬화된 숫자 (정수, 실수, --money-format string 의 10진 문자열) 에 factor 곱함
+fn scale(amount: &Value, factor: Decimal) -> Result<Value> {
+    match amount {
+        Value::Null => Ok(Value::Null),
+        Value::String(text) => {
+            let amount: Decimal = text.parse().context("Not a decimal")?;
+            Ok(Value::String((amount * factor).normalize().to_string()))
+        }
+        Value::Number(number) => {
+            let amount = match number.as_i64() {
+                Some(amount) => Decimal::from(amount),
+                None => number
+                    .as_f64()
+                    .and_then(Decimal::from_f64)
+                    .context("Not a number")?,
+            };
+            let scaled = (amount * factor).normalize();
+            // 정수로 떨어지면 정수 그대로
+            match scaled.is_integer().then(|| scaled.to_i64()).flatten() {
+                Some(scaled) => Ok(scaled.into()),
+                None => scaled
+                    .to_f64()
+                    .and_then(Number::from_f64)
+                    .map(Value::Number)
+                    .context("Amount out of range"),
+            }
+        }
+        _ => anyhow::bail!("Not a number"),
+    }
+}
+
+// field map 을 적용한 뒤 원래 sink 에 전달
+pub struct MappedSink {
+    pub inner: Box<dyn Sink>,
+    pub fields: FieldMap,
+}
+
+#[async_trait]
+impl Sink for MappedSink {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    async fn write_rows(
+        &mut self,
+        customer_number: &str,
+        result: &AccountResult,
+        value: &Value,
+    ) -> Result<()> {
+        let value = self.fields.apply(value)?;
+        self.inner
+            .write_rows(customer_number, &self.fields.convert(result), &value)
+            .await
+    }
+
+    async fn preflight(&mut self) -> Result<()> {
+        self.inner.preflight().await
+    }
+
+    fn begin(&mut self, run: &RunSummary) {
+        self.inner.begin(run);
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        self.inner.flush().await
+    }
+
+    async fn finalize(&mut self) -> Result<()> {
+        self.inner.finalize().await
+    }
+}
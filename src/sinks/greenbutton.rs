@@ -0,0 +1,210 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{Datelike, FixedOffset, NaiveDate, TimeZone, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::PathBuf;
+
+use super::Sink;
+use crate::output::AccountResult;
+use crate::PpData;
+
+// ESPI 코드 값 (NAESB REQ.21)
+// ReadingType: deltaData, electricity, KRW, normal, forward, energy, Wh
+const READING_TYPE: &str = "<espi:ReadingType>\
+<espi:accumulationBehaviour>4</espi:accumulationBehaviour>\
+<espi:commodity>1</espi:commodity>\
+<espi:currency>410</espi:currency>\
+<espi:dataQualifier>12</espi:dataQualifier>\
+<espi:flowDirection>1</espi:flowDirection>\
+<espi:kind>12</espi:kind>\
+<espi:powerOfTenMultiplier>0</espi:powerOfTenMultiplier>\
+<espi:timeAttribute>0</espi:timeAttribute>\
+<espi:uom>72</espi:uom>\
+</espi:ReadingType>";
+
+// 청구 월 기준 시각 (KST)
+const KST_OFFSET_SECS: i32 = 9 * 3600;
+
+// Green Button (ESPI Atom feed) XML 파일 (greenbutton:<path>)
+// 고객 번호마다 UsagePoint, 청구 월마다 IntervalReading (사용량 Wh, 요금 1/100000 원)
+pub struct GreenButtonSink {
+    pub path: PathBuf,
+    pub customers: BTreeMap<String, Vec<PpData>>,
+}
+
+#[async_trait]
+impl Sink for GreenButtonSink {
+    fn name(&self) -> String {
+        format!("greenbutton:{}", self.path.display())
+    }
+
+    async fn preflight(&mut self) -> Result<()> {
+        super::check_writable(super::parent_dir(&self.path))
+    }
+
+    async fn write_rows(
+        &mut self,
+        customer_number: &str,
+        result: &AccountResult,
+        _value: &Value,
+    ) -> Result<()> {
+        let mut data = result.data.clone();
+        data.sort_by_key(|entry| entry.claim_date);
+        self.customers.insert(customer_number.to_string(), data);
+        Ok(())
+    }
+
+    async fn finalize(&mut self) -> Result<()> {
+        fs::write(&self.path, feed(&self.customers))
+            .context(format!("Failed to write {}", self.path.display()))
+    }
+}
+
+fn feed(customers: &BTreeMap<String, Vec<PpData>>) -> String {
+    let updated = Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string();
+    let mut xml = String::new();
+    let _ = write!(
+        xml,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom" xmlns:espi="http://naesb.org/espi">
+<id>tag:rip-hyphen,2024:feed</id>
+<title>KEPCO billing data</title>
+<updated>{}</updated>
+"#,
+        updated
+    );
+
+    for (customer_number, data) in customers {
+        let customer = escape(customer_number);
+        let base = format!("RetailCustomer/1/UsagePoint/{}", customer);
+        entry(
+            &mut xml,
+            &updated,
+            &base,
+            &format!("Customer {}", customer),
+            &[
+                ("related", format!("{}/MeterReading", base)),
+                ("related", format!("LocalTimeParameters/{}", customer)),
+            ],
+            "<espi:UsagePoint><espi:ServiceCategory><espi:kind>0</espi:kind></espi:ServiceCategory></espi:UsagePoint>",
+        );
+        entry(
+            &mut xml,
+            &updated,
+            &format!("LocalTimeParameters/{}", customer),
+            "KST",
+            &[],
+            &format!(
+                "<espi:LocalTimeParameters><espi:dstEndRule>00000000</espi:dstEndRule><espi:dstOffset>0</espi:dstOffset><espi:dstStartRule>00000000</espi:dstStartRule><espi:tzOffset>{}</espi:tzOffset></espi:LocalTimeParameters>",
+                KST_OFFSET_SECS
+            ),
+        );
+        entry(
+            &mut xml,
+            &updated,
+            &format!("{}/MeterReading/1", base),
+            "Monthly billing",
+            &[
+                ("related", format!("{}/MeterReading/1/IntervalBlock", base)),
+                ("related", format!("ReadingType/{}", customer)),
+            ],
+            "<espi:MeterReading/>",
+        );
+        entry(
+            &mut xml,
+            &updated,
+            &format!("ReadingType/{}", customer),
+            "Energy delivered (Wh), cost (KRW)",
+            &[],
+            READING_TYPE,
+        );
+        entry(
+            &mut xml,
+            &updated,
+            &format!("{}/MeterReading/1/IntervalBlock/1", base),
+            "Monthly claims",
+            &[],
+            &interval_block(data),
+        );
+    }
+    xml.push_str("</feed>\n");
+    xml
+}
+
+fn entry(
+    xml: &mut String,
+    updated: &str,
+    href: &str,
+    title: &str,
+    related: &[(&str, String)],
+    content: &str,
+) {
+    let _ = writeln!(xml, "<entry>");
+    let _ = writeln!(xml, "<id>tag:rip-hyphen,2024:{}</id>", href);
+    let _ = writeln!(xml, r#"<link rel="self" href="{}"/>"#, href);
+    for (rel, target) in related {
+        let _ = writeln!(xml, r#"<link rel="{}" href="{}"/>"#, rel, target);
+    }
+    let _ = writeln!(xml, "<title>{}</title>", title);
+    let _ = writeln!(xml, "<updated>{}</updated>", updated);
+    let _ = writeln!(xml, "<content>{}</content>", content);
+    let _ = writeln!(xml, "</entry>");
+}
+
+// 청구 월 1일 0시 (KST) 부터 다음 달 1일까지를 한 구간으로
+fn interval_block(data: &[PpData]) -> String {
+    let periods: Vec<(i64, i64, &PpData)> = data
+        .iter()
+        .filter_map(|entry| {
+            let start = month_start(entry.claim_date)?;
+            let next = entry
+                .claim_date
+                .with_day(1)?
+                .checked_add_months(chrono::Months::new(1))?;
+            Some((start, month_start(next)? - start, entry))
+        })
+        .collect();
+
+    let mut block = String::from("<espi:IntervalBlock>");
+    if let (Some(first), Some(last)) = (periods.first(), periods.last()) {
+        let _ = write!(
+            block,
+            "<espi:interval><espi:duration>{}</espi:duration><espi:start>{}</espi:start></espi:interval>",
+            last.0 + last.1 - first.0,
+            first.0
+        );
+    }
+    for (start, duration, entry) in periods {
+        let _ = write!(
+            block,
+            "<espi:IntervalReading><espi:cost>{}</espi:cost><espi:timePeriod><espi:duration>{}</espi:duration><espi:start>{}</espi:start></espi:timePeriod><espi:value>{}</espi:value></espi:IntervalReading>",
+            (entry.paid * Decimal::from(100_000))
+                .round()
+                .to_i64()
+                .unwrap_or_default(),
+            duration,
+            start,
+            (entry.usage * 1000.0).round() as i64
+        );
+    }
+    block.push_str("</espi:IntervalBlock>");
+    block
+}
+
+fn month_start(date: NaiveDate) -> Option<i64> {
+    let kst = FixedOffset::east_opt(KST_OFFSET_SECS)?;
+    let midnight = date.with_day(1)?.and_hms_opt(0, 0, 0)?;
+    Some(kst.from_local_datetime(&midnight).single()?.timestamp())
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
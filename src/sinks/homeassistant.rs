@@ -0,0 +1,126 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{Datelike, FixedOffset, TimeZone};
+use rust_decimal::prelude::ToPrimitive;
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+use super::Sink;
+use crate::output::AccountResult;
+use crate::PpData;
+
+// Home Assistant 외부 statistics source (statistic_id 의 prefix)
+const SOURCE: &str = "rip_hyphen";
+
+// Home Assistant recorder.import_statistics 입력 형태의 JSON 파일 (homeassistant:<path>)
+// 고객 번호마다 사용량 (kWh), 요금 (KRW) statistic, 단위는 --sink-field 로 변경 (e.g. Wh)
+// 청구 월 1일 0시 (KST) 의 hourly row 에 한 달 값, state 는 월마다 reset, sum 은 누적
+pub struct HomeAssistantSink {
+    pub path: PathBuf,
+    pub usage_unit: &'static str,
+    pub paid_unit: &'static str,
+    pub customers: BTreeMap<String, Vec<PpData>>,
+}
+
+#[async_trait]
+impl Sink for HomeAssistantSink {
+    fn name(&self) -> String {
+        format!("homeassistant:{}", self.path.display())
+    }
+
+    async fn preflight(&mut self) -> Result<()> {
+        super::check_writable(super::parent_dir(&self.path))
+    }
+
+    async fn write_rows(
+        &mut self,
+        customer_number: &str,
+        result: &AccountResult,
+        _value: &Value,
+    ) -> Result<()> {
+        let mut data = result.data.clone();
+        data.sort_by_key(|entry| entry.claim_date);
+        self.customers.insert(customer_number.to_string(), data);
+        Ok(())
+    }
+
+    async fn finalize(&mut self) -> Result<()> {
+        let mut statistics = Vec::new();
+        for (customer_number, data) in &self.customers {
+            statistics.push(statistic(
+                customer_number,
+                "energy",
+                "KEPCO energy",
+                self.usage_unit,
+                data,
+                |entry| entry.usage,
+            )?);
+            statistics.push(statistic(
+                customer_number,
+                "cost",
+                "KEPCO cost",
+                self.paid_unit,
+                data,
+                |entry| entry.paid.to_f64().unwrap_or_default(),
+            )?);
+        }
+        fs::write(&self.path, serde_json::to_vec_pretty(&statistics)?)
+            .context(format!("Failed to write {}", self.path.display()))
+    }
+}
+
+fn statistic(
+    customer_number: &str,
+    kind: &str,
+    name: &str,
+    unit: &str,
+    data: &[PpData],
+    value: impl Fn(&PpData) -> f64,
+) -> Result<Value> {
+    let kst = FixedOffset::east_opt(9 * 3600).context("Invalid KST offset")?;
+    // statistic_id 의 object id 는 소문자, 숫자, _ 만
+    let object_id: String = customer_number
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() {
+                c.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    let mut sum = 0.0;
+    let mut stats = Vec::new();
+    for entry in data {
+        let start = entry
+            .claim_date
+            .with_day(1)
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .and_then(|midnight| kst.from_local_datetime(&midnight).single())
+            .context(format!("Invalid claim date: {}", entry.claim_date))?
+            .to_rfc3339();
+        let state = value(entry);
+        sum += state;
+        stats.push(json!({
+            "start": start,
+            "last_reset": start,
+            "state": state,
+            "sum": sum,
+        }));
+    }
+
+    Ok(json!({
+        "metadata": {
+            "source": SOURCE,
+            "statistic_id": format!("{}:{}_{}", SOURCE, kind, object_id),
+            "name": format!("{} {}", name, customer_number),
+            "unit_of_measurement": unit,
+            "has_mean": false,
+            "has_sum": true,
+        },
+        "stats": stats,
+    }))
+}
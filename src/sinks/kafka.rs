@@ -0,0 +1,130 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use rdkafka::config::ClientConfig;
+use rdkafka::producer::{DeliveryFuture, FutureProducer, FutureRecord, Producer};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+use super::{Sink, SINK_TIMEOUT};
+use crate::output::AccountResult;
+
+// brokers 별 producer (pipeline 의 다음 계정 실행에서도 재사용, clone 은 같은 producer 공유)
+static PRODUCERS: Mutex<Option<HashMap<String, FutureProducer>>> = Mutex::new(None);
+
+// 청구 월 row 마다 message 1개 (key: 고객 번호)
+// send 를 기다리지 않고 producer 에 쌓은 뒤 flush 에 batch 로 전송, 전달 결과 확인
+pub struct KafkaSink {
+    pub brokers: String,
+    pub topic: String,
+    pub producer: Option<FutureProducer>,
+    pub pending: Vec<DeliveryFuture>,
+}
+
+impl KafkaSink {
+    fn producer(&mut self) -> Result<FutureProducer> {
+        if let Some(producer) = &self.producer {
+            return Ok(producer.clone());
+        }
+        let mut producers = PRODUCERS.lock().unwrap_or_else(PoisonError::into_inner);
+        let producers = producers.get_or_insert_with(HashMap::new);
+        let producer = match producers.get(&self.brokers) {
+            Some(producer) => producer.clone(),
+            None => {
+                let producer: FutureProducer = ClientConfig::new()
+                    .set("bootstrap.servers", &self.brokers)
+                    .set("message.timeout.ms", SINK_TIMEOUT.as_millis().to_string())
+                    .set("enable.idempotence", "true")
+                    .create()
+                    .context("Failed to create Kafka producer")?;
+                producers.insert(self.brokers.clone(), producer.clone());
+                producer
+            }
+        };
+        Ok(self.producer.insert(producer).clone())
+    }
+}
+
+#[async_trait]
+impl Sink for KafkaSink {
+    fn name(&self) -> String {
+        format!("kafka://{}/{}", self.brokers, self.topic)
+    }
+
+    async fn preflight(&mut self) -> Result<()> {
+        let topic = self.topic.clone();
+        let producer = self.producer()?;
+        // broker 에서 topic metadata 조회 (blocking 호출이라 blocking thread 에서)
+        let metadata = {
+            let topic = topic.clone();
+            tokio::task::spawn_blocking(move || {
+                producer.client().fetch_metadata(Some(&topic), SINK_TIMEOUT)
+            })
+            .await
+            .context("Kafka metadata lookup panicked")?
+        }
+        .context("Kafka brokers are not reachable")?;
+        if let Some(error) = metadata
+            .topics()
+            .iter()
+            .find(|metadata| metadata.name() == topic)
+            .and_then(|metadata| metadata.error())
+        {
+            anyhow::bail!("Kafka topic {} is not available: {:?}", topic, error);
+        }
+        Ok(())
+    }
+
+    async fn write_rows(
+        &mut self,
+        customer_number: &str,
+        _result: &AccountResult,
+        value: &Value,
+    ) -> Result<()> {
+        let producer = self.producer()?;
+        for row in value["data"].as_array().into_iter().flatten() {
+            // 고객 번호를 포함해 message 만으로 row 를 식별할 수 있도록
+            let mut row = row.clone();
+            if let Value::Object(map) = &mut row {
+                map.insert("customer_number".to_string(), customer_number.into());
+            }
+            let payload = serde_json::to_vec(&row)?;
+            let delivery = producer
+                .send_result(
+                    FutureRecord::to(&self.topic)
+                        .key(customer_number)
+                        .payload(&payload),
+                )
+                .map_err(|(e, _)| e)
+                .context(format!(
+                    "Failed to queue a Kafka message for {}",
+                    self.topic
+                ))?;
+            self.pending.push(delivery);
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        if let Some(producer) = self.producer.clone() {
+            // flush 는 전송이 끝날 때까지 막히므로 runtime worker 를 잡지 않도록
+            tokio::task::spawn_blocking(move || producer.flush(SINK_TIMEOUT))
+                .await
+                .context("Kafka producer flush panicked")?
+                .context("Failed to flush Kafka producer")?;
+        }
+        for delivery in std::mem::take(&mut self.pending) {
+            delivery
+                .await
+                .context("Kafka producer dropped a message")?
+                .map_err(|(e, _)| e)
+                .context(format!("Failed to publish to Kafka topic {}", self.topic))?;
+        }
+        Ok(())
+    }
+
+    async fn finalize(&mut self) -> Result<()> {
+        self.producer.take();
+        Ok(())
+    }
+}
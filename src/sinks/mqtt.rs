@@ -0,0 +1,81 @@
+use anyhow::{bail, Context, Result};
+use async_trait::async_trait;
+use rumqttc::{AsyncClient, Event, MqttOptions, Outgoing, QoS};
+use serde_json::Value;
+use tokio::time::{timeout, Duration};
+
+use super::{Sink, SINK_TIMEOUT};
+use crate::output::AccountResult;
+
+// 고객 번호별 retain message 를 모아서 flush 에 publish
+pub struct MqttSink {
+    pub name: String,
+    pub host: String,
+    pub port: u16,
+    pub topic: String,
+    pub messages: Vec<(String, Vec<u8>)>,
+}
+
+#[async_trait]
+impl Sink for MqttSink {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+
+    async fn preflight(&mut self) -> Result<()> {
+        publish_mqtt(&self.host, self.port, Vec::new()).await
+    }
+
+    async fn write_rows(
+        &mut self,
+        customer_number: &str,
+        _result: &AccountResult,
+        value: &Value,
+    ) -> Result<()> {
+        self.messages.push((
+            format!("{}/{}", self.topic, customer_number),
+            serde_json::to_vec(value)?,
+        ));
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        let messages = std::mem::take(&mut self.messages);
+        if messages.is_empty() {
+            return Ok(());
+        }
+        publish_mqtt(&self.host, self.port, messages).await
+    }
+}
+
+// retain message publish 후 연결 종료 (message 가 없으면 연결 확인만)
+async fn publish_mqtt(host: &str, port: u16, messages: Vec<(String, Vec<u8>)>) -> Result<()> {
+    let mut options = MqttOptions::new(format!("rip_hyphen-{}", std::process::id()), host, port);
+    options.set_keep_alive(Duration::from_secs(30));
+    // publish 요청이 event loop 를 기다리지 않도록 전체 message 수만큼 buffer
+    let (client, mut eventloop) = AsyncClient::new(options, messages.len() + 1);
+
+    for (topic, payload) in messages {
+        client
+            .publish(topic, QoS::AtLeastOnce, true, payload)
+            .await
+            .context("Failed to queue MQTT message")?;
+    }
+    client
+        .disconnect()
+        .await
+        .context("Failed to queue MQTT disconnect")?;
+
+    // 쌓인 요청을 broker 로 전송 (disconnect 전송까지)
+    loop {
+        match timeout(SINK_TIMEOUT, eventloop.poll()).await {
+            Ok(Ok(Event::Outgoing(Outgoing::Disconnect))) => return Ok(()),
+            Ok(Ok(_)) => continue,
+            Ok(Err(e)) => return Err(e).context("MQTT connection failed"),
+            Err(_) => bail!(
+                "MQTT broker did not respond within {}s",
+                SINK_TIMEOUT.as_secs()
+            ),
+        }
+    }
+}
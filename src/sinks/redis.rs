@@ -0,0 +1,141 @@
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use redis::aio::MultiplexedConnection;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+use tokio::time::timeout;
+
+use super::{Sink, SINK_TIMEOUT};
+use crate::output::AccountResult;
+
+// 고객 번호별 최신 청구 월 hash key
+const KEY_PREFIX: &str = "rip_hyphen:latest";
+
+// url 별 연결 (multiplexed 라 clone 해서 공유, pipeline 의 다음 계정 실행에서도 재사용)
+static CONNECTIONS: Mutex<Option<HashMap<String, MultiplexedConnection>>> = Mutex::new(None);
+
+// 고객 번호마다 최신 청구 월 row 를 hash 로 (rip_hyphen:latest:<고객 번호>), TTL 후 만료
+// 고객 번호별 명령을 pipeline 하나에 모아 flush 에 한 번에 전송
+pub struct RedisSink {
+    pub url: String,
+    pub ttl: u64,
+    pub pipeline: redis::Pipeline,
+}
+
+impl RedisSink {
+    async fn connection(&self) -> Result<MultiplexedConnection> {
+        let cached = CONNECTIONS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_ref()
+            .and_then(|connections| connections.get(&self.url).cloned());
+        if let Some(conn) = cached {
+            return Ok(conn);
+        }
+        let client = redis::Client::open(self.url.as_str()).context("Invalid Redis url")?;
+        let conn = timeout(SINK_TIMEOUT, client.get_multiplexed_async_connection())
+            .await
+            .context("Redis did not respond")?
+            .context("Failed to connect to Redis")?;
+        CONNECTIONS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get_or_insert_with(HashMap::new)
+            .insert(self.url.clone(), conn.clone());
+        Ok(conn)
+    }
+
+    // 실패한 연결은 다음 실행에서 다시 연결
+    fn forget(&self) {
+        if let Some(connections) = CONNECTIONS
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .as_mut()
+        {
+            connections.remove(&self.url);
+        }
+    }
+}
+
+#[async_trait]
+impl Sink for RedisSink {
+    fn name(&self) -> String {
+        super::display_url(&self.url)
+    }
+
+    async fn preflight(&mut self) -> Result<()> {
+        let mut conn = self.connection().await?;
+        let ping = timeout(
+            SINK_TIMEOUT,
+            redis::cmd("PING").query_async::<_, String>(&mut conn),
+        )
+        .await
+        .context("Redis did not respond")
+        .and_then(|ping| ping.context("Redis PING failed"));
+        if ping.is_err() {
+            self.forget();
+        }
+        ping.map(|_| ())
+    }
+
+    async fn write_rows(
+        &mut self,
+        customer_number: &str,
+        result: &AccountResult,
+        value: &Value,
+    ) -> Result<()> {
+        // data 와 같은 순서로 변환된 row 중 청구 월이 가장 늦은 row
+        let latest = result
+            .data
+            .iter()
+            .zip(value["data"].as_array().into_iter().flatten())
+            .max_by_key(|(entry, _)| entry.claim_date)
+            .map(|(_, row)| row);
+        let Some(Value::Object(row)) = latest else {
+            return Ok(());
+        };
+
+        let mut fields: Vec<(String, String)> = row
+            .iter()
+            .filter(|(_, value)| !value.is_null())
+            .map(|(field, value)| {
+                let value = match value {
+                    Value::String(text) => text.clone(),
+                    other => other.to_string(),
+                };
+                (field.clone(), value)
+            })
+            .collect();
+        fields.push(("customer_number".to_string(), customer_number.to_string()));
+        fields.push(("updated_at".to_string(), chrono::Utc::now().to_rfc3339()));
+
+        // 이전 row 의 field 가 남지 않도록 교체
+        let key = format!("{}:{}", KEY_PREFIX, customer_number);
+        self.pipeline
+            .del(&key)
+            .hset_multiple(&key, &fields)
+            .expire(&key, self.ttl as i64);
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<()> {
+        let mut pipeline = std::mem::replace(&mut self.pipeline, redis::pipe());
+        if pipeline.cmd_iter().next().is_none() {
+            return Ok(());
+        }
+        // 고객 번호 일부만 갱신되지 않도록 MULTI/EXEC
+        pipeline.atomic();
+        let mut conn = self.connection().await?;
+        let written = timeout(SINK_TIMEOUT, pipeline.query_async::<_, ()>(&mut conn))
+            .await
+            .context("Redis did not respond")
+            .and_then(|written| {
+                written.context("Failed to write the latest billing months to Redis")
+            });
+        if written.is_err() {
+            self.forget();
+        }
+        written
+    }
+}
@@ -0,0 +1,61 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::{customer, PpData};
+
+// --since-last 실행 사이에 유지하는 고객 번호별 마지막으로 조회한 최근 청구 월 (data dir 의 state.json)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct State {
+    #[serde(default)]
+    latest: BTreeMap<String, NaiveDate>,
+}
+
+impl State {
+    // 파일이 없으면 빈 상태 (첫 실행은 전체 조회)
+    pub fn load(path: &Path) -> Result<Self> {
+        match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes)
+                .context(format!("Invalid state file {}", path.display())),
+            Err(_) => Ok(Self::default()),
+        }
+    }
+
+    pub fn latest(&self, customer_number: &str) -> Option<NaiveDate> {
+        self.latest
+            .get(&customer::normalize(customer_number))
+            .copied()
+    }
+
+    // 조회를 마친 고객 번호의 row 중 확정된 최근 청구 월 기록 (이전 값보다 과거면 유지)
+    pub fn record(&mut self, customer_number: &str, rows: &[PpData]) {
+        let Some(newest) = rows
+            .iter()
+            .filter(|entry| !entry.provisional)
+            .map(|entry| entry.claim_date)
+            .max()
+        else {
+            return;
+        };
+        let latest = self
+            .latest
+            .entry(customer::normalize(customer_number))
+            .or_insert(newest);
+        *latest = (*latest).max(newest);
+    }
+
+    // tmp 에 쓴 뒤 rename (중간에 끊겨도 이전 상태 유지)
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .context(format!("Failed to create directory: {}", dir.display()))?;
+        }
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec_pretty(self)?)
+            .context(format!("Failed to write state: {}", tmp.display()))?;
+        fs::rename(&tmp, path).context(format!("Failed to update state: {}", path.display()))
+    }
+}
@@ -0,0 +1,733 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::ValueEnum;
+use rusqlite::types::{Type, ValueRef};
+use rusqlite::{params, params_from_iter, Connection, ToSql};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::path::Path;
+
+use crate::error::{ErrorCode, Failure};
+use crate::output::Status;
+use crate::PpData;
+
+// 실행 중 집계 (runs, account_status table 기록용)
+#[derive(Debug, Default)]
+pub struct RunStats {
+    pub accounts: Vec<String>,
+    pub rows: usize,
+    // 고객 번호별 조회 결과 (없는 고객 번호는 실행 결과로 기록)
+    pub statuses: HashMap<String, (Status, Option<String>)>,
+}
+
+// 고객 번호의 마지막 조회 (account_status table)
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountStatus {
+    pub last_attempt_at: DateTime<Utc>,
+    pub last_status: String,
+    pub last_error: Option<String>,
+    // 한 번도 성공하지 않았으면 None
+    pub last_success_at: Option<DateTime<Utc>>,
+}
+
+// runs table 의 실행 기록
+#[derive(Debug, Serialize)]
+pub struct RunRecord {
+    pub id: i64,
+    // 실행 id (artifact 디렉터리 이름), 이전 버전 기록은 None
+    pub run_id: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: Option<DateTime<Utc>>,
+    pub version: String,
+    pub command: String,
+    pub accounts: String,
+    pub rows: i64,
+    // 종료되지 않은 실행이면 None
+    pub status: Option<String>,
+    pub error: Option<String>,
+}
+
+// 금액 column -> Decimal (정수 금액은 INTEGER, 원 미만 금액은 REAL 로 저장됨)
+fn decimal_from_sql(value: ValueRef) -> rusqlite::Result<Decimal> {
+    match value {
+        ValueRef::Integer(amount) => Ok(Decimal::from(amount)),
+        ValueRef::Real(amount) => Decimal::try_from(amount)
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, Type::Real, Box::new(e))),
+        ValueRef::Text(text) => String::from_utf8_lossy(text)
+            .parse()
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(2, Type::Text, Box::new(e))),
+        other => Err(rusqlite::Error::InvalidColumnType(
+            2,
+            "paid".to_string(),
+            other.data_type(),
+        )),
+    }
+}
+
+// 저장된 청구 월 값 변경 (claim_changes table)
+#[derive(Debug, Clone)]
+pub struct ClaimChange {
+    // 새로 저장되는 달이면 None
+    pub before: Option<PpData>,
+    pub after: PpData,
+}
+
+impl std::fmt::Display for ClaimChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let month = self.after.claim_date.format("%Y-%m");
+        match &self.before {
+            Some(before) => write!(
+                f,
+                "{}: {} -> {} kWh, {} -> {} won",
+                month, before.usage, self.after.usage, before.paid, self.after.paid
+            ),
+            None => write!(
+                f,
+                "{}: new, {} kWh, {} won",
+                month, self.after.usage, self.after.paid
+            ),
+        }
+    }
+}
+
+// 한 INSERT statement 당 row 수
+const BATCH_SIZE: usize = 500;
+
+// 청구 row 출처 (monthly_claims.source)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClaimSource {
+    Scraped,
+    // import excel 로 가져온 row
+    Imported,
+}
+
+impl ClaimSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ClaimSource::Scraped => "scraped",
+            ClaimSource::Imported => "imported",
+        }
+    }
+}
+
+// 출처가 다른 row 가 같은 달에 값이 다를 때 우선순위
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum MergePolicy {
+    #[default]
+    ScrapedWins,
+    ImportedWins,
+    // 값이 다르면 저장하지 않고 실패
+    Error,
+}
+
+impl MergePolicy {
+    fn winner(self) -> Option<ClaimSource> {
+        match self {
+            MergePolicy::ScrapedWins => Some(ClaimSource::Scraped),
+            MergePolicy::ImportedWins => Some(ClaimSource::Imported),
+            MergePolicy::Error => None,
+        }
+    }
+}
+
+// 로컬 저장소 (SQLite)
+// 연결은 실행 동안 1개를 열어 모든 고객 번호에 재사용
+pub struct Store {
+    conn: Connection,
+    merge_policy: MergePolicy,
+}
+
+impl Store {
+    // 저장소 열기, 테이블 생성
+    pub fn open(path: &Path) -> Result<Self> {
+        let conn =
+            Connection::open(path).context(format!("Failed to open store: {}", path.display()))?;
+
+        conn.execute_batch(
+            r#"
+            CREATE TABLE IF NOT EXISTS monthly_claims (
+                customer_number TEXT NOT NULL,
+                claim_date TEXT NOT NULL,
+                usage REAL NOT NULL,
+                paid TEXT NOT NULL,
+                PRIMARY KEY (customer_number, claim_date)
+            );
+            CREATE TABLE IF NOT EXISTS runs (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                started_at TEXT NOT NULL,
+                finished_at TEXT,
+                version TEXT NOT NULL,
+                command TEXT NOT NULL,
+                accounts TEXT NOT NULL,
+                rows INTEGER NOT NULL DEFAULT 0,
+                status TEXT,
+                error TEXT
+            );
+            CREATE TABLE IF NOT EXISTS claim_changes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                changed_at TEXT NOT NULL,
+                reason TEXT NOT NULL,
+                customer_number TEXT NOT NULL,
+                claim_date TEXT NOT NULL,
+                old_usage REAL,
+                old_paid TEXT,
+                new_usage REAL NOT NULL,
+                new_paid TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS account_status (
+                customer_number TEXT PRIMARY KEY,
+                last_attempt_at TEXT NOT NULL,
+                last_status TEXT NOT NULL,
+                last_error TEXT,
+                last_success_at TEXT
+            );
+            "#,
+        )
+        .context("Failed to create store tables")?;
+
+        // 이전 버전 저장소에 없는 column 추가 (기존 row 는 조회한 row)
+        add_column(&conn, "runs", "run_id", "TEXT")?;
+        add_column(
+            &conn,
+            "monthly_claims",
+            "source",
+            "TEXT NOT NULL DEFAULT 'scraped'",
+        )?;
+        // 원 미만 금액이 INTEGER affinity 로 REAL 이 되지 않도록 10진 문자열로 저장
+        migrate_paid_to_text(&conn)?;
+
+        Ok(Self {
+            conn,
+            merge_policy: MergePolicy::default(),
+        })
+    }
+
+    pub fn with_merge_policy(mut self, merge_policy: MergePolicy) -> Self {
+        self.merge_policy = merge_policy;
+        self
+    }
+
+    // 고객 번호의 저장된 청구 data
+    pub fn load_claims(&self, customer_number: &str) -> Result<HashMap<NaiveDate, PpData>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT claim_date, usage, paid FROM monthly_claims WHERE customer_number = ?1",
+        )?;
+
+        let rows = stmt
+            .query_map(params![customer_number], |row| {
+                Ok(PpData {
+                    claim_date: row.get(0)?,
+                    usage: row.get(1)?,
+                    paid: decimal_from_sql(row.get_ref(2)?)?,
+                    payment_status: None,
+                    provisional: false,
+                    breakdown: None,
+                    provenance: None,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<PpData>>>()
+            .context("Failed to load claims from store")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|data| (data.claim_date, data))
+            .collect())
+    }
+
+    // 조회한 청구 data upsert
+    pub fn upsert_claims(&mut self, customer_number: &str, data: &[PpData]) -> Result<()> {
+        self.merge_claims(customer_number, data, ClaimSource::Scraped)
+            .map(|_| ())
+    }
+
+    // 청구 data 병합 (BATCH_SIZE 단위 multi-row insert), 저장한 row 수 반환
+    // 출처가 다른 저장된 row 와 값이 다르면 merge policy 에 따라 유지, 교체 또는 실패
+    // 진행 중인 달은 저장하지 않음 (저장된 달로 보고 다음 조회에서 확정값을 건너뛰지 않도록)
+    pub fn merge_claims(
+        &mut self,
+        customer_number: &str,
+        data: &[PpData],
+        source: ClaimSource,
+    ) -> Result<usize> {
+        let stored = self.load_sources(customer_number)?;
+        let mut conflicts = Vec::new();
+        let data: Vec<&PpData> = data
+            .iter()
+            .filter(|entry| !entry.provisional)
+            .filter(|entry| {
+                let Some((stored, stored_source)) = stored.get(&entry.claim_date) else {
+                    return true;
+                };
+                if stored.same_values(entry) {
+                    return false;
+                }
+                if *stored_source == source.as_str() {
+                    return true;
+                }
+                match self.merge_policy.winner() {
+                    Some(winner) => winner == source,
+                    None => {
+                        conflicts.push(format!(
+                            "{} ({} {} kWh {} / {} {} kWh {})",
+                            entry.claim_date.format("%Y-%m"),
+                            stored_source,
+                            stored.usage,
+                            stored.paid,
+                            source.as_str(),
+                            entry.usage,
+                            entry.paid
+                        ));
+                        false
+                    }
+                }
+            })
+            .collect();
+        if !conflicts.is_empty() {
+            return Err(Failure::new(
+                ErrorCode::Store,
+                format!(
+                    "Imported and scraped claims differ for {}: {}",
+                    customer_number,
+                    conflicts.join(", ")
+                ),
+            )
+            .into());
+        }
+
+        let source_name = source.as_str();
+        let tx = self.conn.transaction()?;
+        for chunk in data.chunks(BATCH_SIZE) {
+            let sql = format!(
+                r#"
+                INSERT INTO monthly_claims (customer_number, claim_date, usage, paid, source)
+                VALUES {}
+                ON CONFLICT (customer_number, claim_date)
+                DO UPDATE SET usage = excluded.usage, paid = excluded.paid, source = excluded.source
+                "#,
+                vec!["(?, ?, ?, ?, ?)"; chunk.len()].join(", ")
+            );
+
+            // 금액은 10진 문자열로 (paid 는 TEXT column)
+            let paid: Vec<String> = chunk.iter().map(|entry| entry.paid.to_string()).collect();
+            let values: Vec<&dyn ToSql> = chunk
+                .iter()
+                .zip(&paid)
+                .flat_map(|(entry, paid)| {
+                    [
+                        &customer_number as &dyn ToSql,
+                        &entry.claim_date,
+                        &entry.usage,
+                        paid,
+                        &source_name,
+                    ]
+                })
+                .collect();
+
+            tx.prepare_cached(&sql)?.execute(params_from_iter(values))?;
+        }
+        tx.commit().context("Failed to upsert claims into store")?;
+        Ok(data.len())
+    }
+
+    // 저장된 청구 data 와 출처
+    fn load_sources(&self, customer_number: &str) -> Result<HashMap<NaiveDate, (PpData, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT claim_date, usage, paid, source FROM monthly_claims WHERE customer_number = ?1",
+        )?;
+        let rows = stmt
+            .query_map(params![customer_number], |row| {
+                Ok((
+                    PpData {
+                        claim_date: row.get(0)?,
+                        usage: row.get(1)?,
+                        paid: decimal_from_sql(row.get_ref(2)?)?,
+                        payment_status: None,
+                        provisional: false,
+                        breakdown: None,
+                        provenance: None,
+                    },
+                    row.get::<_, String>(3)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .context("Failed to load claims from store")?;
+        Ok(rows
+            .into_iter()
+            .map(|(data, source)| (data.claim_date, (data, source)))
+            .collect())
+    }
+
+    // 청구 월 값 변경 기록 (reparse 등 저장된 값을 고친 이유와 함께)
+    pub fn log_changes(
+        &mut self,
+        customer_number: &str,
+        changes: &[ClaimChange],
+        reason: &str,
+    ) -> Result<()> {
+        let changed_at = Utc::now();
+        let tx = self.conn.transaction()?;
+        for change in changes {
+            tx.prepare_cached(
+                r#"
+                INSERT INTO claim_changes
+                    (changed_at, reason, customer_number, claim_date, old_usage, old_paid, new_usage, new_paid)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                "#,
+            )?
+            .execute(params![
+                changed_at,
+                reason,
+                customer_number,
+                change.after.claim_date,
+                change.before.as_ref().map(|before| before.usage),
+                change.before.as_ref().map(|before| before.paid.to_string()),
+                change.after.usage,
+                change.after.paid.to_string(),
+            ])?;
+        }
+        tx.commit().context("Failed to record claim changes")
+    }
+
+    // 청구 data 가 저장된 고객 번호
+    pub fn customers(&self) -> Result<Vec<String>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT DISTINCT customer_number FROM monthly_claims ORDER BY customer_number",
+        )?;
+        let customers = stmt
+            .query_map([], |row| row.get(0))?
+            .collect::<rusqlite::Result<Vec<String>>>()
+            .context("Failed to load customers from store")?;
+        Ok(customers)
+    }
+
+    // 저장된 가장 최근 청구월
+    pub fn latest_claim_date(&self) -> Result<Option<NaiveDate>> {
+        self.conn
+            .query_row("SELECT MAX(claim_date) FROM monthly_claims", [], |row| {
+                row.get(0)
+            })
+            .context("Failed to query the latest claim")
+    }
+
+    // 고객 번호별 저장된 가장 최근 청구월
+    pub fn latest_claim_dates(&self) -> Result<HashMap<String, NaiveDate>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT customer_number, MAX(claim_date) FROM monthly_claims GROUP BY customer_number",
+        )?;
+        let latest = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<HashMap<String, NaiveDate>>>()
+            .context("Failed to query the latest claims")?;
+        Ok(latest)
+    }
+
+    // 고객 번호별 마지막 조회
+    pub fn account_statuses(&self) -> Result<HashMap<String, AccountStatus>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT customer_number, last_attempt_at, last_status, last_error, last_success_at FROM account_status",
+        )?;
+        let statuses = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get(0)?,
+                    AccountStatus {
+                        last_attempt_at: row.get(1)?,
+                        last_status: row.get(2)?,
+                        last_error: row.get(3)?,
+                        last_success_at: row.get(4)?,
+                    },
+                ))
+            })?
+            .collect::<rusqlite::Result<HashMap<String, AccountStatus>>>()
+            .context("Failed to load account statuses from store")?;
+        Ok(statuses)
+    }
+
+    // cutoff 이전 청구월 삭제, 삭제한 row 수 반환
+    pub fn prune_claims(&self, cutoff: NaiveDate) -> Result<usize> {
+        self.conn
+            .execute(
+                "DELETE FROM monthly_claims WHERE claim_date < ?1",
+                params![cutoff],
+            )
+            .context("Failed to prune claims")
+    }
+
+    // 실행 시작 기록, row id 반환
+    pub fn start_run(&self, command: &str, run_id: &str) -> Result<i64> {
+        self.conn
+            .execute(
+                "INSERT INTO runs (started_at, version, command, accounts, run_id) VALUES (?1, ?2, ?3, '', ?4)",
+                params![Utc::now(), env!("CARGO_PKG_VERSION"), command, run_id],
+            )
+            .context("Failed to record run start")?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    // 실행 종료 기록, 고객 번호별 마지막 조회 갱신
+    pub fn finish_run(
+        &self,
+        id: i64,
+        stats: &RunStats,
+        status: Status,
+        error: Option<&str>,
+    ) -> Result<()> {
+        let finished_at = Utc::now();
+        for customer_number in &stats.accounts {
+            let (status, error) = stats
+                .statuses
+                .get(customer_number)
+                .map_or((status, error), |(status, error)| {
+                    (*status, error.as_deref())
+                });
+            self.conn
+                .execute(
+                    r#"
+                    INSERT INTO account_status (customer_number, last_attempt_at, last_status, last_error, last_success_at)
+                    VALUES (?1, ?2, ?3, ?4, CASE WHEN ?3 = 'ok' THEN ?2 END)
+                    ON CONFLICT (customer_number) DO UPDATE
+                    SET last_attempt_at = excluded.last_attempt_at,
+                        last_status = excluded.last_status,
+                        last_error = excluded.last_error,
+                        last_success_at = COALESCE(excluded.last_success_at, last_success_at)
+                    "#,
+                    params![customer_number, finished_at, status.as_str(), error],
+                )
+                .context("Failed to record account status")?;
+        }
+        self.conn
+            .execute(
+                r#"
+                UPDATE runs
+                SET finished_at = ?2, accounts = ?3, rows = ?4, status = ?5, error = ?6
+                WHERE id = ?1
+                "#,
+                params![
+                    id,
+                    finished_at,
+                    stats.accounts.join(","),
+                    stats.rows as i64,
+                    status.as_str(),
+                    error
+                ],
+            )
+            .context("Failed to record run end")?;
+        Ok(())
+    }
+
+    // 최근 실행 기록 (최신순)
+    pub fn list_runs(&self, limit: usize) -> Result<Vec<RunRecord>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT id, started_at, finished_at, version, command, accounts, rows, status, error, run_id
+            FROM runs ORDER BY id DESC LIMIT ?1
+            "#,
+        )?;
+
+        let runs = stmt
+            .query_map(params![limit as i64], |row| {
+                Ok(RunRecord {
+                    id: row.get(0)?,
+                    started_at: row.get(1)?,
+                    finished_at: row.get(2)?,
+                    version: row.get(3)?,
+                    command: row.get(4)?,
+                    accounts: row.get(5)?,
+                    rows: row.get(6)?,
+                    status: row.get(7)?,
+                    error: row.get(8)?,
+                    run_id: row.get(9)?,
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<RunRecord>>>()
+            .context("Failed to load runs from store")?;
+        Ok(runs)
+    }
+
+    // 임의 SQL 조회 (읽기 전용 statement 만), column 이름과 row
+    pub fn query(&self, sql: &str) -> Result<(Vec<String>, Vec<Vec<Value>>)> {
+        let mut stmt = self.conn.prepare(sql).context("Invalid SQL")?;
+        if !stmt.readonly() {
+            anyhow::bail!("Only read-only statements can be queried");
+        }
+        let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+
+        let mut rows = Vec::new();
+        let mut cursor = stmt.query([])?;
+        while let Some(row) = cursor.next()? {
+            let values = (0..columns.len())
+                .map(|i| {
+                    Ok(match row.get_ref(i)? {
+                        ValueRef::Null => Value::Null,
+                        ValueRef::Integer(value) => value.into(),
+                        ValueRef::Real(value) => value.into(),
+                        ValueRef::Text(text) => String::from_utf8_lossy(text).into(),
+                        ValueRef::Blob(blob) => format!("<{} bytes>", blob.len()).into(),
+                    })
+                })
+                .collect::<rusqlite::Result<Vec<Value>>>()?;
+            rows.push(values);
+        }
+        Ok((columns, rows))
+    }
+}
+
+// 이전 버전 저장소의 paid INTEGER column 을 TEXT 로 (SQLite 는 column type 변경이 없어 table 을 다시 만듦)
+fn migrate_paid_to_text(conn: &Connection) -> Result<()> {
+    let declared: String = conn
+        .query_row(
+            "SELECT type FROM pragma_table_info('monthly_claims') WHERE name = 'paid'",
+            [],
+            |row| row.get(0),
+        )
+        .context("Failed to inspect store tables")?;
+    if declared.eq_ignore_ascii_case("TEXT") {
+        return Ok(());
+    }
+    conn.execute_batch(
+        r#"
+        BEGIN;
+        CREATE TABLE monthly_claims_text (
+            customer_number TEXT NOT NULL,
+            claim_date TEXT NOT NULL,
+            usage REAL NOT NULL,
+            paid TEXT NOT NULL,
+            source TEXT NOT NULL DEFAULT 'scraped',
+            PRIMARY KEY (customer_number, claim_date)
+        );
+        INSERT INTO monthly_claims_text (customer_number, claim_date, usage, paid, source)
+            SELECT customer_number, claim_date, usage, CAST(paid AS TEXT), source FROM monthly_claims;
+        DROP TABLE monthly_claims;
+        ALTER TABLE monthly_claims_text RENAME TO monthly_claims;
+        COMMIT;
+        "#,
+    )
+    .context("Failed to migrate monthly_claims table")
+}
+
+// 이전 버전 저장소에 column 이 없으면 추가
+fn add_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    let exists: bool = conn
+        .query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info(?1) WHERE name = ?2",
+            params![table, column],
+            |row| row.get(0),
+        )
+        .context("Failed to inspect store tables")?;
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )
+        .context(format!("Failed to migrate {} table", table))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claim(month: u32, usage: f64, paid: &str) -> PpData {
+        PpData {
+            claim_date: NaiveDate::from_ymd_opt(2024, month, 1).unwrap(),
+            usage,
+            paid: paid.parse().unwrap(),
+            payment_status: None,
+            provisional: false,
+            breakdown: None,
+            provenance: None,
+        }
+    }
+
+    // 같은 달을 scraped 로 저장한 뒤 값이 다른 imported row 병합
+    fn merge_imported(policy: MergePolicy) -> (Store, Result<usize>) {
+        let mut store = Store::open(Path::new(":memory:"))
+            .unwrap()
+            .with_merge_policy(policy);
+        store
+            .merge_claims(
+                "0123456789",
+                &[claim(1, 310.0, "45230")],
+                ClaimSource::Scraped,
+            )
+            .unwrap();
+        let merged = store.merge_claims(
+            "0123456789",
+            &[claim(1, 300.0, "44000")],
+            ClaimSource::Imported,
+        );
+        (store, merged)
+    }
+
+    fn stored_paid(store: &Store) -> Decimal {
+        store.load_claims("0123456789").unwrap()[&NaiveDate::from_ymd_opt(2024, 1, 1).unwrap()].paid
+    }
+
+    #[test]
+    fn scraped_wins_keeps_scraped_claim() {
+        let (store, merged) = merge_imported(MergePolicy::ScrapedWins);
+        assert_eq!(merged.unwrap(), 0);
+        assert_eq!(stored_paid(&store), "45230".parse::<Decimal>().unwrap());
+    }
+
+    #[test]
+    fn imported_wins_replaces_scraped_claim() {
+        let (store, merged) = merge_imported(MergePolicy::ImportedWins);
+        assert_eq!(merged.unwrap(), 1);
+        assert_eq!(stored_paid(&store), "44000".parse::<Decimal>().unwrap());
+    }
+
+    #[test]
+    fn error_policy_rejects_conflict() {
+        let (store, merged) = merge_imported(MergePolicy::Error);
+        let error = merged.unwrap_err().to_string();
+        assert!(error.contains("2024-01"), "{}", error);
+        // 충돌이 있으면 아무것도 저장하지 않음
+        assert_eq!(stored_paid(&store), "45230".parse::<Decimal>().unwrap());
+    }
+
+    #[test]
+    fn migrates_integer_paid_to_exact_text() {
+        let path = std::env::temp_dir().join(format!("store-migrate-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        // paid INTEGER 인 이전 버전 저장소, 원 미만 금액은 REAL 로 저장되어 있음
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE monthly_claims (
+                customer_number TEXT NOT NULL,
+                claim_date TEXT NOT NULL,
+                usage REAL NOT NULL,
+                paid INTEGER NOT NULL,
+                PRIMARY KEY (customer_number, claim_date)
+            );
+            INSERT INTO monthly_claims VALUES ('0123456789', '2024-01-01', 310.0, 45230);
+            INSERT INTO monthly_claims VALUES ('0123456789', '2024-02-01', 295.0, '1234.5');",
+        )
+        .unwrap();
+        drop(conn);
+
+        let mut store = Store::open(&path).unwrap();
+        store
+            .upsert_claims("0123456789", &[claim(3, 280.0, "-1234.56")])
+            .unwrap();
+        let claims = store.load_claims("0123456789").unwrap();
+        let paid = |month| claims[&NaiveDate::from_ymd_opt(2024, month, 1).unwrap()].paid;
+        assert_eq!(paid(1), "45230".parse::<Decimal>().unwrap());
+        assert_eq!(paid(2), "1234.5".parse::<Decimal>().unwrap());
+        assert_eq!(paid(3), "-1234.56".parse::<Decimal>().unwrap());
+        let declared: String = store
+            .conn
+            .query_row(
+                "SELECT type FROM pragma_table_info('monthly_claims') WHERE name = 'paid'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(declared, "TEXT");
+        drop(store);
+        std::fs::remove_file(&path).unwrap();
+    }
+}
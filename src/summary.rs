@@ -0,0 +1,146 @@
+use anyhow::Result;
+use chrono::{Datelike, Local, NaiveDate, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+
+use crate::freshness;
+use crate::output::MoneyFormat;
+use crate::store::Store;
+use crate::PpData;
+
+// 기간 합계
+#[derive(Debug, Clone, Copy, Default)]
+struct Totals {
+    months: usize,
+    usage: f64,
+    paid: Decimal,
+}
+
+impl Totals {
+    fn of<'a>(rows: impl Iterator<Item = &'a PpData>) -> Self {
+        rows.fold(Self::default(), |totals, entry| Self {
+            months: totals.months + 1,
+            usage: totals.usage + entry.usage,
+            paid: totals.paid + entry.paid,
+        })
+    }
+
+    fn to_value(self, money_format: MoneyFormat) -> Result<Value> {
+        Ok(json!({
+            "months": self.months,
+            "usage": self.usage,
+            "paid": money_format.format(self.paid)?,
+        }))
+    }
+}
+
+// 전년 대비 증감률 (%), 전년 값이 없으면 null
+fn change(current: f64, previous: f64) -> Value {
+    if previous == 0.0 {
+        return Value::Null;
+    }
+    json!(((current - previous) / previous.abs() * 1000.0).round() / 10.0)
+}
+
+fn yoy(current: Totals, previous: Totals) -> Value {
+    json!({
+        "usage_pct": change(current.usage, previous.usage),
+        "paid_pct": change(
+            current.paid.to_f64().unwrap_or_default(),
+            previous.paid.to_f64().unwrap_or_default()
+        ),
+    })
+}
+
+// 고객 번호별 최근 청구 월, 올해 누적, 전년 동기 대비 (저장된 가장 최근 달 기준)
+fn meter_summary(
+    customer_number: &str,
+    rows: &[PpData],
+    money_format: MoneyFormat,
+) -> Result<Value> {
+    let Some(latest) = rows.iter().max_by_key(|entry| entry.claim_date) else {
+        return Ok(json!({ "customer_number": customer_number, "current_month": null }));
+    };
+    let month = latest.claim_date;
+    // 같은 해 1월부터 최근 달까지, 전년 같은 기간
+    let in_period = |year: i32| {
+        move |entry: &&PpData| {
+            entry.claim_date.year() == year && entry.claim_date.month() <= month.month()
+        }
+    };
+    let ytd = Totals::of(rows.iter().filter(in_period(month.year())));
+    let previous_ytd = Totals::of(rows.iter().filter(in_period(month.year() - 1)));
+    let previous_month = Totals::of(rows.iter().filter(|entry| {
+        NaiveDate::from_ymd_opt(month.year() - 1, month.month(), 1) == Some(entry.claim_date)
+    }));
+    let current_month = Totals::of(std::iter::once(latest));
+
+    Ok(json!({
+        "customer_number": customer_number,
+        "current_month": {
+            "month": month.format("%Y-%m").to_string(),
+            "usage": latest.usage,
+            "paid": money_format.format(latest.paid)?,
+            "yoy": yoy(current_month, previous_month),
+        },
+        "ytd": {
+            "year": month.year(),
+            "totals": ytd.to_value(money_format)?,
+            "previous_year": previous_ytd.to_value(money_format)?,
+            "yoy": yoy(ytd, previous_ytd),
+        },
+    }))
+}
+
+// 저장소 전체 고객 번호 요약 (대시보드용), 고객 번호마다 data 최신 여부 포함
+pub fn compute(
+    store: &Store,
+    money_format: MoneyFormat,
+    thresholds: freshness::Thresholds,
+) -> Result<Value> {
+    let freshness: HashMap<String, freshness::Freshness> =
+        freshness::compute(store, thresholds, Utc::now())?
+            .into_iter()
+            .map(|entry| (entry.customer_number.clone(), entry))
+            .collect();
+    let mut meters = Vec::new();
+    for customer_number in store.customers()? {
+        let rows: Vec<PpData> = store.load_claims(&customer_number)?.into_values().collect();
+        let mut meter = meter_summary(&customer_number, &rows, money_format)?;
+        meter["freshness"] = json!(freshness.get(&customer_number));
+        meters.push(meter);
+    }
+    Ok(json!({
+        "generated_at": Local::now().to_rfc3339(),
+        "meters": meters,
+    }))
+}
+
+// 고객 번호 1개 요약 (GET /customers/{customer}), 저장된 data 나 조회 기록이 없으면 None
+// 새로 조회하지 못했거나 (refresh_error) 기준을 넘은 data 는 stale, 마지막 성공 조회 후 지난 시간 포함
+pub fn customer(
+    store: &Store,
+    customer_number: &str,
+    money_format: MoneyFormat,
+    thresholds: freshness::Thresholds,
+    refresh_error: Option<&str>,
+) -> Result<Option<Value>> {
+    let now = Utc::now();
+    let Some(freshness) = freshness::compute(store, thresholds, now)?
+        .into_iter()
+        .find(|entry| entry.customer_number == customer_number)
+    else {
+        return Ok(None);
+    };
+    let rows: Vec<PpData> = store.load_claims(customer_number)?.into_values().collect();
+    let mut meter = meter_summary(customer_number, &rows, money_format)?;
+    meter["stale"] = json!(refresh_error.is_some() || freshness.health == freshness::Health::Red);
+    meter["age_secs"] = json!(freshness
+        .last_success_at
+        .map(|at| (now - at).num_seconds().max(0)));
+    meter["refresh_error"] = json!(refresh_error);
+    meter["freshness"] = json!(freshness);
+    Ok(Some(meter))
+}
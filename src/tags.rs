@@ -0,0 +1,63 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+// 고객 번호 대신 쓰면 모든 고객 번호에 붙는 tag
+const ALL_CUSTOMERS: &str = "*";
+
+// 고객 번호별 tag (tag 이름 -> 값)
+pub type Tags = BTreeMap<String, String>;
+
+// 출력 row 마다 붙일 tag (--tag 0123456789:building=HQ, --tag *:site=seoul)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tag {
+    // 정규화한 고객 번호 또는 *
+    pub customer: String,
+    pub key: String,
+    pub value: String,
+}
+
+impl FromStr for Tag {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || {
+            format!(
+                "Invalid tag, expected <customer number|*>:<key>=<value>: {}",
+                s
+            )
+        };
+        let (customer, pair) = s.split_once(':').ok_or_else(invalid)?;
+        let (key, value) = pair.split_once('=').ok_or_else(invalid)?;
+        let (customer, key) = (customer.trim(), key.trim());
+
+        let customer = match customer {
+            ALL_CUSTOMERS => customer.to_string(),
+            number => crate::customer::validate(number).map_err(|e| e.to_string())?,
+        };
+        // DB column, JSON field 이름으로 쓸 수 있는 문자만
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(format!(
+                "Invalid tag key '{}': only letters, digits and underscores are allowed",
+                key
+            ));
+        }
+        Ok(Self {
+            customer,
+            key: key.to_string(),
+            value: value.trim().to_string(),
+        })
+    }
+}
+
+// 고객 번호에 붙는 tag (* tag 위에 고객 번호 tag, 같은 key 는 나중 값)
+pub fn for_customer(tags: &[Tag], customer_number: &str) -> Tags {
+    let number = crate::customer::normalize(customer_number);
+    let mut matched = Tags::new();
+    for tag in tags.iter().filter(|tag| tag.customer == ALL_CUSTOMERS) {
+        matched.insert(tag.key.clone(), tag.value.clone());
+    }
+    for tag in tags.iter().filter(|tag| tag.customer == number) {
+        matched.insert(tag.key.clone(), tag.value.clone());
+    }
+    matched
+}
@@ -0,0 +1,104 @@
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tera::Tera;
+
+use crate::error::{ErrorCode, Failure};
+use crate::hooks::RunSummary;
+use crate::output::{AccountResult, Locale};
+
+const BODY: &str = "body";
+const SUBJECT: &str = "subject";
+
+// 알림 문구 template (--notify-template, --notify-subject, Tera 문법)
+#[derive(Debug, Clone)]
+pub struct NotifyTemplate {
+    tera: Tera,
+    has_subject: bool,
+}
+
+impl NotifyTemplate {
+    // template 문법 오류는 조회 전에 설정 오류로
+    pub fn load(body: &Path, subject: Option<&str>) -> Result<Self> {
+        let source = fs::read_to_string(body)
+            .context(format!("Failed to read notify template {}", body.display()))?;
+        let mut tera = Tera::default();
+        tera.add_raw_template(BODY, &source).context(Failure::new(
+            ErrorCode::Config,
+            format!("Invalid notify template {}", body.display()),
+        ))?;
+        if let Some(subject) = subject {
+            tera.add_raw_template(SUBJECT, subject)
+                .context(Failure::new(ErrorCode::Config, "Invalid --notify-subject"))?;
+        }
+        Ok(Self {
+            tera,
+            has_subject: subject.is_some(),
+        })
+    }
+
+    pub fn body(&self, context: &Value) -> Result<String> {
+        self.render(BODY, context)
+    }
+
+    // --notify-subject 가 없으면 None (기본 제목)
+    pub fn subject(&self, context: &Value) -> Result<Option<String>> {
+        if !self.has_subject {
+            return Ok(None);
+        }
+        Ok(Some(self.render(SUBJECT, context)?.trim().to_string()))
+    }
+
+    fn render(&self, name: &str, context: &Value) -> Result<String> {
+        let context =
+            tera::Context::from_value(context.clone()).context("Invalid template context")?;
+        self.tera
+            .render(name, &context)
+            .context(format!("Failed to render the notify {}", name))
+    }
+}
+
+// template 에서 쓰는 값: run (실행 요약), customers (고객 번호별 상태, 최근 row), rows (전체 row 수)
+pub fn context(
+    results: &BTreeMap<String, AccountResult>,
+    locale: Locale,
+    run: Option<&RunSummary>,
+) -> Value {
+    let customers: Vec<Value> = results
+        .iter()
+        .map(|(customer_number, result)| {
+            let mut data: Vec<_> = result.data.iter().collect();
+            data.sort_by_key(|entry| std::cmp::Reverse(entry.claim_date));
+            let rows: Vec<Value> = data
+                .iter()
+                .map(|entry| {
+                    json!({
+                        "month": entry.claim_date.format("%Y-%m").to_string(),
+                        "claim_date": entry.claim_date,
+                        "usage": entry.usage,
+                        "paid": entry.paid.to_string(),
+                        // --locale 형식 (1,234.5, ₩12,345)
+                        "usage_text": locale.usage(entry.usage),
+                        "paid_text": locale.money(entry.paid),
+                    })
+                })
+                .collect();
+            json!({
+                "customer_number": customer_number,
+                "status": result.status,
+                "error": result.error,
+                "info": result.info,
+                "latest": rows.first(),
+                "rows": rows,
+            })
+        })
+        .collect();
+
+    json!({
+        "run": run,
+        "rows": results.values().map(|result| result.data.len()).sum::<usize>(),
+        "customers": customers,
+    })
+}
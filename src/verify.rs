@@ -0,0 +1,36 @@
+use anyhow::{Context, Result};
+use std::fmt::Write as _;
+
+use crate::output::{self, OutputFormat};
+pub use seppuku_hyphen::bill::{BillDetail, Verification};
+
+// 월별 검증 결과 출력
+pub fn render(verifications: &[Verification], format: OutputFormat) -> Result<String> {
+    match format {
+        OutputFormat::Json => serde_json::to_string_pretty(verifications)
+            .context("Failed to serialize verifications to JSON"),
+        OutputFormat::Csv => Err(output::csv_unsupported("verify")),
+        OutputFormat::Table => {
+            let mut table = String::new();
+            for verification in verifications {
+                let _ = write!(
+                    table,
+                    "{}  {}  {}",
+                    verification.customer_number, verification.month, verification.status
+                );
+                for difference in &verification.differences {
+                    let _ = write!(
+                        table,
+                        "  {}({}): {} != {}",
+                        difference["field"].as_str().unwrap_or_default(),
+                        difference["source"].as_str().unwrap_or_default(),
+                        difference["scraped"],
+                        difference["expected"]
+                    );
+                }
+                table.push('\n');
+            }
+            Ok(table)
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+tokio::task_local! {
+    // 현재 실행의 경고 수집기
+    static CURRENT: Warnings;
+}
+
+// 실행을 멈추지 않는 문제의 종류
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WarningKind {
+    // 값을 읽지 못해 건너뛴 row
+    SkippedRow,
+    // layout 판별 실패로 기본 selector 사용
+    FallbackSelector,
+    // 실패 후 재시도
+    Retry,
+    // 실패했지만 나머지 고객 번호는 계속 조회
+    SkippedCustomer,
+    // 고객 정보를 읽지 못함 (청구 요금은 계속 조회)
+    CustomerInfo,
+    // 청구서 상세의 요금 항목을 읽지 못함 (--detailed, 해당 월은 항목 없이 출력)
+    BillDetail,
+    // 요소를 찾지 못하거나 값을 입력하지 못함 (멈추는 단계면 에러로도 반환)
+    MissingElement,
+    // 원본 page, CAPTCHA 캡처 등 보관 파일을 쓰지 못함 (조회는 계속)
+    Artifact,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub message: String,
+    pub at: DateTime<Utc>,
+}
+
+type Callback = Arc<dyn Fn(&Warning) + Send + Sync>;
+
+// 실행 중 경고 수집 (복제해도 같은 목록), callback 이 있으면 발생 즉시 호출
+#[derive(Clone, Default)]
+pub struct Warnings {
+    collected: Arc<Mutex<Vec<Warning>>>,
+    callback: Option<Callback>,
+}
+
+impl Warnings {
+    pub fn with_callback(callback: impl Fn(&Warning) + Send + Sync + 'static) -> Self {
+        Self {
+            collected: Arc::default(),
+            callback: Some(Arc::new(callback)),
+        }
+    }
+
+    // future 실행 동안 emit 된 경고를 이 수집기에 모음
+    pub async fn scope<F: Future>(&self, future: F) -> F::Output {
+        CURRENT.scope(self.clone(), future).await
+    }
+
+    pub fn count(&self) -> usize {
+        self.lock().len()
+    }
+
+    // 모인 경고를 꺼냄 (목록은 비워짐)
+    pub fn take(&self) -> Vec<Warning> {
+        std::mem::take(&mut *self.lock())
+    }
+
+    fn push(&self, warning: Warning) {
+        if let Some(callback) = &self.callback {
+            callback(&warning);
+        }
+        self.lock().push(warning);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<Warning>> {
+        self.collected
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// 현재 실행의 수집기에 경고 추가 (scope 밖이면 stderr 출력)
+pub fn emit(kind: WarningKind, message: impl Into<String>) {
+    let warning = Warning {
+        kind,
+        message: message.into(),
+        at: Utc::now(),
+    };
+    if CURRENT
+        .try_with(|warnings| warnings.push(warning.clone()))
+        .is_err()
+    {
+        eprintln!("Warning: {}", warning.message);
+    }
+}
+
+// 현재 실행에서 발생한 경고 수
+pub fn count() -> usize {
+    CURRENT.try_with(Warnings::count).unwrap_or(0)
+}
@@ -0,0 +1,54 @@
+use chrono::{Datelike, Days, Months, NaiveDate};
+use std::time::Duration;
+
+// watch 주기 설정 (검침일 기준 예상 청구 시점)
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    // 검침일 (1~31, 짧은 달은 말일)
+    pub reading_day: u32,
+    // 검침일 이후 청구 요금이 조회되기까지 걸리는 일수
+    pub arrival_lag_days: u32,
+    // 예상 시점 며칠 전부터 자주 조회
+    pub window_days: u32,
+    // 예상 시점 전후 조회 간격
+    pub poll: Duration,
+    // 평소 조회 간격
+    pub interval: Duration,
+}
+
+impl Schedule {
+    // 마지막 청구 월 다음 달의 예상 청구 조회 가능일
+    pub fn expected_arrival(&self, latest: NaiveDate) -> Option<NaiveDate> {
+        let next = latest.with_day(1)?.checked_add_months(Months::new(1))?;
+        day_of_month(next, self.reading_day)?
+            .checked_add_days(Days::new(self.arrival_lag_days.into()))
+    }
+
+    // 다음 조회까지 대기 시간과 사유
+    pub fn next_poll(&self, latest: Option<NaiveDate>, today: NaiveDate) -> (Duration, String) {
+        let Some(expected) = latest.and_then(|latest| self.expected_arrival(latest)) else {
+            return (self.interval, "no stored bill yet".to_string());
+        };
+        let window_start = expected
+            .checked_sub_days(Days::new(self.window_days.into()))
+            .unwrap_or(expected);
+
+        if today >= window_start {
+            return (
+                self.poll,
+                format!("waiting for the bill expected on {}", expected),
+            );
+        }
+        // 평소 간격으로 기다리되 조회 구간 시작은 넘기지 않음
+        let until_window = Duration::from_secs((window_start - today).num_days() as u64 * 86_400);
+        (
+            self.interval.min(until_window),
+            format!("next bill expected on {}", expected),
+        )
+    }
+}
+
+// 해당 월의 day 일 (짧은 달은 말일)
+pub fn day_of_month(month: NaiveDate, day: u32) -> Option<NaiveDate> {
+    (1..=day).rev().find_map(|day| month.with_day(day))
+}
@@ -0,0 +1,73 @@
+use fantoccini::Client;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::time::{sleep_until, timeout, Duration, Instant};
+
+// 진단 정보 수집 시 driver 응답 대기 한도
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// 단계 진행 감시 (window 동안 완료된 단계가 없으면 hung 으로 판단)
+#[derive(Debug, Clone)]
+pub struct Watchdog {
+    window: Duration,
+    last: Arc<Mutex<(Instant, &'static str)>>,
+}
+
+impl Watchdog {
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            last: Arc::new(Mutex::new((Instant::now(), "start"))),
+        }
+    }
+
+    pub fn window(&self) -> Duration {
+        self.window
+    }
+
+    // 단계 완료 기록
+    pub fn tick(&self, step: &'static str) {
+        *self.last.lock().unwrap() = (Instant::now(), step);
+    }
+
+    // 진행이 멈추면 마지막으로 완료된 단계 반환
+    pub async fn stalled(&self) -> &'static str {
+        loop {
+            let (at, step) = *self.last.lock().unwrap();
+            let deadline = at + self.window;
+            if Instant::now() >= deadline {
+                return step;
+            }
+            sleep_until(deadline).await;
+        }
+    }
+}
+
+// hung 상태 진단 정보 저장 (screenshot, 현재 URL), 저장된 파일 목록 반환
+pub async fn capture(client: &Client, dir: &Path, step: &str) -> Vec<PathBuf> {
+    let name = chrono::Local::now().format("%Y%m%d-%H%M%S-hung");
+    let mut artifacts = Vec::new();
+
+    let url = match timeout(CAPTURE_TIMEOUT, client.current_url()).await {
+        Ok(Ok(url)) => url.to_string(),
+        Ok(Err(e)) => format!("unavailable ({})", e),
+        Err(_) => "unavailable (driver not responding)".to_string(),
+    };
+    let report = dir.join(format!("{}.txt", name));
+    let text = format!("last completed step: {}\ncurrent url: {}\n", step, url);
+    match fs::write(&report, text) {
+        Ok(()) => artifacts.push(report),
+        Err(e) => eprintln!("Failed to write {}: {}", report.display(), e),
+    }
+
+    if let Ok(Ok(png)) = timeout(CAPTURE_TIMEOUT, client.screenshot()).await {
+        let path = dir.join(format!("{}.png", name));
+        match fs::write(&path, png) {
+            Ok(()) => artifacts.push(path),
+            Err(e) => eprintln!("Failed to write {}: {}", path.display(), e),
+        }
+    }
+
+    artifacts
+}